@@ -369,15 +369,15 @@ pub fn route(attrs: TokenStream, item: TokenStream) -> TokenStream {
                 result.into()
             }
 
-            fn pages_internal(&self, ctx: &mut maudit::route::DynamicRouteContext) -> Vec<(maudit::route::PageParams, Box<dyn std::any::Any + Send + Sync>, Box<dyn std::any::Any + Send + Sync>, Option<(String, String)>)> {
+            fn pages_internal(&self, ctx: &mut maudit::route::DynamicRouteContext) -> Vec<(maudit::route::PageParams, Box<dyn std::any::Any + Send + Sync>, Box<dyn std::any::Any + Send + Sync>, Vec<(String, String)>)> {
                 self.pages(ctx)
                     .into_iter()
                     .map(|route| {
                         let raw_params: maudit::route::PageParams = (&route.params).into();
-                        let source_entry = route._source_entry;
+                        let source_entries = route._source_entries;
                         let typed_params: Box<dyn std::any::Any + Send + Sync> = Box::new(route.params);
                         let props: Box<dyn std::any::Any + Send + Sync> = Box::new(route.props);
-                        (raw_params, typed_params, props, source_entry)
+                        (raw_params, typed_params, props, source_entries)
                     })
                     .collect()
             }
@@ -458,7 +458,7 @@ pub fn markdown_entry(args: TokenStream, item: TokenStream) -> TokenStream {
 
     let struct_name = &item_struct.ident;
 
-    // Add __internal_headings field
+    // Add __internal_headings and __internal_summary fields
     if let syn::Fields::Named(ref mut fields) = item_struct.fields {
         fields.named.push(
             syn::Field::parse_named
@@ -468,6 +468,14 @@ pub fn markdown_entry(args: TokenStream, item: TokenStream) -> TokenStream {
                 })
                 .unwrap(),
         );
+        fields.named.push(
+            syn::Field::parse_named
+                .parse2(quote! {
+                    #[serde(skip)]
+                    __internal_summary: maudit::content::MarkdownSummary
+                })
+                .unwrap(),
+        );
     }
 
     let expanded = quote! {
@@ -478,12 +486,20 @@ pub fn markdown_entry(args: TokenStream, item: TokenStream) -> TokenStream {
             fn get_headings(&self) -> &Vec<maudit::content::MarkdownHeading> {
                 &self.__internal_headings
             }
+
+            fn get_summary(&self) -> &maudit::content::MarkdownSummary {
+                &self.__internal_summary
+            }
         }
 
         impl maudit::content::InternalMarkdownContent for #struct_name {
             fn set_headings(&mut self, headings: Vec<maudit::content::MarkdownHeading>) {
                 self.__internal_headings = headings;
             }
+
+            fn set_summary(&mut self, summary: maudit::content::MarkdownSummary) {
+                self.__internal_summary = summary;
+            }
         }
     };
 
@@ -1,10 +1,9 @@
 use log::debug;
 use rapidhash::fast::RapidHasher;
 use rustc_hash::{FxHashMap, FxHashSet};
-use std::cell::RefCell;
 use std::hash::{Hash, Hasher};
 use std::path::Path;
-use std::rc::Rc;
+use std::sync::{Arc, Mutex};
 use std::time::Instant;
 use std::{fs, path::PathBuf};
 
@@ -72,7 +71,8 @@ impl AssetHashKey {
 
 /// Shared cache for asset hash computations, avoiding redundant file reads
 /// when multiple pages add the same asset (e.g. a shared layout CSS file).
-pub type AssetHashCache = Rc<RefCell<FxHashMap<AssetHashKey, String>>>;
+/// Shared across the rendering threads, as pages are built in parallel.
+pub type AssetHashCache = Arc<Mutex<FxHashMap<AssetHashKey, String>>>;
 
 #[derive(Default)]
 pub struct RouteAssets {
@@ -93,6 +93,8 @@ pub struct RouteAssetsOptions {
     /// Must match what `url_to_disk_path` produces for the placeholder URL.
     pub(crate) output_dir: PathBuf,
     pub(crate) intermediate_url_format: IntermediateUrlFormat,
+    /// Where files generated while rendering, such as highlighting stylesheets, are written before being bundled.
+    pub(crate) cache_dir: PathBuf,
 }
 
 /// URL format for bundled assets pre-substitution. Coronate sets `Placeholder`;
@@ -119,6 +121,7 @@ impl Default for RouteAssetsOptions {
             hashing_strategy: page_assets_options.hashing_strategy,
             output_dir: default_build_options.output_dir,
             intermediate_url_format: IntermediateUrlFormat::default(),
+            cache_dir: default_build_options.cache_dir,
         }
     }
 }
@@ -164,7 +167,7 @@ impl RouteAssets {
         let key = AssetHashKey::new(path, config);
 
         if let Some(cache) = &self.hash_cache
-            && let Some(hash) = cache.borrow().get(&key)
+            && let Some(hash) = cache.lock().unwrap().get(&key)
         {
             return Ok(hash.clone());
         }
@@ -172,7 +175,7 @@ impl RouteAssets {
         let hash = calculate_hash(path, config)?;
 
         if let Some(cache) = &self.hash_cache {
-            cache.borrow_mut().insert(key, hash.clone());
+            cache.lock().unwrap().insert(key, hash.clone());
         }
 
         Ok(hash)
@@ -1,12 +1,10 @@
 use std::{
-    cell::RefCell,
     env,
     fs::{self},
     io::{self},
     path::{Path, PathBuf},
-    rc::Rc,
-    sync::Arc,
-    time::{Instant, SystemTime, UNIX_EPOCH},
+    sync::{Arc, Mutex},
+    time::{Duration, Instant, SystemTime, UNIX_EPOCH},
 };
 
 use crate::assets::css::bundle_css;
@@ -18,10 +16,14 @@ use crate::{
         calculate_hash, image_cache::ImageCache, make_final_url, prefetch,
     },
     build::{images::process_image, options::PrefetchStrategy},
-    content::ContentSources,
+    content::{ContentSources, links::EntryUrls, tracked::ContentAccessLog},
+    errors::{BuildError, ContentError, MauditError},
     is_dev,
     logging::print_title,
-    route::{CachedRoute, DynamicRouteContext, FullRoute, InternalRoute, PageContext, PageParams},
+    route::{
+        CachedRoute, DynamicRouteContext, FullRoute, InternalRoute, PageContext, PageParams,
+        PagesResult,
+    },
     routing::extract_params_from_raw_route,
     sitemap::{SitemapEntry, generate_sitemap},
 };
@@ -91,7 +93,7 @@ fn try_cache_hit(
 fn record_page_cache_entry(
     new_cache: &mut Option<cache::BuildCache>,
     page_key: cache::PageKey,
-    access_log: crate::content::tracked::ContentAccessLog,
+    access_log: ContentAccessLog,
     route_assets: &RouteAssets,
     output_file: PathBuf,
 ) {
@@ -103,6 +105,8 @@ fn record_page_cache_entry(
         cache::PageCacheEntry {
             content_entries_read: access_log.entries_read,
             content_sources_iterated: access_log.sources_iterated,
+            content_sources_queried: access_log.sources_queried,
+            content_query_results: access_log.query_results,
             scripts: route_assets
                 .scripts
                 .iter()
@@ -166,6 +170,180 @@ fn restore_assets_from_cache(
     }
 }
 
+/// A step of the page generation output, replayed in route order once all pages
+/// have been rendered.
+enum PageStep<'a> {
+    Info(String),
+    Warn(String),
+    Page(PlannedPage<'a>),
+}
+
+/// A page enumerated from a route, either served from the incremental cache or
+/// waiting on its render job.
+struct PlannedPage<'a> {
+    route: &'a dyn FullRoute,
+    /// Log line prefix, e.g. `/about -> dist/about/index.html` or `├─ dist/posts/a/index.html`.
+    label: String,
+    route_path: String,
+    params: Option<FxHashMap<String, Option<String>>>,
+    url: String,
+    file_path: PathBuf,
+    page_key: Option<cache::PageKey>,
+    cached: bool,
+}
+
+/// Everything needed to render a single page on a worker thread.
+struct PageJob<'a> {
+    route: &'a dyn FullRoute,
+    /// The page returned by `get_pages()`, or None for static routes.
+    page: Option<PagesResult>,
+    variant: Option<String>,
+    url: String,
+    file_path: PathBuf,
+    /// Content accessed while enumerating the pages of a dynamic route.
+    get_pages_access_log: Option<Arc<ContentAccessLog>>,
+}
+
+struct RenderedPage {
+    route_assets: RouteAssets,
+    access_log: ContentAccessLog,
+    has_assets: bool,
+    elapsed: Duration,
+}
+
+/// State shared by all the rendering threads.
+struct PageRenderer<'a> {
+    content_sources: &'a ContentSources,
+    base_url: &'a Option<String>,
+    route_assets_options: &'a assets::RouteAssetsOptions,
+    image_cache: &'a ImageCache,
+    asset_hash_cache: &'a assets::AssetHashCache,
+    default_scripts: &'a [Script],
+    entry_urls: &'a EntryUrls,
+    markdown_cache: Option<&'a cache::MarkdownRenderCache>,
+    strict_links: bool,
+    created_dirs: Mutex<FxHashSet<PathBuf>>,
+    assets_prefix: &'a [u8],
+}
+
+impl PageRenderer<'_> {
+    /// Render a page and write it to disk. Each page gets its own [`RouteAssets`] and
+    /// access log, which are merged into the build by the caller.
+    fn render(&self, job: PageJob) -> Result<RenderedPage, MauditError> {
+        let page_start = Instant::now();
+        let mut route_assets = RouteAssets::with_default_assets(
+            self.route_assets_options,
+            Some(self.image_cache.clone()),
+            Some(self.asset_hash_cache.clone()),
+            self.default_scripts.to_vec(),
+            vec![],
+        );
+
+        let mut page_ctx = match &job.page {
+            Some(page) => PageContext::from_dynamic_route(
+                page,
+                self.content_sources,
+                &mut route_assets,
+                &job.url,
+                self.base_url,
+                job.variant.clone(),
+            ),
+            None => PageContext::from_static_route(
+                self.content_sources,
+                &mut route_assets,
+                &job.url,
+                self.base_url,
+                job.variant.clone(),
+            ),
+        };
+        page_ctx.entry_urls = Some(self.entry_urls);
+        page_ctx.markdown_cache = self.markdown_cache;
+        let content =
+            job.route
+                .build(&mut page_ctx)
+                .map_err(|err| BuildError::PageRenderFailed {
+                    url: job.url.clone(),
+                    message: err.to_string(),
+                })?;
+        let mut access_log = page_ctx.take_access_log();
+
+        let shortcode_errors = std::mem::take(&mut page_ctx.shortcode_errors);
+        if !shortcode_errors.is_empty() {
+            return Err(BuildError::ShortcodesFailed {
+                url: job.url,
+                errors: shortcode_errors,
+            }
+            .into());
+        }
+
+        let highlight_errors = std::mem::take(&mut page_ctx.highlight_errors);
+        if !highlight_errors.is_empty() {
+            return Err(BuildError::HighlightFailed {
+                url: job.url,
+                errors: highlight_errors,
+            }
+            .into());
+        }
+
+        let broken_links = std::mem::take(&mut page_ctx.broken_links);
+        if self.strict_links && !broken_links.is_empty() {
+            return Err(BuildError::BrokenLinks {
+                url: job.url,
+                links: broken_links.iter().map(ToString::to_string).collect(),
+            }
+            .into());
+        }
+        for link in broken_links {
+            warn!(target: "build", "{}: link to {}, which isn't the source of any page", job.url, link);
+        }
+
+        if let (Some(page), Some(get_pages_access_log)) = (&job.page, &job.get_pages_access_log) {
+            // Merge content dependencies from get_pages() into each page's log,
+            // so that content read during page enumeration is tracked per-page.
+            access_log.merge_entries_read(get_pages_access_log);
+            // If into_pages() (or a taxonomy) produced this page from specific
+            // entries, record precise per-entry dependencies. Otherwise, if
+            // render() didn't track any content dependencies itself,
+            // fall back to source-level tracking (all pages dirty when
+            // any entry changes) to avoid serving stale content.
+            if !page.3.is_empty() {
+                access_log.entries_read.extend(page.3.iter().cloned());
+            } else if access_log.entries_read.is_empty()
+                && access_log.sources_iterated.is_empty()
+                && access_log.sources_queried.is_empty()
+            {
+                access_log
+                    .sources_iterated
+                    .extend(get_pages_access_log.sources_iterated.iter().cloned());
+                access_log
+                    .sources_queried
+                    .extend(get_pages_access_log.sources_queried.iter().cloned());
+            }
+        }
+        // Only the queries of `pages()` are run again before deciding whether to render a page,
+        // the ones run by render() depend on the data of their sources instead.
+        access_log.query_results = job
+            .get_pages_access_log
+            .as_ref()
+            .map(|log| log.query_results.clone())
+            .unwrap_or_default();
+
+        let has_assets = write_route_file(
+            &content,
+            &job.file_path,
+            &self.created_dirs,
+            self.assets_prefix,
+        )?;
+
+        Ok(RenderedPage {
+            route_assets,
+            access_log,
+            has_assets,
+            elapsed: page_start.elapsed(),
+        })
+    }
+}
+
 pub async fn build(
     routes: &[&dyn FullRoute],
     content_sources: &mut ContentSources,
@@ -230,12 +408,23 @@ pub async fn build(
 
     let content_sources_start = Instant::now();
     print_title("initializing content sources");
-    content_sources.sources_mut().iter_mut().for_each(|source| {
-        let source_start = Instant::now();
-        source.init();
-
-        info!(target: "content", "{} initialized in {}", source.get_name(), format_elapsed_time(source_start.elapsed(), &FormatElapsedTimeOptions::default()));
-    });
+    // Sources are initialized concurrently, slow loaders (network, large globs, etc.) don't hold up the others
+    let content_errors: Vec<ContentError> = futures::future::join_all(
+        content_sources.sources_mut().iter_mut().map(|source| async move {
+            let source_start = Instant::now();
+            source.init(options.include_drafts).await?;
+
+            info!(target: "content", "{} initialized in {}", source.get_name(), format_elapsed_time(source_start.elapsed(), &FormatElapsedTimeOptions::default()));
+            Ok(())
+        }),
+    )
+    .await
+    .into_iter()
+    .filter_map(Result::err)
+    .collect();
+    // Every invalid entry of every source is reported at once, so they can all be fixed in one go
+    ContentError::aggregate(content_errors)?;
+    content_sources.check_references()?;
 
     info!(target: "content", "{}", format!("Content sources initialized in {}", format_elapsed_time(
         content_sources_start.elapsed(),
@@ -248,21 +437,30 @@ pub async fn build(
     if options.incremental {
         let incremental_start = Instant::now();
         let current_binary_hash = cache::BuildCache::compute_binary_hash();
+        let current_options_hash = options.options_hash();
+        // The data hashes of unchanged entries can be reused, unless how they're computed may have changed
+        let previous_content_states = previous_build_cache
+            .as_ref()
+            .filter(|c| {
+                c.binary_hash == current_binary_hash && c.options_hash == current_options_hash
+            })
+            .map(|c| &c.content_sources);
         let current_content_states: FxHashMap<String, cache::ContentSourceState> = content_sources
             .sources()
             .iter()
             .map(|s| {
-                let entries = s.entry_file_info();
+                let entries = s.entry_dependencies();
                 let raw_content = s.entry_raw_content();
-                (
-                    s.get_name().to_string(),
-                    cache::compute_content_source_state(&entries, &raw_content),
-                )
+                let mut state = cache::compute_content_source_state(&entries, &raw_content);
+                state.data_hashes = cache::compute_entry_data_hashes(
+                    &state,
+                    previous_content_states.and_then(|states| states.get(s.get_name())),
+                    |id| s.entry_data_fingerprint(id),
+                );
+                (s.get_name().to_string(), state)
             })
             .collect();
 
-        let current_options_hash = options.options_hash();
-
         incremental_state = cache::load_incremental_state(
             previous_build_cache,
             &current_content_states,
@@ -328,7 +526,8 @@ pub async fn build(
     let mut sitemap_entries: Vec<SitemapEntry> = Vec::new();
     let mut rendered_count: usize = 0;
     let mut cached_count: usize = 0;
-    let mut created_dirs: FxHashSet<PathBuf> = FxHashSet::default();
+    // Pages served from the cache, which keep their Markdown renders for when they're rendered again
+    let mut cached_urls: FxHashSet<String> = FxHashSet::default();
     // Pages flagged at write time as containing an asset-URL prefix. The post-bundle
     // pass only re-reads these, not every page.
     let mut pages_with_assets: Vec<PathBuf> = Vec::new();
@@ -354,9 +553,19 @@ pub async fn build(
         if !map.is_empty() {
             debug!(target: "cache", "Seeded asset hash cache with {} entries from previous build", map.len());
         }
-        Rc::new(RefCell::new(map))
+        Arc::new(Mutex::new(map))
     };
 
+    // Seed the Markdown render cache from the previous build cache, which is only kept when the binary didn't change.
+    let markdown_cache = new_cache.as_ref().map(|cache| {
+        let entries = incremental_state
+            .previous_cache
+            .as_ref()
+            .map(|prev| prev.rendered_markdown.clone())
+            .unwrap_or_default();
+        cache::MarkdownRenderCache::new(&cache.binary_hash, entries)
+    });
+
     // Normalize base_url once to avoid repeated trimming
     let normalized_base_url = options
         .base_url
@@ -388,21 +597,17 @@ pub async fn build(
         default_scripts.push(prefetch_script);
     }
 
-    // For non-incremental builds, share a single RouteAssets across all routes
-    // to avoid per-route allocation overhead (cloning image_cache, default_scripts, etc.)
-    let mut shared_route_assets = if new_cache.is_none() {
-        Some(RouteAssets::with_default_assets(
-            &route_assets_options,
-            Some(image_cache.clone()),
-            Some(asset_hash_cache.clone()),
-            default_scripts.clone(),
-            vec![],
-        ))
-    } else {
-        None
-    };
+    // Pages are generated in three passes. Routes are first walked in order to
+    // enumerate their pages and resolve cache hits, every page that needs rendering
+    // is then built in parallel, and the results are finally merged back in route
+    // order, so that logs, build metadata and the build cache don't depend on
+    // which thread finished first.
+    let mut steps: Vec<PageStep> = Vec::new();
+    let mut jobs: Vec<PageJob> = Vec::new();
+    // Links between entries point to the pages of routes whose pages are each generated from a single entry, like with `into_pages`.
+    // Routes listing entries (pagination, taxonomies) usually have pages containing several entries, and are left out.
+    let mut entry_urls = EntryUrls::default();
 
-    // Serial page rendering loop.
     for route in routes {
         let cached_route = CachedRoute::new(*route);
         let base_path = route.route_raw();
@@ -413,7 +618,7 @@ pub async fn build(
         let has_base_route = base_path.is_some();
 
         if !has_base_route && !variants.is_empty() {
-            info!(target: "pages", "(variants only)");
+            steps.push(PageStep::Info("(variants only)".to_string()));
         }
 
         // Handle base route
@@ -422,13 +627,11 @@ pub async fn build(
 
             // Static base route
             if base_params.is_empty() {
-                let params = PageParams::default();
-                let (url, file_path) = cached_route.url_and_file_path(&params, &options.output_dir);
-                let page_key = if new_cache.is_some() {
-                    Some(cache::PageKey::new_static(base_path, None))
-                } else {
-                    None
-                };
+                let (url, file_path) =
+                    cached_route.url_and_file_path(&PageParams::default(), &options.output_dir);
+                let page_key = new_cache
+                    .is_some()
+                    .then(|| cache::PageKey::new_static(base_path, None));
 
                 let cache_hit = page_key.as_ref().is_some_and(|pk| {
                     try_cache_hit(
@@ -442,82 +645,27 @@ pub async fn build(
                     )
                 });
 
-                if cache_hit {
-                    info!(target: "pages", "{} -> {} (cached)", url, file_path.to_string_lossy().dimmed());
-                    build_metadata.add_page(
-                        base_path.clone(),
-                        file_path.to_string_lossy().to_string(),
-                        None,
-                        true,
-                    );
-                    add_sitemap_entry(
-                        &mut sitemap_entries,
-                        normalized_base_url,
-                        &url,
-                        base_path,
-                        &route.sitemap_metadata(),
-                        &options.sitemap,
-                    );
-                    cached_count += 1;
-                } else {
-                    let page_start = Instant::now();
-                    let mut route_assets = RouteAssets::with_default_assets(
-                        &route_assets_options,
-                        Some(image_cache.clone()),
-                        Some(asset_hash_cache.clone()),
-                        default_scripts.clone(),
-                        vec![],
-                    );
-
-                    let mut page_ctx = PageContext::from_static_route(
-                        content_sources,
-                        &mut route_assets,
-                        &url,
-                        &options.base_url,
-                        None,
-                    );
-                    let result = route.build(&mut page_ctx)?;
-                    let access_log = page_ctx.take_access_log();
-
-                    write_route_file(
-                        &result,
-                        &file_path,
-                        &mut created_dirs,
-                        &mut pages_with_assets,
-                        assets_prefix_bytes,
-                    )?;
-                    info!(target: "pages", "{} -> {} {}", url, file_path.to_string_lossy().dimmed(), format_elapsed_time(page_start.elapsed(), &route_format_options));
-
-                    if let Some(page_key) = page_key {
-                        record_page_cache_entry(
-                            &mut new_cache,
-                            page_key,
-                            access_log,
-                            &route_assets,
-                            file_path.clone(),
-                        );
-                    }
-
-                    build_pages_images.extend(route_assets.images);
-                    build_pages_scripts.extend(route_assets.scripts);
-                    build_pages_styles.extend(route_assets.styles);
-
-                    build_metadata.add_page(
-                        base_path.clone(),
-                        file_path.to_string_lossy().to_string(),
-                        None,
-                        false,
-                    );
-                    add_sitemap_entry(
-                        &mut sitemap_entries,
-                        normalized_base_url,
-                        &url,
-                        base_path,
-                        &route.sitemap_metadata(),
-                        &options.sitemap,
-                    );
-                    rendered_count += 1;
+                if !cache_hit {
+                    jobs.push(PageJob {
+                        route: *route,
+                        page: None,
+                        variant: None,
+                        url: url.clone(),
+                        file_path: file_path.clone(),
+                        get_pages_access_log: None,
+                    });
                 }
+
+                steps.push(PageStep::Page(PlannedPage {
+                    route: *route,
+                    label: format!("{} -> {}", url, file_path.to_string_lossy().dimmed()),
+                    route_path: base_path.clone(),
+                    params: None,
+                    url,
+                    file_path,
+                    page_key,
+                    cached: cache_hit,
+                }));
             } else {
                 // Dynamic base route
                 let mut pages_route_assets = RouteAssets::with_default_assets(
@@ -530,167 +678,62 @@ pub async fn build(
                 let mut dynamic_ctx =
                     DynamicRouteContext::new(content_sources, &mut pages_route_assets, None);
                 let pages = route.get_pages(&mut dynamic_ctx);
-                let get_pages_access_log = dynamic_ctx.take_access_log();
+                let get_pages_access_log = Arc::new(dynamic_ctx.take_access_log());
 
                 if pages.is_empty() {
-                    warn!(target: "build", "{} is a dynamic route, but its implementation of Route::pages returned an empty Vec. No pages will be generated for this route.", base_path.bold());
+                    steps.push(PageStep::Warn(format!("{} is a dynamic route, but its implementation of Route::pages returned an empty Vec. No pages will be generated for this route.", base_path.bold())));
                     continue;
                 }
 
-                info!(target: "pages", "{}", base_path);
-
-                if new_cache.is_some() {
-                    // Incremental: per-page RouteAssets for dependency tracking
-                    for page in pages {
-                        let page_key = cache::PageKey::new(base_path, &page.0.0, None);
-                        let (url, file_path) =
-                            cached_route.url_and_file_path(&page.0, &options.output_dir);
-
-                        if try_cache_hit(
-                            *route,
-                            &page_key,
-                            &incremental_state,
-                            &mut new_cache,
-                            &route_assets_options,
-                            &mut build_pages_scripts,
-                            &mut build_pages_styles,
-                        ) {
-                            info!(target: "pages", "├─ {} (cached)", file_path.to_string_lossy().dimmed());
-                            build_metadata.add_page(
-                                base_path.clone(),
-                                file_path.to_string_lossy().to_string(),
-                                Some(page.0.0.clone()),
-                                true,
-                            );
-                            add_sitemap_entry(
-                                &mut sitemap_entries,
-                                normalized_base_url,
-                                &url,
-                                base_path,
-                                &route.sitemap_metadata(),
-                                &options.sitemap,
-                            );
-                            cached_count += 1;
-                            continue;
-                        }
-
-                        let page_start = Instant::now();
-                        let mut route_assets = RouteAssets::with_default_assets(
-                            &route_assets_options,
-                            Some(image_cache.clone()),
-                            Some(asset_hash_cache.clone()),
-                            default_scripts.clone(),
-                            vec![],
-                        );
-
-                        let mut page_ctx = PageContext::from_dynamic_route(
-                            &page,
-                            content_sources,
-                            &mut route_assets,
-                            &url,
-                            &options.base_url,
-                            None,
-                        );
-                        let content = route.build(&mut page_ctx)?;
-                        let mut access_log = page_ctx.take_access_log();
-                        // Merge content dependencies from get_pages() into each page's log,
-                        // so that content read during page enumeration is tracked per-page.
-                        access_log.merge_entries_read(&get_pages_access_log);
-                        // If into_pages() produced this page from a specific entry,
-                        // record precise per-entry dependency. Otherwise, if
-                        // render() didn't track any content dependencies itself,
-                        // fall back to source-level tracking (all pages dirty when
-                        // any entry changes) to avoid serving stale content.
-                        if let Some((src, id)) = &page.3 {
-                            access_log.entries_read.push((src.clone(), id.clone()));
-                        } else if access_log.entries_read.is_empty()
-                            && access_log.sources_iterated.is_empty()
-                        {
-                            access_log
-                                .sources_iterated
-                                .extend(get_pages_access_log.sources_iterated.iter().cloned());
-                        }
+                steps.push(PageStep::Info(base_path.clone()));
 
-                        write_route_file(
-                            &content,
-                            &file_path,
-                            &mut created_dirs,
-                            &mut pages_with_assets,
-                            assets_prefix_bytes,
-                        )?;
-                        info!(target: "pages", "├─ {} {}", file_path.to_string_lossy().dimmed(), format_elapsed_time(page_start.elapsed(), &route_format_options));
-
-                        record_page_cache_entry(
-                            &mut new_cache,
-                            page_key,
-                            access_log,
-                            &route_assets,
-                            file_path.clone(),
-                        );
-
-                        build_pages_images.extend(route_assets.images);
-                        build_pages_scripts.extend(route_assets.scripts);
-                        build_pages_styles.extend(route_assets.styles);
-
-                        build_metadata.add_page(
-                            base_path.clone(),
-                            file_path.to_string_lossy().to_string(),
-                            Some(page.0.0.clone()),
-                            false,
-                        );
-                        add_sitemap_entry(
-                            &mut sitemap_entries,
-                            normalized_base_url,
-                            &url,
-                            base_path,
-                            &route.sitemap_metadata(),
-                            &options.sitemap,
-                        );
-                        rendered_count += 1;
+                let entry_pages = pages.iter().all(|page| page.3.len() == 1);
+                for page in pages {
+                    let (url, file_path) =
+                        cached_route.url_and_file_path(&page.0, &options.output_dir);
+                    if entry_pages {
+                        entry_urls.add_page(&page.3[0], None, &url);
                     }
-                } else {
-                    // Non-incremental: use shared RouteAssets across all routes
-                    let route_assets = shared_route_assets.as_mut().unwrap();
-
-                    for page in pages {
-                        let page_start = Instant::now();
-                        let (url, file_path) =
-                            cached_route.url_and_file_path(&page.0, &options.output_dir);
-
-                        let content = route.build(&mut PageContext::from_dynamic_route(
-                            &page,
-                            content_sources,
-                            route_assets,
-                            &url,
-                            &options.base_url,
-                            None,
-                        ))?;
-
-                        write_route_file(
-                            &content,
-                            &file_path,
-                            &mut created_dirs,
-                            &mut pages_with_assets,
-                            assets_prefix_bytes,
-                        )?;
-                        info!(target: "pages", "├─ {} {}", file_path.to_string_lossy().dimmed(), format_elapsed_time(page_start.elapsed(), &route_format_options));
-
-                        build_metadata.add_page(
-                            base_path.clone(),
-                            file_path.to_string_lossy().to_string(),
-                            Some(page.0.0.clone()),
-                            false,
-                        );
-                        add_sitemap_entry(
-                            &mut sitemap_entries,
-                            normalized_base_url,
-                            &url,
-                            base_path,
-                            &route.sitemap_metadata(),
-                            &options.sitemap,
-                        );
-                        rendered_count += 1;
+                    let page_key = new_cache
+                        .is_some()
+                        .then(|| cache::PageKey::new(base_path, &page.0.0, None));
+
+                    let cache_hit = page_key.as_ref().is_some_and(|pk| {
+                        !incremental_state
+                            .query_results_changed(pk, &get_pages_access_log.query_results)
+                            && try_cache_hit(
+                                *route,
+                                pk,
+                                &incremental_state,
+                                &mut new_cache,
+                                &route_assets_options,
+                                &mut build_pages_scripts,
+                                &mut build_pages_styles,
+                            )
+                    });
+
+                    let params = Some(page.0.0.clone());
+                    if !cache_hit {
+                        jobs.push(PageJob {
+                            route: *route,
+                            page: Some(page),
+                            variant: None,
+                            url: url.clone(),
+                            file_path: file_path.clone(),
+                            get_pages_access_log: Some(get_pages_access_log.clone()),
+                        });
                     }
+
+                    steps.push(PageStep::Page(PlannedPage {
+                        route: *route,
+                        label: format!("├─ {}", file_path.to_string_lossy().dimmed()),
+                        route_path: base_path.clone(),
+                        params,
+                        url,
+                        file_path,
+                        page_key,
+                        cached: cache_hit,
+                    }));
                 }
             }
         }
@@ -701,17 +744,14 @@ pub async fn build(
 
             if variant_params.is_empty() {
                 // Static variant
-                let params = PageParams::default();
                 let (url, file_path) = cached_route.variant_url_and_file_path(
-                    &params,
+                    &PageParams::default(),
                     &options.output_dir,
                     &variant_id,
                 )?;
-                let page_key = if new_cache.is_some() {
-                    Some(cache::PageKey::new_static(&variant_path, Some(&variant_id)))
-                } else {
-                    None
-                };
+                let page_key = new_cache
+                    .is_some()
+                    .then(|| cache::PageKey::new_static(&variant_path, Some(&variant_id)));
 
                 let cache_hit = page_key.as_ref().is_some_and(|pk| {
                     try_cache_hit(
@@ -725,83 +765,27 @@ pub async fn build(
                     )
                 });
 
-                if cache_hit {
-                    info!(target: "pages", "├─ {} (cached)", file_path.to_string_lossy().dimmed());
-                    build_metadata.add_page(
-                        variant_path.clone(),
-                        file_path.to_string_lossy().to_string(),
-                        None,
-                        true,
-                    );
-                    add_sitemap_entry(
-                        &mut sitemap_entries,
-                        normalized_base_url,
-                        &url,
-                        &variant_path,
-                        &route.sitemap_metadata(),
-                        &options.sitemap,
-                    );
-                    cached_count += 1;
-                    continue;
-                }
-
-                let variant_start = Instant::now();
-                let mut route_assets = RouteAssets::with_default_assets(
-                    &route_assets_options,
-                    Some(image_cache.clone()),
-                    Some(asset_hash_cache.clone()),
-                    default_scripts.clone(),
-                    vec![],
-                );
-
-                let mut page_ctx = PageContext::from_static_route(
-                    content_sources,
-                    &mut route_assets,
-                    &url,
-                    &options.base_url,
-                    Some(variant_id.clone()),
-                );
-                let result = route.build(&mut page_ctx)?;
-                let access_log = page_ctx.take_access_log();
-
-                write_route_file(
-                    &result,
-                    &file_path,
-                    &mut created_dirs,
-                    &mut pages_with_assets,
-                    assets_prefix_bytes,
-                )?;
-                info!(target: "pages", "├─ {} {}", file_path.to_string_lossy().dimmed(), format_elapsed_time(variant_start.elapsed(), &route_format_options));
-
-                if let Some(page_key) = page_key {
-                    record_page_cache_entry(
-                        &mut new_cache,
-                        page_key,
-                        access_log,
-                        &route_assets,
-                        file_path.clone(),
-                    );
+                if !cache_hit {
+                    jobs.push(PageJob {
+                        route: *route,
+                        page: None,
+                        variant: Some(variant_id.clone()),
+                        url: url.clone(),
+                        file_path: file_path.clone(),
+                        get_pages_access_log: None,
+                    });
                 }
 
-                build_pages_images.extend(route_assets.images);
-                build_pages_scripts.extend(route_assets.scripts);
-                build_pages_styles.extend(route_assets.styles);
-
-                build_metadata.add_page(
-                    variant_path.clone(),
-                    file_path.to_string_lossy().to_string(),
-                    None,
-                    false,
-                );
-                add_sitemap_entry(
-                    &mut sitemap_entries,
-                    normalized_base_url,
-                    &url,
-                    &variant_path,
-                    &route.sitemap_metadata(),
-                    &options.sitemap,
-                );
-                rendered_count += 1;
+                steps.push(PageStep::Page(PlannedPage {
+                    route: *route,
+                    label: format!("├─ {}", file_path.to_string_lossy().dimmed()),
+                    route_path: variant_path.clone(),
+                    params: None,
+                    url,
+                    file_path,
+                    page_key,
+                    cached: cache_hit,
+                }));
             } else {
                 // Dynamic variant
                 let mut pages_route_assets = RouteAssets::with_default_assets(
@@ -817,176 +801,153 @@ pub async fn build(
                     Some(&variant_id),
                 );
                 let pages = route.get_pages(&mut dynamic_ctx);
-                let get_pages_access_log = dynamic_ctx.take_access_log();
+                let get_pages_access_log = Arc::new(dynamic_ctx.take_access_log());
 
                 if pages.is_empty() {
-                    warn!(target: "build", "Variant {} has dynamic parameters but Route::pages returned an empty Vec.", variant_id.bold());
+                    steps.push(PageStep::Warn(format!(
+                        "Variant {} has dynamic parameters but Route::pages returned an empty Vec.",
+                        variant_id.bold()
+                    )));
                     continue;
                 }
 
-                info!(target: "pages", "├─ {}", variant_path);
-
-                if new_cache.is_some() {
-                    for page in pages {
-                        let page_key =
-                            cache::PageKey::new(&variant_path, &page.0.0, Some(&variant_id));
-                        let (url, file_path) = cached_route.variant_url_and_file_path(
-                            &page.0,
-                            &options.output_dir,
-                            &variant_id,
-                        )?;
-
-                        if try_cache_hit(
-                            *route,
-                            &page_key,
-                            &incremental_state,
-                            &mut new_cache,
-                            &route_assets_options,
-                            &mut build_pages_scripts,
-                            &mut build_pages_styles,
-                        ) {
-                            info!(target: "pages", "│  ├─ {} (cached)", file_path.to_string_lossy().dimmed());
-                            build_metadata.add_page(
-                                variant_path.clone(),
-                                file_path.to_string_lossy().to_string(),
-                                Some(page.0.0.clone()),
-                                true,
-                            );
-                            add_sitemap_entry(
-                                &mut sitemap_entries,
-                                normalized_base_url,
-                                &url,
-                                &variant_path,
-                                &route.sitemap_metadata(),
-                                &options.sitemap,
-                            );
-                            cached_count += 1;
-                            continue;
-                        }
-
-                        let variant_page_start = Instant::now();
-                        let mut route_assets = RouteAssets::with_default_assets(
-                            &route_assets_options,
-                            Some(image_cache.clone()),
-                            Some(asset_hash_cache.clone()),
-                            default_scripts.clone(),
-                            vec![],
-                        );
-
-                        let mut page_ctx = PageContext::from_dynamic_route(
-                            &page,
-                            content_sources,
-                            &mut route_assets,
-                            &url,
-                            &options.base_url,
-                            Some(variant_id.clone()),
-                        );
-                        let content = route.build(&mut page_ctx)?;
-                        let mut access_log = page_ctx.take_access_log();
-                        access_log.merge_entries_read(&get_pages_access_log);
-                        if let Some((src, id)) = &page.3 {
-                            access_log.entries_read.push((src.clone(), id.clone()));
-                        } else if access_log.entries_read.is_empty()
-                            && access_log.sources_iterated.is_empty()
-                        {
-                            access_log
-                                .sources_iterated
-                                .extend(get_pages_access_log.sources_iterated.iter().cloned());
-                        }
+                steps.push(PageStep::Info(format!("├─ {}", variant_path)));
 
-                        write_route_file(
-                            &content,
-                            &file_path,
-                            &mut created_dirs,
-                            &mut pages_with_assets,
-                            assets_prefix_bytes,
-                        )?;
-                        info!(target: "pages", "│  ├─ {} {}", file_path.to_string_lossy().dimmed(), format_elapsed_time(variant_page_start.elapsed(), &route_format_options));
-
-                        record_page_cache_entry(
-                            &mut new_cache,
-                            page_key,
-                            access_log,
-                            &route_assets,
-                            file_path.clone(),
-                        );
-
-                        build_pages_images.extend(route_assets.images);
-                        build_pages_scripts.extend(route_assets.scripts);
-                        build_pages_styles.extend(route_assets.styles);
-
-                        build_metadata.add_page(
-                            variant_path.clone(),
-                            file_path.to_string_lossy().to_string(),
-                            Some(page.0.0.clone()),
-                            false,
-                        );
-                        add_sitemap_entry(
-                            &mut sitemap_entries,
-                            normalized_base_url,
-                            &url,
-                            &variant_path,
-                            &route.sitemap_metadata(),
-                            &options.sitemap,
-                        );
-                        rendered_count += 1;
+                let entry_pages = pages.iter().all(|page| page.3.len() == 1);
+                for page in pages {
+                    let (url, file_path) = cached_route.variant_url_and_file_path(
+                        &page.0,
+                        &options.output_dir,
+                        &variant_id,
+                    )?;
+                    if entry_pages {
+                        entry_urls.add_page(&page.3[0], Some(&variant_id), &url);
                     }
-                } else {
-                    // Non-incremental: use shared RouteAssets across all routes
-                    let route_assets = shared_route_assets.as_mut().unwrap();
-
-                    for page in pages {
-                        let variant_page_start = Instant::now();
-                        let (url, file_path) = cached_route.variant_url_and_file_path(
-                            &page.0,
-                            &options.output_dir,
-                            &variant_id,
-                        )?;
-
-                        let content = route.build(&mut PageContext::from_dynamic_route(
-                            &page,
-                            content_sources,
-                            route_assets,
-                            &url,
-                            &options.base_url,
-                            Some(variant_id.clone()),
-                        ))?;
-
-                        write_route_file(
-                            &content,
-                            &file_path,
-                            &mut created_dirs,
-                            &mut pages_with_assets,
-                            assets_prefix_bytes,
-                        )?;
-                        info!(target: "pages", "│  ├─ {} {}", file_path.to_string_lossy().dimmed(), format_elapsed_time(variant_page_start.elapsed(), &route_format_options));
-
-                        build_metadata.add_page(
-                            variant_path.clone(),
-                            file_path.to_string_lossy().to_string(),
-                            Some(page.0.0.clone()),
-                            false,
-                        );
-                        add_sitemap_entry(
-                            &mut sitemap_entries,
-                            normalized_base_url,
-                            &url,
-                            &variant_path,
-                            &route.sitemap_metadata(),
-                            &options.sitemap,
-                        );
-                        rendered_count += 1;
+                    let page_key = new_cache
+                        .is_some()
+                        .then(|| cache::PageKey::new(&variant_path, &page.0.0, Some(&variant_id)));
+
+                    let cache_hit = page_key.as_ref().is_some_and(|pk| {
+                        !incremental_state
+                            .query_results_changed(pk, &get_pages_access_log.query_results)
+                            && try_cache_hit(
+                                *route,
+                                pk,
+                                &incremental_state,
+                                &mut new_cache,
+                                &route_assets_options,
+                                &mut build_pages_scripts,
+                                &mut build_pages_styles,
+                            )
+                    });
+
+                    let params = Some(page.0.0.clone());
+                    if !cache_hit {
+                        jobs.push(PageJob {
+                            route: *route,
+                            page: Some(page),
+                            variant: Some(variant_id.clone()),
+                            url: url.clone(),
+                            file_path: file_path.clone(),
+                            get_pages_access_log: Some(get_pages_access_log.clone()),
+                        });
                     }
+
+                    steps.push(PageStep::Page(PlannedPage {
+                        route: *route,
+                        label: format!("│  ├─ {}", file_path.to_string_lossy().dimmed()),
+                        route_path: variant_path.clone(),
+                        params,
+                        url,
+                        file_path,
+                        page_key,
+                        cached: cache_hit,
+                    }));
                 }
             }
         }
     }
 
-    // Collect assets from the shared RouteAssets (non-incremental builds)
-    if let Some(route_assets) = shared_route_assets {
-        build_pages_images.extend(route_assets.images);
-        build_pages_scripts.extend(route_assets.scripts);
-        build_pages_styles.extend(route_assets.styles);
+    entry_urls.index_files(content_sources);
+
+    let renderer = PageRenderer {
+        content_sources,
+        base_url: &options.base_url,
+        route_assets_options: &route_assets_options,
+        image_cache: &image_cache,
+        asset_hash_cache: &asset_hash_cache,
+        default_scripts: &default_scripts,
+        entry_urls: &entry_urls,
+        markdown_cache: markdown_cache.as_ref(),
+        strict_links: options.strict_links,
+        created_dirs: Mutex::new(FxHashSet::default()),
+        assets_prefix: assets_prefix_bytes,
+    };
+
+    // `collect` keeps the results in job order, which is also the order of the steps.
+    let mut rendered_pages = jobs
+        .into_par_iter()
+        .map(|job| renderer.render(job))
+        .collect::<Vec<_>>()
+        .into_iter();
+
+    for step in steps {
+        let page = match step {
+            PageStep::Info(message) => {
+                info!(target: "pages", "{}", message);
+                continue;
+            }
+            PageStep::Warn(message) => {
+                warn!(target: "build", "{}", message);
+                continue;
+            }
+            PageStep::Page(page) => page,
+        };
+
+        if page.cached {
+            info!(target: "pages", "{} (cached)", page.label);
+            cached_count += 1;
+            cached_urls.insert(page.url.clone());
+        } else {
+            let rendered = rendered_pages
+                .next()
+                .expect("every uncached page should have a render result")?;
+            info!(target: "pages", "{} {}", page.label, format_elapsed_time(rendered.elapsed, &route_format_options));
+
+            if let Some(page_key) = page.page_key {
+                record_page_cache_entry(
+                    &mut new_cache,
+                    page_key,
+                    rendered.access_log,
+                    &rendered.route_assets,
+                    page.file_path.clone(),
+                );
+            }
+
+            if rendered.has_assets {
+                pages_with_assets.push(page.file_path.clone());
+            }
+
+            build_pages_images.extend(rendered.route_assets.images);
+            build_pages_scripts.extend(rendered.route_assets.scripts);
+            build_pages_styles.extend(rendered.route_assets.styles);
+            rendered_count += 1;
+        }
+
+        build_metadata.add_page(
+            page.route_path.clone(),
+            page.file_path.to_string_lossy().to_string(),
+            page.params,
+            page.cached,
+        );
+        add_sitemap_entry(
+            &mut sitemap_entries,
+            normalized_base_url,
+            &page.url,
+            &page.route_path,
+            &page.route.sitemap_metadata(),
+            &options.sitemap,
+        );
     }
 
     let page_count = rendered_count + cached_count;
@@ -999,6 +960,10 @@ pub async fn build(
     // Populate asset_file_hashes in the new cache from all pages (cached + rendered).
     // Carry forward fingerprints from the previous cache to avoid re-reading unchanged files.
     if let Some(ref mut cache) = new_cache {
+        if let Some(markdown_cache) = &markdown_cache {
+            cache.rendered_markdown = markdown_cache.entries(&cached_urls);
+        }
+
         let asset_hash_start = Instant::now();
         let previous_fingerprints = incremental_state
             .previous_cache
@@ -1050,7 +1015,7 @@ pub async fn build(
 
         // Persist the in-memory asset hash cache for the next build.
         // Each entry gets the current file mtime+size so we can validate on reload.
-        let hash_cache = asset_hash_cache.borrow();
+        let hash_cache = asset_hash_cache.lock().unwrap();
         for (key, asset_hash) in hash_cache.iter() {
             if let Some((mtime, size)) = cache::file_fingerprint(key.path()) {
                 cache
@@ -1672,27 +1637,27 @@ fn bare_script_chunk_name(path: &Path) -> String {
     default_sanitize_file_name(stem)
 }
 
+/// Write a rendered page to disk, returning whether its content references an
+/// asset URL that the post-bundle pass will need to substitute.
 fn write_route_file(
     content: &[u8],
-    file_path: &PathBuf,
-    created_dirs: &mut FxHashSet<PathBuf>,
-    pages_with_assets: &mut Vec<PathBuf>,
+    file_path: &Path,
+    created_dirs: &Mutex<FxHashSet<PathBuf>>,
     assets_prefix: &[u8],
-) -> Result<(), io::Error> {
-    if let Some(parent_dir) = file_path.parent()
-        && created_dirs.insert(parent_dir.to_path_buf())
-    {
-        fs::create_dir_all(parent_dir)?;
+) -> Result<bool, io::Error> {
+    if let Some(parent_dir) = file_path.parent() {
+        // Hold the lock while creating the directory, so that another page in the same
+        // directory can't try to write its file before the directory exists.
+        let mut created_dirs = created_dirs.lock().unwrap();
+        if created_dirs.insert(parent_dir.to_path_buf()) {
+            fs::create_dir_all(parent_dir)?;
+        }
     }
 
     fs::write(file_path, content)?;
 
     // Skip pages that can't possibly reference an asset URL; saves a disk read each.
-    if memchr::memmem::find(content, assets_prefix).is_some() {
-        pages_with_assets.push(file_path.clone());
-    }
-
-    Ok(())
+    Ok(memchr::memmem::find(content, assets_prefix).is_some())
 }
 
 /// Rewrite asset URLs in HTML to the content-hashed URLs bundling produced. Handles
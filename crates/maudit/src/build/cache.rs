@@ -1,6 +1,7 @@
 use std::{
     fs,
     path::{Path, PathBuf},
+    sync::{Arc, Mutex},
     time::UNIX_EPOCH,
 };
 
@@ -8,7 +9,10 @@ use log::{debug, info};
 use rustc_hash::{FxHashMap, FxHashSet};
 use serde::{Deserialize, Serialize};
 
-pub const BUILD_CACHE_VERSION: u32 = 13;
+use crate::content::Dependency;
+use crate::content::tracked::QueryResult;
+
+pub const BUILD_CACHE_VERSION: u32 = 18;
 pub const BUILD_CACHE_FILENAME: &str = "build_cache.bin";
 
 /// Fingerprint for an asset file (script, style, image) used for fast change detection.
@@ -100,6 +104,9 @@ pub struct BuildCache {
     /// `@import`/lightningcss-driven byte changes that the source-file hash misses.
     #[serde(default)]
     pub style_substitutions: FxHashMap<String, String>,
+    /// Rendered HTML of Markdown entries, see `MarkdownRenderCache`.
+    #[serde(default)]
+    pub rendered_markdown: MarkdownRenders,
 }
 
 #[derive(Serialize, Deserialize, Default, Clone)]
@@ -108,11 +115,22 @@ pub struct ContentSourceState {
     pub files: FxHashMap<PathBuf, String>,
     /// Sorted list of entry IDs — used to detect structural changes.
     pub entry_ids: Vec<String>,
-    /// Reverse map from file_path to entry_id. Not serialized — rebuilt each run
+    /// Reverse map from file_path to the IDs of the entries it was read into (data files
+    /// can hold several entries). Not serialized — rebuilt each run
     /// by `compute_content_source_state()`. WARNING: this field is empty on
     /// deserialized (cached) instances — only use it on freshly-computed states.
     #[serde(skip)]
-    pub file_to_entry: FxHashMap<PathBuf, String>,
+    pub file_to_entry: FxHashMap<PathBuf, Vec<String>>,
+    /// Hashes of the dependencies that aren't single files (globs, environment variables
+    /// and custom values), keyed by `dependency_key()`.
+    pub dependencies: FxHashMap<String, String>,
+    /// Reverse map from dependency key to entry IDs, see `file_to_entry`.
+    #[serde(skip)]
+    pub dependency_to_entry: FxHashMap<String, Vec<String>>,
+    /// Mapping from entry ID to the hash of what its data is derived from, for entries
+    /// whose data is only part of their content (e.g. the frontmatter of Markdown files).
+    /// Computed by `compute_entry_data_hashes()`.
+    pub data_hashes: FxHashMap<String, String>,
 }
 
 /// Canonical key for a generated page. Must be stable across builds.
@@ -159,6 +177,11 @@ pub struct PageCacheEntry {
     pub content_entries_read: Vec<(String, String)>,
     /// Content sources this page fully iterated.
     pub content_sources_iterated: Vec<String>,
+    /// Content sources this page queried: the page depends on the data of all their entries,
+    /// the entries it actually used are in `content_entries_read`.
+    pub content_sources_queried: Vec<String>,
+    /// Results of the queries run by the route's `pages()`, the page is re-rendered when they change.
+    pub content_query_results: Vec<QueryResult>,
     /// Image assets used by this page.
     pub images: Vec<CachedImage>,
     /// Script assets used by this page.
@@ -198,6 +221,110 @@ pub struct SerializedAssetRef {
     pub hash: String,
 }
 
+/// HTML of a Markdown entry rendered in a previous build.
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct CachedMarkdown {
+    pub html: String,
+    /// Styles the rendering included in the page, e.g. the stylesheet of the highlighting theme, with whether they use Tailwind.
+    pub styles: Vec<(PathBuf, bool)>,
+}
+
+/// Rendered HTML of Markdown entries, keyed by the hash of what they're rendered from, see [`MarkdownRenderCache::hash`].
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+pub struct MarkdownRenders {
+    pub html: FxHashMap<String, CachedMarkdown>,
+    /// Hashes of the renders each page used, to keep the ones of pages served from the page cache.
+    pub pages: FxHashMap<String, FxHashSet<String>>,
+}
+
+/// Rendered HTML of Markdown entries, reused across builds for pages that are re-rendered while their entries didn't change,
+/// e.g. every page of a documentation listing all of its pages when one of them is edited, and across the pages of a build
+/// showing the same entry.
+///
+/// Renders are only cached when their output solely depends on what's hashed: ones that resolve links to other entries,
+/// use images or read content, for instance through shortcodes, are always rendered again.
+#[derive(Clone, Default)]
+pub struct MarkdownRenderCache {
+    binary_hash: String,
+    /// Renders of the previous build.
+    previous: Arc<MarkdownRenders>,
+    /// Renders reused or added during this build.
+    current: Arc<Mutex<MarkdownRenders>>,
+}
+
+impl MarkdownRenderCache {
+    pub fn new(binary_hash: &str, previous: MarkdownRenders) -> Self {
+        Self {
+            binary_hash: binary_hash.to_string(),
+            previous: Arc::new(previous),
+            current: Arc::default(),
+        }
+    }
+
+    /// Hash of what the HTML of a Markdown entry is rendered from.
+    pub fn hash(&self, raw_content: &str, options_fingerprint: &str) -> String {
+        hash_bytes(
+            format!(
+                "{}\0{}\0{}",
+                self.binary_hash, options_fingerprint, raw_content
+            )
+            .as_bytes(),
+        )
+    }
+
+    /// Get the HTML of an entry rendered from the same content, recording that `page` uses it.
+    pub fn get(&self, page: &str, hash: &str) -> Option<CachedMarkdown> {
+        let mut current = self.current.lock().unwrap();
+        let cached = match current.html.get(hash) {
+            Some(cached) => cached.clone(),
+            None => {
+                let cached = self.previous.html.get(hash)?.clone();
+                current.html.insert(hash.to_string(), cached.clone());
+                cached
+            }
+        };
+        current
+            .pages
+            .entry(page.to_string())
+            .or_default()
+            .insert(hash.to_string());
+        Some(cached)
+    }
+
+    pub fn insert(&self, page: &str, hash: String, cached: CachedMarkdown) {
+        let mut current = self.current.lock().unwrap();
+        current
+            .pages
+            .entry(page.to_string())
+            .or_default()
+            .insert(hash.clone());
+        current.html.insert(hash, cached);
+    }
+
+    /// Renders to keep for the next build: the ones used by the pages rendered during this build, and the ones of
+    /// the pages served from the page cache, which still need them once they're rendered again.
+    ///
+    /// Renders no page uses anymore, e.g. of entries that changed or of pages that no longer exist, are dropped.
+    pub fn entries(&self, cached_pages: &FxHashSet<String>) -> MarkdownRenders {
+        let mut entries = self.current.lock().unwrap().clone();
+        for (page, hashes) in &self.previous.pages {
+            if !cached_pages.contains(page) || entries.pages.contains_key(page) {
+                continue;
+            }
+            for hash in hashes {
+                if let Some(cached) = self.previous.html.get(hash) {
+                    entries
+                        .html
+                        .entry(hash.clone())
+                        .or_insert_with(|| cached.clone());
+                }
+            }
+            entries.pages.insert(page.clone(), hashes.clone());
+        }
+        entries
+    }
+}
+
 impl BuildCache {
     pub fn load(cache_dir: &Path) -> Option<Self> {
         let path = cache_dir.join(BUILD_CACHE_FILENAME);
@@ -286,6 +413,15 @@ impl IncrementalState {
             IncrementalMode::Incremental => self.dirty_pages.contains(key),
         }
     }
+
+    /// Check if the queries run by `pages()` selected different entries than when a page was last rendered,
+    /// in which case it may now show other entries.
+    pub fn query_results_changed(&self, key: &PageKey, query_results: &[QueryResult]) -> bool {
+        self.previous_cache
+            .as_ref()
+            .and_then(|c| c.pages.get(key))
+            .is_some_and(|entry| entry.content_query_results != query_results)
+    }
 }
 
 /// Hash raw bytes using rapidhash for speed.
@@ -310,18 +446,20 @@ pub fn hash_file_content(path: &Path) -> Option<String> {
 /// When an entry has exactly one file dep and its content is available, we hash
 /// from memory instead of re-reading from disk.
 pub fn compute_content_source_state(
-    entry_file_info: &[(String, Vec<PathBuf>)],
+    entry_dependencies: &[(String, Vec<Dependency>)],
     raw_content_by_entry: &FxHashMap<String, &str>,
 ) -> ContentSourceState {
     let mut files = FxHashMap::default();
-    let mut entry_ids: Vec<String> = Vec::with_capacity(entry_file_info.len());
+    let mut entry_ids: Vec<String> = Vec::with_capacity(entry_dependencies.len());
     let mut file_to_entry = FxHashMap::default();
+    let mut dependencies = FxHashMap::default();
+    let mut dependency_to_entry = FxHashMap::default();
 
-    for (id, file_paths) in entry_file_info {
+    for (id, entry_deps) in entry_dependencies {
         entry_ids.push(id.clone());
 
         // If the entry has exactly one file dep and we have its raw_content, hash from memory
-        let in_memory_hash = if file_paths.len() == 1 {
+        let in_memory_hash = if let [Dependency::File(_)] = entry_deps.as_slice() {
             raw_content_by_entry
                 .get(id.as_str())
                 .map(|content| hash_bytes(content.as_bytes()))
@@ -329,13 +467,28 @@ pub fn compute_content_source_state(
             None
         };
 
-        for (i, fp) in file_paths.iter().enumerate() {
-            let hash = if i == 0 { in_memory_hash.clone() } else { None }
-                .or_else(|| hash_file_content(fp));
+        for dependency in entry_deps {
+            let Dependency::File(fp) = dependency else {
+                let key = dependency_key(dependency);
+                // Hashed once per source, as many entries can share a glob or a variable
+                if !dependencies.contains_key(&key) {
+                    dependencies.insert(key.clone(), hash_dependency(dependency));
+                }
+                dependency_to_entry
+                    .entry(key)
+                    .or_insert_with(Vec::new)
+                    .push(id.clone());
+                continue;
+            };
+
+            let hash = in_memory_hash.clone().or_else(|| hash_file_content(fp));
 
             if let Some(hash) = hash {
                 files.insert(fp.clone(), hash);
-                file_to_entry.insert(fp.clone(), id.clone());
+                file_to_entry
+                    .entry(fp.clone())
+                    .or_insert_with(Vec::new)
+                    .push(id.clone());
             }
         }
     }
@@ -346,6 +499,75 @@ pub fn compute_content_source_state(
         files,
         entry_ids,
         file_to_entry,
+        dependencies,
+        dependency_to_entry,
+        data_hashes: FxHashMap::default(),
+    }
+}
+
+/// Hash the data fingerprints of the entries of a content source. Entries whose files and dependencies
+/// didn't change since `previous` keep their previous hash, so only the entries that changed are fingerprinted.
+pub fn compute_entry_data_hashes(
+    current: &ContentSourceState,
+    previous: Option<&ContentSourceState>,
+    fingerprint: impl Fn(&str) -> Option<String>,
+) -> FxHashMap<String, String> {
+    let reusable = previous.map(|previous| (previous, changed_entry_ids(previous, current)));
+
+    current
+        .entry_ids
+        .iter()
+        .filter_map(|id| {
+            let previous_hash = reusable
+                .as_ref()
+                .filter(|(_, changed)| !changed.contains(id))
+                .and_then(|(previous, _)| previous.data_hashes.get(id));
+            let hash = match previous_hash {
+                Some(hash) => hash.clone(),
+                None => hash_bytes(fingerprint(id)?.as_bytes()),
+            };
+            Some((id.clone(), hash))
+        })
+        .collect()
+}
+
+/// Stable key identifying a non-file dependency across builds.
+fn dependency_key(dependency: &Dependency) -> String {
+    match dependency {
+        Dependency::File(path) => format!("file:{}", path.display()),
+        Dependency::Glob(pattern) => format!("glob:{}", pattern),
+        Dependency::Env(name) => format!("env:{}", name),
+        Dependency::Custom { key, .. } => format!("custom:{}", key),
+    }
+}
+
+/// Hash the current state of a dependency.
+fn hash_dependency(dependency: &Dependency) -> String {
+    match dependency {
+        Dependency::File(path) => hash_file_content(path).unwrap_or_default(),
+        Dependency::Glob(pattern) => {
+            // Both the list of matched files and their content are hashed, so adding or removing a file is a change
+            let mut paths: Vec<PathBuf> = glob::glob(pattern)
+                .map(|paths| paths.filter_map(Result::ok).collect())
+                .unwrap_or_default();
+            paths.sort();
+
+            let mut state = String::new();
+            for path in paths {
+                state.push_str(&path.to_string_lossy());
+                state.push('\0');
+                if let Some(hash) = hash_file_content(&path) {
+                    state.push_str(&hash);
+                }
+                state.push('\n');
+            }
+            hash_bytes(state.as_bytes())
+        }
+        Dependency::Env(name) => match std::env::var_os(name) {
+            Some(value) => hash_bytes(format!("set:{}", value.to_string_lossy()).as_bytes()),
+            None => hash_bytes(b"unset"),
+        },
+        Dependency::Custom { hash, .. } => hash.clone(),
     }
 }
 
@@ -370,19 +592,8 @@ pub fn diff_content_sources(
                     structurally_changed.insert(name.clone());
                 }
 
-                // Check per-file content changes
-                for (file_path, current_hash) in &current_state.files {
-                    match cached_state.files.get(file_path) {
-                        Some(cached_hash) if cached_hash == current_hash => {
-                            // Unchanged
-                        }
-                        _ => {
-                            // File changed or new — look up the owning entry via reverse map
-                            if let Some(entry_id) = current_state.file_to_entry.get(file_path) {
-                                changed_entries.insert((name.clone(), entry_id.clone()));
-                            }
-                        }
-                    }
+                for entry_id in changed_entry_ids(cached_state, current_state) {
+                    changed_entries.insert((name.clone(), entry_id));
                 }
             }
         }
@@ -398,6 +609,62 @@ pub fn diff_content_sources(
     (structurally_changed, changed_entries)
 }
 
+/// IDs of the entries of a content source whose files or other dependencies changed.
+fn changed_entry_ids(
+    cached: &ContentSourceState,
+    current: &ContentSourceState,
+) -> FxHashSet<String> {
+    let mut changed = FxHashSet::default();
+
+    // Check per-file content changes
+    for (file_path, current_hash) in &current.files {
+        match cached.files.get(file_path) {
+            Some(cached_hash) if cached_hash == current_hash => {
+                // Unchanged
+            }
+            _ => {
+                // File changed or new — look up the owning entries via reverse map
+                if let Some(entry_ids) = current.file_to_entry.get(file_path) {
+                    changed.extend(entry_ids.iter().cloned());
+                }
+            }
+        }
+    }
+
+    // Same for the other dependencies (globs, environment variables, custom values)
+    for (key, current_hash) in &current.dependencies {
+        if cached.dependencies.get(key) == Some(current_hash) {
+            continue;
+        }
+        if let Some(entry_ids) = current.dependency_to_entry.get(key) {
+            changed.extend(entry_ids.iter().cloned());
+        }
+    }
+
+    changed
+}
+
+/// Among changed entries, find the ones whose data changed too. Entries without a data hash
+/// are considered to have changed entirely.
+pub fn diff_entry_data(
+    cached: &FxHashMap<String, ContentSourceState>,
+    current: &FxHashMap<String, ContentSourceState>,
+    changed_entries: &FxHashSet<(String, String)>,
+) -> FxHashSet<(String, String)> {
+    changed_entries
+        .iter()
+        .filter(|(source, id)| {
+            let cached_hash = cached.get(source).and_then(|s| s.data_hashes.get(id));
+            let current_hash = current.get(source).and_then(|s| s.data_hashes.get(id));
+            match (cached_hash, current_hash) {
+                (Some(cached_hash), Some(current_hash)) => cached_hash != current_hash,
+                _ => true,
+            }
+        })
+        .cloned()
+        .collect()
+}
+
 /// Get file mtime (nanoseconds since epoch) and size for fast change detection.
 pub fn file_fingerprint(path: &Path) -> Option<(u64, u64)> {
     let meta = fs::metadata(path).ok()?;
@@ -446,6 +713,7 @@ pub fn determine_dirty_pages(
     cache: &BuildCache,
     structurally_changed_sources: &FxHashSet<String>,
     changed_entries: &FxHashSet<(String, String)>,
+    changed_data_entries: &FxHashSet<(String, String)>,
     changed_asset_files: &FxHashSet<PathBuf>,
 ) -> FxHashSet<PageKey> {
     let mut dirty = FxHashSet::default();
@@ -468,6 +736,17 @@ pub fn determine_dirty_pages(
             }
         }
 
+        // Check if any queried source had entries added, removed or whose data changed,
+        // as filters and sort keys are computed from the data of every entry
+        for source_name in &page_entry.content_sources_queried {
+            if structurally_changed_sources.contains(source_name)
+                || changed_data_entries.iter().any(|(s, _)| s == source_name)
+            {
+                dirty.insert(page_key.clone());
+                continue 'pages;
+            }
+        }
+
         // Check if any specifically-read entry changed or was deleted.
         // A deleted entry won't appear in changed_entries (which only tracks current files),
         // but the owning source will have a structural change (entry_ids differ).
@@ -609,6 +888,12 @@ pub fn load_incremental_state(
         );
     }
 
+    let changed_data_entries = diff_entry_data(
+        &cache.content_sources,
+        current_content_states,
+        &changed_entries,
+    );
+
     // Diff asset files (scripts, styles, images) against cached hashes
     let changed_asset_files = diff_asset_files(&cache.asset_file_hashes);
 
@@ -625,6 +910,7 @@ pub fn load_incremental_state(
         &cache,
         &structurally_changed_sources,
         &changed_entries,
+        &changed_data_entries,
         &changed_asset_files,
     );
 
@@ -647,6 +933,8 @@ mod tests {
         PageCacheEntry {
             content_entries_read: entries_read,
             content_sources_iterated: sources_iterated,
+            content_sources_queried: vec![],
+            content_query_results: vec![],
             images: vec![],
             scripts: vec![],
             styles: vec![],
@@ -730,9 +1018,10 @@ mod tests {
                 entry_ids: vec!["a".to_string()],
                 file_to_entry: {
                     let mut m = FxHashMap::default();
-                    m.insert(PathBuf::from("content/a.md"), "a".to_string());
+                    m.insert(PathBuf::from("content/a.md"), vec!["a".to_string()]);
                     m
                 },
+                ..Default::default()
             },
         );
 
@@ -827,6 +1116,7 @@ mod tests {
             &FxHashSet::default(),
             &changed_entries,
             &FxHashSet::default(),
+            &FxHashSet::default(),
         );
 
         // Index is dirty because it iterates "articles"
@@ -901,6 +1191,7 @@ mod tests {
             &FxHashSet::default(),
             &changed_entries,
             &FxHashSet::default(),
+            &FxHashSet::default(),
         );
 
         assert!(!dirty.contains(&key));
@@ -934,12 +1225,75 @@ mod tests {
             &cache,
             &FxHashSet::default(),
             &FxHashSet::default(),
+            &FxHashSet::default(),
             &changed_assets,
         );
 
         assert!(dirty.contains(&key));
     }
 
+    #[test]
+    fn test_determine_dirty_pages_queried_source() {
+        let mut pages = FxHashMap::default();
+        let key = PageKey::new_static("/", None);
+        pages.insert(
+            key.clone(),
+            PageCacheEntry {
+                content_sources_queried: vec!["articles".to_string()],
+                content_query_results: vec![],
+                ..page_entry(
+                    vec![("articles".to_string(), "a".to_string())],
+                    vec![],
+                    "dist/index.html",
+                )
+            },
+        );
+        let cache = BuildCache {
+            pages,
+            ..Default::default()
+        };
+
+        let state = |data_hash: &str| {
+            let mut sources = FxHashMap::default();
+            sources.insert(
+                "articles".to_string(),
+                ContentSourceState {
+                    data_hashes: [("b".to_string(), data_hash.to_string())]
+                        .into_iter()
+                        .collect(),
+                    ..Default::default()
+                },
+            );
+            sources
+        };
+        let changed: FxHashSet<_> = [("articles".to_string(), "b".to_string())]
+            .into_iter()
+            .collect();
+
+        // Only the content of "b" changed, which the page doesn't use
+        let changed_data = diff_entry_data(&state("h1"), &state("h1"), &changed);
+        assert!(changed_data.is_empty());
+        let dirty = determine_dirty_pages(
+            &cache,
+            &FxHashSet::default(),
+            &changed,
+            &changed_data,
+            &FxHashSet::default(),
+        );
+        assert!(dirty.is_empty());
+
+        // The data of "b" changed, it may now be part of the query's result
+        let changed_data = diff_entry_data(&state("h1"), &state("h2"), &changed);
+        let dirty = determine_dirty_pages(
+            &cache,
+            &FxHashSet::default(),
+            &changed,
+            &changed_data,
+            &FxHashSet::default(),
+        );
+        assert!(dirty.contains(&key));
+    }
+
     #[test]
     fn test_find_stale_pages() {
         let mut cached_pages = FxHashMap::default();
@@ -1073,6 +1427,7 @@ mod tests {
             &structural,
             &FxHashSet::default(),
             &FxHashSet::default(),
+            &FxHashSet::default(),
         );
 
         // Index iterates "articles" → dirty
@@ -1086,7 +1441,7 @@ mod tests {
         let empty_raw: FxHashMap<String, &str> = FxHashMap::default();
 
         // Test with empty entries
-        let entries: Vec<(String, Vec<PathBuf>)> = vec![];
+        let entries: Vec<(String, Vec<Dependency>)> = vec![];
         let state = compute_content_source_state(&entries, &empty_raw);
         assert!(state.files.is_empty());
         assert!(state.entry_ids.is_empty());
@@ -1102,7 +1457,7 @@ mod tests {
         raw.insert("entry1".to_string(), "hello world");
         let entries = vec![(
             "entry1".to_string(),
-            vec![PathBuf::from("nonexistent/file.md")],
+            vec![Dependency::File(PathBuf::from("nonexistent/file.md"))],
         )];
         let state = compute_content_source_state(&entries, &raw);
         let expected_hash = hash_bytes(b"hello world");
@@ -1112,6 +1467,135 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_compute_entry_data_hashes_reuses_unchanged_entries() {
+        let mut raw = FxHashMap::default();
+        raw.insert("first".to_string(), "first");
+        raw.insert("second".to_string(), "second");
+        let entries = vec![
+            (
+                "first".to_string(),
+                vec![Dependency::File(PathBuf::from("first.md"))],
+            ),
+            (
+                "second".to_string(),
+                vec![Dependency::File(PathBuf::from("second.md"))],
+            ),
+        ];
+
+        let mut previous = compute_content_source_state(&entries, &raw);
+        previous.data_hashes =
+            compute_entry_data_hashes(&previous, None, |id| Some(id.to_string()));
+        assert_eq!(previous.data_hashes.len(), 2);
+
+        raw.insert("second".to_string(), "second, edited");
+        let current = compute_content_source_state(&entries, &raw);
+        let fingerprinted = std::cell::RefCell::new(Vec::new());
+        let data_hashes = compute_entry_data_hashes(&current, Some(&previous), |id| {
+            fingerprinted.borrow_mut().push(id.to_string());
+            Some(format!("{id}, edited"))
+        });
+
+        // Only the entry that changed is fingerprinted again
+        assert_eq!(fingerprinted.into_inner(), vec!["second".to_string()]);
+        assert_eq!(data_hashes.get("first"), previous.data_hashes.get("first"));
+        assert_ne!(
+            data_hashes.get("second"),
+            previous.data_hashes.get("second")
+        );
+    }
+
+    #[test]
+    fn test_diff_shared_file_marks_all_its_entries_changed() {
+        let tmp = tempfile::tempdir().unwrap();
+        let path = tmp.path().join("authors.json");
+        std::fs::write(&path, "[]").unwrap();
+
+        let entries = vec![
+            ("ada".to_string(), vec![Dependency::File(path.clone())]),
+            ("grace".to_string(), vec![Dependency::File(path.clone())]),
+        ];
+        let empty_raw: FxHashMap<String, &str> = FxHashMap::default();
+
+        let mut cached = FxHashMap::default();
+        cached.insert(
+            "authors".to_string(),
+            compute_content_source_state(&entries, &empty_raw),
+        );
+
+        std::fs::write(&path, "[{}]").unwrap();
+        let mut current = FxHashMap::default();
+        current.insert(
+            "authors".to_string(),
+            compute_content_source_state(&entries, &empty_raw),
+        );
+
+        let (structural, changed) = diff_content_sources(&cached, &current);
+        assert!(structural.is_empty());
+        assert!(changed.contains(&("authors".to_string(), "ada".to_string())));
+        assert!(changed.contains(&("authors".to_string(), "grace".to_string())));
+    }
+
+    #[test]
+    fn test_diff_non_file_dependencies() {
+        let tmp = tempfile::tempdir().unwrap();
+        std::fs::write(tmp.path().join("a.jpg"), "a").unwrap();
+        let pattern = tmp.path().join("*.jpg").to_string_lossy().to_string();
+        let env_var = "MAUDIT_TEST_DIFF_NON_FILE_DEPENDENCIES";
+
+        let entries = |snapshot: &str| {
+            vec![
+                (
+                    "gallery".to_string(),
+                    vec![Dependency::Glob(pattern.clone())],
+                ),
+                (
+                    "config".to_string(),
+                    vec![Dependency::Env(env_var.to_string())],
+                ),
+                (
+                    "api".to_string(),
+                    vec![Dependency::Custom {
+                        key: "api-snapshot".to_string(),
+                        hash: snapshot.to_string(),
+                    }],
+                ),
+            ]
+        };
+        let empty_raw: FxHashMap<String, &str> = FxHashMap::default();
+        let state = |snapshot: &str| {
+            let mut states = FxHashMap::default();
+            states.insert(
+                "source".to_string(),
+                compute_content_source_state(&entries(snapshot), &empty_raw),
+            );
+            states
+        };
+        fn changed_ids(
+            cached: &FxHashMap<String, ContentSourceState>,
+            current: &FxHashMap<String, ContentSourceState>,
+        ) -> Vec<String> {
+            let (_, changed) = diff_content_sources(cached, current);
+            let mut ids: Vec<String> = changed.into_iter().map(|(_, id)| id).collect();
+            ids.sort();
+            ids
+        }
+
+        let cached = state("v1");
+        assert!(changed_ids(&cached, &state("v1")).is_empty());
+        assert_eq!(changed_ids(&cached, &state("v2")), vec!["api"]);
+
+        // Adding a file matching the glob changes it, even if existing files are untouched
+        std::fs::write(tmp.path().join("b.jpg"), "b").unwrap();
+        assert_eq!(changed_ids(&cached, &state("v1")), vec!["gallery"]);
+
+        let cached = state("v1");
+        // SAFETY: no other test reads this variable
+        unsafe { std::env::set_var(env_var, "1") };
+        assert_eq!(changed_ids(&cached, &state("v1")), vec!["config"]);
+        unsafe { std::env::remove_var(env_var) };
+    }
+
     #[test]
     fn test_diff_new_source_is_structural_change() {
         let cached = FxHashMap::default();
@@ -1170,6 +1654,7 @@ mod tests {
             &FxHashSet::default(),
             &changed,
             &FxHashSet::default(),
+            &FxHashSet::default(),
         );
 
         assert!(dirty.contains(&key));
@@ -1205,6 +1690,7 @@ mod tests {
             &FxHashSet::default(),
             &FxHashSet::default(),
             &FxHashSet::default(),
+            &FxHashSet::default(),
         );
 
         assert!(dirty.is_empty());
@@ -1324,4 +1810,61 @@ mod tests {
         let changed = diff_asset_files(&cached);
         assert!(changed.contains(&file));
     }
+
+    #[test]
+    fn test_markdown_render_cache() {
+        let cache = MarkdownRenderCache::new("binary", MarkdownRenders::default());
+        let hash = cache.hash("# Post", "options");
+        cache.insert(
+            "/post/",
+            hash.clone(),
+            CachedMarkdown {
+                html: "<h1>Post</h1>".to_string(),
+                styles: vec![],
+            },
+        );
+
+        assert_eq!(cache.get("/post/", &hash).unwrap().html, "<h1>Post</h1>");
+        // The same entry rendered on another page is reused
+        assert_eq!(cache.get("/posts/", &hash).unwrap().html, "<h1>Post</h1>");
+        // Changing the content, the options or the binary changes the hash
+        assert_ne!(cache.hash("# Edited", "options"), hash);
+        assert_ne!(cache.hash("# Post", "other"), hash);
+        let rebuilt =
+            MarkdownRenderCache::new("other-binary", cache.entries(&FxHashSet::default()));
+        assert_ne!(rebuilt.hash("# Post", "options"), hash);
+    }
+
+    #[test]
+    fn test_markdown_render_cache_drops_unused_entries() {
+        let cached = || CachedMarkdown {
+            html: String::new(),
+            styles: vec![],
+        };
+        let hashes = |hashes: &[&str]| hashes.iter().map(|h| h.to_string()).collect();
+        let previous = MarkdownRenders {
+            html: ["post", "removed", "skipped", "deleted"]
+                .into_iter()
+                .map(|hash| (hash.to_string(), cached()))
+                .collect(),
+            pages: FxHashMap::from_iter([
+                ("/post/".to_string(), hashes(&["post", "removed"])),
+                ("/skipped/".to_string(), hashes(&["skipped"])),
+                ("/deleted/".to_string(), hashes(&["deleted"])),
+            ]),
+        };
+        let cache = MarkdownRenderCache::new("binary", previous);
+
+        // `/post/` is rendered again without `removed`, `/skipped/` is served from the page cache and `/deleted/` is gone
+        assert!(cache.get("/post/", "post").is_some());
+        cache.insert("/post/", "added".to_string(), cached());
+        let cached_pages = FxHashSet::from_iter(["/skipped/".to_string()]);
+
+        let entries = cache.entries(&cached_pages);
+        let mut kept = entries.html.into_keys().collect::<Vec<_>>();
+        kept.sort();
+        assert_eq!(kept, vec!["added", "post", "skipped"]);
+        assert_eq!(entries.pages["/post/"], hashes(&["added", "post"]));
+        assert_eq!(entries.pages["/skipped/"], hashes(&["skipped"]));
+    }
 }
@@ -118,6 +118,16 @@ pub struct BuildOptions {
     /// Directory for build cache storage.
     /// Defaults to `{workspace_root}/target/maudit/<binary_name>`.
     pub cache_dir: PathBuf,
+
+    /// Whether to include draft entries (`draft: true` in their frontmatter) and entries whose `publish_date` is in the future.
+    ///
+    /// Defaults to `true` in development builds and `false` in production builds. Can be set to `true` for preview deployments.
+    pub include_drafts: bool,
+
+    /// Whether links to Markdown files that don't match the entry of any page fail the build, instead of only logging a warning.
+    ///
+    /// Defaults to `false`.
+    pub strict_links: bool,
 }
 
 #[derive(Clone, Copy, PartialEq, Eq)]
@@ -193,6 +203,7 @@ impl BuildOptions {
             hashing_strategy: self.assets.hashing_strategy,
             output_dir: self.output_dir.clone(),
             intermediate_url_format: IntermediateUrlFormat::SourceHash,
+            cache_dir: self.cache_dir.clone(),
         }
     }
 }
@@ -266,6 +277,8 @@ impl Default for BuildOptions {
             sitemap: SitemapOptions::default(),
             incremental: true,
             cache_dir: default_cache_dir(),
+            include_drafts: is_dev(),
+            strict_links: false,
         }
     }
 }
@@ -1,32 +1,52 @@
 //! Core functions and structs to define the content sources of your website.
 //!
 //! Content sources represent the content of your website, such as articles, blog posts, etc. Then, content sources can be passed to [`coronate()`](crate::coronate), through the [`content_sources!`](crate::content_sources) macro, to be loaded.
-use std::{any::Any, path::PathBuf, sync::Arc};
+use std::{any::Any, future::Future, path::PathBuf, sync::Arc};
 
+use futures::future::{BoxFuture, FutureExt};
 use rustc_hash::FxHashMap;
+use tokio::runtime::RuntimeFlavor;
 
+pub mod data;
+mod deserialize;
+mod files;
 mod highlight;
+pub(crate) mod links;
 pub mod markdown;
+mod math;
+mod publication;
+mod reference;
 mod slugger;
+mod taxonomy;
 pub mod tracked;
 
 use crate::{
     assets::RouteAssets,
+    errors::{ContentError, ShortcodeError},
     route::{DynamicRouteContext, PageContext, PageParams},
 };
 pub use markdown::{
     components::{
-        BlockQuoteKind, BlockquoteComponent, CodeComponent, EmphasisComponent, HardBreakComponent,
-        HeadingComponent, HorizontalRuleComponent, ImageComponent, LinkComponent, LinkType,
-        ListComponent, ListItemComponent, ListType, MarkdownComponents, ParagraphComponent,
-        StrikethroughComponent, StrongComponent, TableAlignment, TableCellComponent,
+        BlockQuoteKind, BlockquoteComponent, CodeBlockComponent, CodeComponent,
+        DefinitionListComponent, DefinitionListDefinitionComponent, DefinitionListTitleComponent,
+        EmphasisComponent, FootnoteDefinitionComponent, FootnoteReferenceComponent,
+        HardBreakComponent, HeadingComponent, HorizontalRuleComponent, ImageComponent,
+        LinkComponent, LinkType, ListComponent, ListItemComponent, ListType, MarkdownComponents,
+        MathComponent, ParagraphComponent, StrikethroughComponent, StrongComponent,
+        SubscriptComponent, SuperscriptComponent, TableAlignment, TableCellComponent,
         TableComponent, TableHeadComponent, TableRowComponent, TaskListMarkerComponent,
     },
     *,
 };
 
-pub use highlight::{HighlightOptions, highlight_code};
-pub use tracked::TrackedContentSource;
+pub use data::{DataFormat, DataMode, DataOptions, glob_data, glob_data_with_options};
+pub use highlight::{
+    CodeFenceInfo, HighlightMode, HighlightOptions, highlight_code, highlight_stylesheet,
+};
+pub use math::{MathDisplay, render_math};
+pub use reference::{Reference, Referenceable};
+pub use taxonomy::{Taxonomy, TaxonomyPage, TaxonomyTerm};
+pub use tracked::{ContentQuery, TrackedContentSource};
 
 /// Helps implement a struct as a Markdown content entry.
 ///
@@ -69,19 +89,29 @@ pub use tracked::TrackedContentSource;
 ///   pub title: String,
 ///   pub content: String,
 ///   #[serde(skip)]
-///   __internal_headings: Vec<maudit::content::MarkdownHeading>
+///   __internal_headings: Vec<maudit::content::MarkdownHeading>,
+///   #[serde(skip)]
+///   __internal_summary: maudit::content::MarkdownSummary,
 /// }
 ///
 /// impl maudit::content::MarkdownContent for Article {
 ///   fn get_headings(&self) -> &Vec<maudit::content::MarkdownHeading> {
 ///     &self.__internal_headings
 ///   }
+///
+///   fn get_summary(&self) -> &maudit::content::MarkdownSummary {
+///     &self.__internal_summary
+///   }
 /// }
 ///
 /// impl maudit::content::InternalMarkdownContent for Article {
 ///   fn set_headings(&mut self, headings: Vec<maudit::content::MarkdownHeading>) {
 ///     self.__internal_headings = headings;
 ///   }
+///
+///   fn set_summary(&mut self, summary: maudit::content::MarkdownSummary) {
+///     self.__internal_summary = summary;
+///   }
 /// }
 /// ```
 pub use maudit_macros::markdown_entry;
@@ -121,16 +151,27 @@ pub use maudit_macros::markdown_entry;
 pub enum Dependency {
     /// A file on disk. Changes to this file will trigger a rebuild of pages that depend on this entry.
     File(PathBuf),
-    // TODO: Add other types of dependencies
+    /// Every file matching a glob pattern, e.g. `content/photos/**/*`. Adding, removing or changing a matching file will trigger a rebuild of pages that depend on this entry.
+    Glob(String),
+    /// An environment variable. Changes to its value, including it being set or unset, will trigger a rebuild of pages that depend on this entry.
+    Env(String),
+    /// An arbitrary value identified by `key`, such as the hash of an API response. Changes to `hash` will trigger a rebuild of pages that depend on this entry.
+    Custom { key: String, hash: String },
 }
 
 pub struct EntryInner<T> {
     pub id: String,
     render: OptionalContentRenderFn,
+    render_text: OptionalTextRenderFn,
     pub raw_content: Option<String>,
     data_loader: Option<DataLoadingFn<T>>,
     cached_data: std::sync::OnceLock<T>,
     pub dependencies: Vec<Dependency>,
+    /// Extracts what the data of the entry is derived from out of its raw content, for entries whose data is only part of it.
+    /// Used by incremental builds to tell content-only changes apart, see [`TrackedContentSource::query`].
+    data_fingerprint: Option<DataFingerprintFn>,
+    /// Drafts and entries scheduled for later, only included when [`BuildOptions::include_drafts`](crate::BuildOptions::include_drafts) is set.
+    draft: bool,
 }
 
 /// Helper type for easier usage of `EntryInner`. Content sources always return Arc-wrapped entries, but the user ergonomics of writing `Arc<EntryInner<T>>` is not great.
@@ -147,10 +188,13 @@ pub trait ContentEntry<T> {
         Arc::new(EntryInner {
             id,
             render,
+            render_text: None,
             raw_content,
             data_loader: None,
             cached_data: std::sync::OnceLock::from(data),
             dependencies,
+            data_fingerprint: None,
+            draft: false,
         })
     }
 
@@ -164,20 +208,39 @@ pub trait ContentEntry<T> {
         Arc::new(EntryInner {
             id,
             render,
+            render_text: None,
             raw_content,
             data_loader: Some(data_loader),
             cached_data: std::sync::OnceLock::new(),
             dependencies,
+            data_fingerprint: None,
+            draft: false,
         })
     }
+
+    /// Mark the entry as a draft or as scheduled for later, so that it's only included when
+    /// [`BuildOptions::include_drafts`](crate::BuildOptions::include_drafts) is set.
+    ///
+    /// Must be called on an entry that was just created, before it is cloned.
+    fn with_draft(self, draft: bool) -> Entry<T>;
 }
 
-impl<T> ContentEntry<T> for Entry<T> {}
+impl<T> ContentEntry<T> for Entry<T> {
+    fn with_draft(mut self, draft: bool) -> Entry<T> {
+        Arc::get_mut(&mut self)
+            .expect("with_draft() must be called before the entry is cloned")
+            .draft = draft;
+        self
+    }
+}
 
 /// Trait for contexts that can provide access to content
 pub trait ContentContext {
     fn content(&self) -> &ContentSources;
     fn assets(&mut self) -> &mut RouteAssets;
+
+    /// Record that an entry was read, for incremental build dependency tracking.
+    fn record_entry_read(&self, _source: &str, _id: &str) {}
 }
 
 impl ContentContext for PageContext<'_> {
@@ -185,6 +248,13 @@ impl ContentContext for PageContext<'_> {
         self.content
     }
 
+    fn record_entry_read(&self, source: &str, id: &str) {
+        self.access_log
+            .borrow_mut()
+            .entries_read
+            .push((source.to_string(), id.to_string()));
+    }
+
     fn assets(&mut self) -> &mut RouteAssets {
         self.assets
     }
@@ -195,6 +265,13 @@ impl ContentContext for DynamicRouteContext<'_> {
         self.content
     }
 
+    fn record_entry_read(&self, source: &str, id: &str) {
+        self.access_log
+            .borrow_mut()
+            .entries_read
+            .push((source.to_string(), id.to_string()));
+    }
+
     fn assets(&mut self) -> &mut RouteAssets {
         self.assets
     }
@@ -202,8 +279,12 @@ impl ContentContext for DynamicRouteContext<'_> {
 
 type DataLoadingFn<T> = Box<dyn Fn(&mut dyn ContentContext) -> T + Send + Sync>;
 
+type DataFingerprintFn = Box<dyn Fn(&str) -> String + Send + Sync>;
+
 type OptionalContentRenderFn =
     Option<Box<dyn Fn(&str, &mut crate::route::PageContext) -> String + Send + Sync>>;
+type OptionalTextRenderFn =
+    Option<Box<dyn Fn(&str, &TextOptions) -> Result<String, ShortcodeError> + Send + Sync>>;
 
 impl<T> EntryInner<T> {
     pub fn data<C: ContentContext>(&self, ctx: &mut C) -> &T {
@@ -219,6 +300,19 @@ impl<T> EntryInner<T> {
     pub fn render(&self, ctx: &mut PageContext) -> String {
         (self.render.as_ref().unwrap())(self.raw_content.as_ref().unwrap(), ctx)
     }
+
+    /// Render the content of this entry to plain text, see [`render_markdown_text`].
+    ///
+    /// Returns `None` for entries that can't be rendered to text, as only Markdown entries can.
+    pub fn render_text(&self, options: &TextOptions) -> Option<Result<String, ShortcodeError>> {
+        let render_text = self.render_text.as_ref()?;
+        Some(render_text(self.raw_content.as_ref()?, options))
+    }
+
+    /// Whether this entry is a draft or scheduled for later. Drafts are only part of content sources when [`BuildOptions::include_drafts`](crate::BuildOptions::include_drafts) is set.
+    pub fn is_draft(&self) -> bool {
+        self.draft
+    }
 }
 
 /// Represents an untyped content source.
@@ -306,12 +400,68 @@ impl ContentSources {
         &mut self.0
     }
 
-    pub fn init_all(&mut self) {
-        for source in &mut self.0 {
-            source.init();
+    /// Initialize every content source, reporting the errors of all sources at once.
+    ///
+    /// Sources are initialized concurrently on the current Tokio runtime when called from a multi-threaded one, blocking
+    /// the current thread until they're done, or on a new runtime otherwise. In async code, prefer [`init_all_async`](Self::init_all_async),
+    /// which doesn't block.
+    ///
+    /// Drafts are only included in development builds, see [`is_dev()`](crate::is_dev) and [`init_all_with_drafts`](Self::init_all_with_drafts).
+    pub fn init_all(&mut self) -> Result<(), ContentError> {
+        match tokio::runtime::Handle::try_current() {
+            Ok(handle) if handle.runtime_flavor() == RuntimeFlavor::MultiThread => {
+                tokio::task::block_in_place(|| handle.block_on(self.init_all_async()))
+            }
+            // A runtime can't be started, nor blocked on, from a thread already running one
+            Ok(_) => std::thread::scope(|scope| {
+                scope
+                    .spawn(|| self.init_all_on_new_runtime())
+                    .join()
+                    .unwrap_or_else(|err| std::panic::resume_unwind(err))
+            }),
+            Err(_) => self.init_all_on_new_runtime(),
         }
     }
 
+    fn init_all_on_new_runtime(&mut self) -> Result<(), ContentError> {
+        let runtime = tokio::runtime::Builder::new_multi_thread()
+            .enable_all()
+            .build()
+            .expect("Failed to create the runtime to initialize content sources");
+
+        runtime.block_on(self.init_all_async())
+    }
+
+    /// Initialize every content source concurrently, reporting the errors of all sources at once.
+    pub async fn init_all_async(&mut self) -> Result<(), ContentError> {
+        self.init_all_with_drafts(crate::is_dev()).await
+    }
+
+    /// Same as [`init_all_async`](Self::init_all_async), choosing whether to include drafts and entries scheduled for later,
+    /// like [`BuildOptions::include_drafts`](crate::BuildOptions::include_drafts) does.
+    pub async fn init_all_with_drafts(&mut self, include_drafts: bool) -> Result<(), ContentError> {
+        let errors =
+            futures::future::join_all(self.0.iter_mut().map(|source| source.init(include_drafts)))
+                .await
+                .into_iter()
+                .filter_map(Result::err)
+                .collect();
+
+        ContentError::aggregate(errors)?;
+        self.check_references()
+    }
+
+    /// Check that every [`Reference`] found while loading content points to an existing entry.
+    pub(crate) fn check_references(&self) -> Result<(), ContentError> {
+        let errors = self
+            .0
+            .iter()
+            .filter_map(|source| source.check_references(self).err())
+            .collect();
+
+        ContentError::aggregate(errors)
+    }
+
     pub fn get_untyped_source(&self, name: &str) -> &ContentSource<Untyped> {
         self.get_source::<Untyped>(name)
     }
@@ -342,24 +492,95 @@ impl ContentSources {
     }
 }
 
-type ContentSourceInitMethod<T> = Box<dyn Fn() -> Vec<Arc<EntryInner<T>>> + Send + Sync>;
+type LoadResult<T> = Result<Vec<Arc<EntryInner<T>>>, ContentError>;
+
+enum ContentSourceInitMethod<T> {
+    /// Synchronous loaders, which are run on Tokio's blocking threads so they don't hold up other sources.
+    Blocking(Arc<dyn Fn() -> LoadResult<T> + Send + Sync>),
+    Async(Box<dyn Fn() -> BoxFuture<'static, LoadResult<T>> + Send + Sync>),
+}
+
+/// Values a content source can be initialized from: either a list of entries, or the result of a loader that can fail.
+pub trait IntoContentEntries {
+    type Data;
+
+    fn into_entries(self) -> Result<Vec<Entry<Self::Data>>, ContentError>;
+}
+
+impl<T> IntoContentEntries for Vec<Entry<T>> {
+    type Data = T;
+
+    fn into_entries(self) -> Result<Vec<Entry<T>>, ContentError> {
+        Ok(self)
+    }
+}
+
+impl<T> IntoContentEntries for Result<Vec<Entry<T>>, ContentError> {
+    type Data = T;
+
+    fn into_entries(self) -> Result<Vec<Entry<T>>, ContentError> {
+        self
+    }
+}
 
 /// A source of content such as articles, blog posts, etc.
 pub struct ContentSource<T = Untyped> {
     pub name: String,
     pub entries: FxHashMap<String, Arc<EntryInner<T>>>,
-    pub(crate) init_method: ContentSourceInitMethod<T>,
+    init_method: ContentSourceInitMethod<T>,
+    /// References to other entries found while loading this source.
+    references: Vec<reference::LoadedReference>,
 }
 
 impl<T> ContentSource<T> {
-    pub fn new<P>(name: P, entries: ContentSourceInitMethod<T>) -> Self
+    pub fn new<P, F, E>(name: P, entries: F) -> Self
+    where
+        P: Into<String>,
+        F: Fn() -> E + Send + Sync + 'static,
+        E: IntoContentEntries<Data = T>,
+    {
+        Self {
+            name: name.into(),
+            entries: FxHashMap::default(),
+            init_method: ContentSourceInitMethod::Blocking(Arc::new(move || {
+                entries().into_entries()
+            })),
+            references: Vec::new(),
+        }
+    }
+
+    /// Create a content source from an async loader, for instance one fetching entries over the network.
+    ///
+    /// ## Example
+    /// ```rust
+    /// use maudit::content::{ContentEntry, ContentSource, Entry};
+    ///
+    /// async fn fetch_products() -> Vec<String> {
+    ///     vec!["chair".into(), "table".into()]
+    /// }
+    ///
+    /// let source = ContentSource::new_async("products", || async {
+    ///     fetch_products()
+    ///         .await
+    ///         .into_iter()
+    ///         .map(|name| Entry::create(name.clone(), None, None, name, vec![]))
+    ///         .collect::<Vec<_>>()
+    /// });
+    /// ```
+    pub fn new_async<P, F, Fut>(name: P, entries: F) -> Self
     where
         P: Into<String>,
+        F: Fn() -> Fut + Send + Sync + 'static,
+        Fut: Future + Send + 'static,
+        Fut::Output: IntoContentEntries<Data = T>,
     {
         Self {
             name: name.into(),
             entries: FxHashMap::default(),
-            init_method: entries,
+            init_method: ContentSourceInitMethod::Async(Box::new(move || {
+                entries().map(IntoContentEntries::into_entries).boxed()
+            })),
+            references: Vec::new(),
         }
     }
 
@@ -395,13 +616,17 @@ impl<T> ContentSource<T> {
 /// Used internally by Maudit and should not be implemented by the user.
 /// We expose it because it's implemented for [`ContentSource`], which is public.
 pub trait ContentSourceInternal: Send + Sync {
-    fn init(&mut self);
+    /// Load the entries of this source. Drafts are left out unless `include_drafts` is true.
+    fn init(&mut self, include_drafts: bool) -> BoxFuture<'_, Result<(), ContentError>>;
+    /// Check that the references found while loading this source point to existing entries.
+    fn check_references(&self, sources: &ContentSources) -> Result<(), ContentError>;
+    fn has_entry(&self, id: &str) -> bool;
     fn get_name(&self) -> &str;
     fn as_any(&self) -> &dyn Any; // Used for type checking at runtime
 
-    /// Return (entry_id, file_paths) for each entry.
-    /// Used by the incremental build system to track file hashes.
-    fn entry_file_info(&self) -> Vec<(String, Vec<PathBuf>)>;
+    /// Return (entry_id, dependencies) for each entry.
+    /// Used by the incremental build system to track dependency hashes.
+    fn entry_dependencies(&self) -> Vec<(String, Vec<Dependency>)>;
 
     /// Return (entry_id, raw_content) for entries that have raw content loaded.
     /// Used to hash content without re-reading files from disk.
@@ -409,16 +634,70 @@ pub trait ContentSourceInternal: Send + Sync {
         FxHashMap::default()
     }
 
+    /// Return the data fingerprint of an entry, if its data is derived from only part of its raw content.
+    /// Used to only re-render pages querying a source when the data of its entries change.
+    fn entry_data_fingerprint(&self, _id: &str) -> Option<String> {
+        None
+    }
+
     /// Return sorted entry IDs for structural change detection.
     fn entry_ids(&self) -> Vec<String>;
 }
 
 impl<T: 'static + Sync + Send> ContentSourceInternal for ContentSource<T> {
-    fn init(&mut self) {
-        self.entries = (self.init_method)()
-            .into_iter()
-            .map(|e| (e.id.clone(), e))
+    fn init(&mut self, include_drafts: bool) -> BoxFuture<'_, Result<(), ContentError>> {
+        async move {
+            let task = match &self.init_method {
+                ContentSourceInitMethod::Blocking(init) => {
+                    let init = init.clone();
+                    tokio::task::spawn_blocking(move || reference::collect_references(&*init))
+                }
+                ContentSourceInitMethod::Async(init) => {
+                    tokio::spawn(reference::CollectReferences::new(init()))
+                }
+            };
+            // Surface panics of loaders as if they happened on the current thread
+            let (entries, references) = task
+                .await
+                .unwrap_or_else(|err| std::panic::resume_unwind(err.into_panic()));
+            let entries = entries.map_err(|error| ContentError::SourceFailed {
+                name: self.name.clone(),
+                error: Box::new(error),
+            })?;
+
+            self.entries = entries
+                .into_iter()
+                .filter(|e| include_drafts || !e.draft)
+                .map(|e| (e.id.clone(), e))
+                .collect();
+            self.references = references;
+            Ok(())
+        }
+        .boxed()
+    }
+    fn check_references(&self, sources: &ContentSources) -> Result<(), ContentError> {
+        let errors = self
+            .references
+            .iter()
+            .filter(|reference| {
+                !sources.0.iter().any(|source| {
+                    source.get_name() == reference.source && source.has_entry(&reference.id)
+                })
+            })
+            .map(|reference| ContentError::DanglingReference {
+                path: reference.origin.clone(),
+                source_name: reference.source.to_string(),
+                id: reference.id.clone(),
+            })
             .collect();
+
+        ContentError::aggregate(errors).map_err(|error| ContentError::SourceFailed {
+            name: self.name.clone(),
+            error: Box::new(error),
+        })
+    }
+    fn has_entry(&self, id: &str) -> bool {
+        self.entries.contains_key(id)
     }
     fn get_name(&self) -> &str {
         &self.name
@@ -426,19 +705,10 @@ impl<T: 'static + Sync + Send> ContentSourceInternal for ContentSource<T> {
     fn as_any(&self) -> &dyn Any {
         self
     }
-    fn entry_file_info(&self) -> Vec<(String, Vec<PathBuf>)> {
+    fn entry_dependencies(&self) -> Vec<(String, Vec<Dependency>)> {
         self.entries
             .values()
-            .map(|e| {
-                let files = e
-                    .dependencies
-                    .iter()
-                    .map(|d| match d {
-                        Dependency::File(p) => p.clone(),
-                    })
-                    .collect();
-                (e.id.clone(), files)
-            })
+            .map(|e| (e.id.clone(), e.dependencies.clone()))
             .collect()
     }
     fn entry_raw_content(&self) -> FxHashMap<String, &str> {
@@ -447,9 +717,65 @@ impl<T: 'static + Sync + Send> ContentSourceInternal for ContentSource<T> {
             .filter_map(|e| e.raw_content.as_deref().map(|rc| (e.id.clone(), rc)))
             .collect()
     }
+    fn entry_data_fingerprint(&self, id: &str) -> Option<String> {
+        let entry = self.entries.get(id)?;
+        let fingerprint = entry.data_fingerprint.as_ref()?;
+        Some(fingerprint(entry.raw_content.as_deref()?))
+    }
     fn entry_ids(&self) -> Vec<String> {
         let mut ids: Vec<String> = self.entries.keys().cloned().collect();
         ids.sort();
         ids
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sources() -> ContentSources {
+        ContentSources::new(vec![Box::new(ContentSource::new_async(
+            "numbers",
+            || async {
+                tokio::task::yield_now().await;
+                vec![Entry::create("one".into(), None, None, 1, vec![])]
+            },
+        ))])
+    }
+
+    #[test]
+    fn test_init_all_outside_of_a_runtime() {
+        let mut sources = sources();
+        sources.init_all().unwrap();
+        assert!(
+            sources
+                .get_source::<i32>("numbers")
+                .get_entry_safe("one")
+                .is_some()
+        );
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_init_all_from_a_multi_threaded_runtime() {
+        let mut sources = sources();
+        sources.init_all().unwrap();
+        assert!(
+            sources
+                .get_source::<i32>("numbers")
+                .get_entry_safe("one")
+                .is_some()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_init_all_from_a_current_thread_runtime() {
+        let mut sources = sources();
+        sources.init_all().unwrap();
+        assert!(
+            sources
+                .get_source::<i32>("numbers")
+                .get_entry_safe("one")
+                .is_some()
+        );
+    }
+}
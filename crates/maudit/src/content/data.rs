@@ -0,0 +1,636 @@
+//! Content sources backed by structured data files (JSON, YAML, TOML and CSV).
+use std::path::{Path, PathBuf};
+
+use log::warn;
+use rustc_hash::FxHashMap;
+use serde::de::DeserializeOwned;
+
+use crate::{
+    content::{
+        ContentEntry, Dependency, Entry,
+        deserialize::{DeserializeFailure, deserialize_str, deserialize_toml_value},
+        files::{file_id, glob_files, read_file},
+        publication::Publication,
+        reference,
+    },
+    errors::ContentError,
+};
+
+/// Format of a data file, detected from its extension.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DataFormat {
+    /// `.json`
+    Json,
+    /// `.yaml` or `.yml`
+    Yaml,
+    /// `.toml`
+    Toml,
+    /// `.csv`
+    Csv,
+}
+
+impl DataFormat {
+    /// Detect the format of a file from its extension, returning `None` for unsupported files.
+    pub fn from_path(path: &Path) -> Option<Self> {
+        match path.extension()?.to_str()? {
+            "json" => Some(Self::Json),
+            "yaml" | "yml" => Some(Self::Yaml),
+            "toml" => Some(Self::Toml),
+            "csv" => Some(Self::Csv),
+            _ => None,
+        }
+    }
+}
+
+/// How the files matched by [`glob_data_with_options`] map to entries.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum DataMode {
+    /// Every file is a single entry, identified by its file name without the extension.
+    #[default]
+    FilePerEntry,
+    /// Every file holds a list of entries, identified by their [`DataOptions::id_field`] field.
+    /// Entries without one are identified by the file name and their position, e.g. `authors-0`.
+    ///
+    /// TOML documents can't be arrays, so their entries are read from the document's only array of tables.
+    Array,
+}
+
+/// Options for [`glob_data_with_options`].
+#[derive(Debug, Clone)]
+pub struct DataOptions {
+    pub mode: DataMode,
+    /// Field used as the ID of entries when reading files in [`DataMode::Array`] mode. Defaults to `id`.
+    pub id_field: String,
+}
+
+impl Default for DataOptions {
+    fn default() -> Self {
+        Self {
+            mode: DataMode::default(),
+            id_field: "id".to_string(),
+        }
+    }
+}
+
+impl DataOptions {
+    /// Options to read every file as a list of entries.
+    pub fn array() -> Self {
+        Self {
+            mode: DataMode::Array,
+            ..Default::default()
+        }
+    }
+}
+
+/// Glob for data files and return a vector of [`Entry`]s, one per file.
+///
+/// JSON, YAML, TOML and CSV files are supported, and are deserialized into `T`. CSV files always produce one entry per row.
+/// Data entries have no content to render, their data is accessed through [`Entry::data`](crate::content::EntryInner::data).
+///
+/// To read files containing a list of entries, use [`glob_data_with_options`] with [`DataOptions::array()`].
+///
+/// Every invalid file is reported in the returned [`ContentError`], alongside the position and field of the error.
+///
+/// ## Example
+/// ```rust
+/// use maudit::{coronate, content_sources, routes, BuildOptions, BuildOutput};
+/// use maudit::content::glob_data;
+/// use serde::Deserialize;
+///
+/// #[derive(Deserialize)]
+/// pub struct Author {
+///   pub name: String,
+///   pub bio: String,
+/// }
+///
+/// fn main() -> Result<BuildOutput, Box<dyn std::error::Error>> {
+///   coronate(
+///     routes![],
+///     content_sources![
+///       "authors" => glob_data::<Author>("data/authors/*.json")
+///     ],
+///     BuildOptions::default(),
+///   )
+/// }
+/// ```
+pub fn glob_data<T>(pattern: &str) -> Result<Vec<Entry<T>>, ContentError>
+where
+    T: DeserializeOwned + Send + Sync + 'static,
+{
+    glob_data_with_options(pattern, DataOptions::default())
+}
+
+/// Glob for data files and return a vector of [`Entry`]s.
+///
+/// See [`glob_data`] for the supported formats and [`DataOptions`] for how files map to entries.
+///
+/// ## Example
+/// ```rust
+/// use maudit::{coronate, content_sources, routes, BuildOptions, BuildOutput};
+/// use maudit::content::{glob_data_with_options, DataOptions};
+/// use serde::Deserialize;
+///
+/// #[derive(Deserialize)]
+/// pub struct NavItem {
+///   pub label: String,
+///   pub href: String,
+/// }
+///
+/// fn main() -> Result<BuildOutput, Box<dyn std::error::Error>> {
+///   coronate(
+///     routes![],
+///     content_sources![
+///       "nav" => glob_data_with_options::<NavItem>("data/nav.yaml", DataOptions::array())
+///     ],
+///     BuildOptions::default(),
+///   )
+/// }
+/// ```
+pub fn glob_data_with_options<T>(
+    pattern: &str,
+    options: DataOptions,
+) -> Result<Vec<Entry<T>>, ContentError>
+where
+    T: DeserializeOwned + Send + Sync + 'static,
+{
+    let mut entries = vec![];
+    let mut errors = vec![];
+    // Path of the file each ID was first read from
+    let mut seen_ids: FxHashMap<String, PathBuf> = FxHashMap::default();
+
+    for entry in glob_files(pattern)? {
+        let entry = match entry {
+            Ok(entry) => entry,
+            Err(err) => {
+                errors.push(err);
+                continue;
+            }
+        };
+
+        let Some(format) = DataFormat::from_path(&entry) else {
+            warn!(
+                "{} is not a supported data file (expected JSON, YAML, TOML or CSV)",
+                entry.display()
+            );
+            continue;
+        };
+
+        let (content, stem) =
+            match read_file(&entry).and_then(|content| Ok((content, file_id(&entry)?))) {
+                Ok(file) => file,
+                Err(err) => {
+                    errors.push(err);
+                    continue;
+                }
+            };
+
+        let single_entry = options.mode == DataMode::FilePerEntry && format != DataFormat::Csv;
+        if single_entry {
+            let data = match reference::with_origin(Some(&entry), || {
+                deserialize_str::<T>(&content, format)
+            }) {
+                Ok(data) => data,
+                Err(failure) => {
+                    errors.push(failure.into_data_error(entry));
+                    continue;
+                }
+            };
+
+            // Files of different formats, such as `ada.json` and `ada.yaml`, would otherwise shadow each other
+            if let Some(other_path) = seen_ids.get(&stem) {
+                errors.push(ContentError::DuplicateEntry {
+                    id: stem,
+                    path: entry,
+                    other_path: other_path.clone(),
+                });
+                continue;
+            }
+
+            let draft = deserialize_str::<Publication>(&content, format)
+                .unwrap_or_default()
+                .is_draft(chrono::Utc::now(), &entry);
+
+            seen_ids.insert(stem.clone(), entry.clone());
+            entries.push(
+                Entry::create(
+                    stem,
+                    None,
+                    Some(content),
+                    data,
+                    vec![Dependency::File(entry)],
+                )
+                .with_draft(draft),
+            );
+            continue;
+        }
+
+        let items = match reference::with_origin(Some(&entry), || {
+            parse_data_array::<T>(&content, format, &options.id_field)
+        }) {
+            Ok(items) => items,
+            Err(failure) => {
+                errors.push(failure.into_data_error(entry));
+                continue;
+            }
+        };
+
+        let publications = parse_data_array::<Publication>(&content, format, &options.id_field)
+            .unwrap_or_default();
+
+        for (index, (id, data)) in items.into_iter().enumerate() {
+            let draft = publications
+                .get(index)
+                .is_some_and(|(_, publication)| publication.is_draft(chrono::Utc::now(), &entry));
+            let id = id.unwrap_or_else(|| format!("{}-{}", stem, index));
+            if let Some(other_path) = seen_ids.insert(id.clone(), entry.clone()) {
+                warn!(
+                    "Duplicate data entry ID `{}` in {} (first found in {}), only the last entry with this ID will be kept",
+                    id,
+                    entry.display(),
+                    other_path.display()
+                );
+            }
+
+            // The raw content is the whole file, which would be duplicated for every entry,
+            // so the cache hashes the file from disk instead.
+            entries.push(
+                Entry::create(id, None, None, data, vec![Dependency::File(entry.clone())])
+                    .with_draft(draft),
+            );
+        }
+    }
+
+    ContentError::aggregate(errors).map(|_| entries)
+}
+
+/// Deserialize a data file holding a list of entries, returning each entry alongside its ID, if it has one.
+fn parse_data_array<T: DeserializeOwned>(
+    content: &str,
+    format: DataFormat,
+    id_field: &str,
+) -> Result<Vec<(Option<String>, T)>, DeserializeFailure> {
+    match format {
+        DataFormat::Json => {
+            // Deserializing the whole list at once keeps track of where errors are in the file
+            let items: Vec<T> = deserialize_str(content, format)?;
+            let values: Vec<serde_json::Value> = serde_json::from_str(content).unwrap_or_default();
+            let ids = values.iter().map(|item| {
+                item.get(id_field).and_then(|id| match id {
+                    serde_json::Value::String(s) => Some(s.clone()),
+                    serde_json::Value::Number(n) => Some(n.to_string()),
+                    _ => None,
+                })
+            });
+            Ok(ids.chain(std::iter::repeat(None)).zip(items).collect())
+        }
+        DataFormat::Yaml => {
+            let items: Vec<T> = deserialize_str(content, format)?;
+            let values: Vec<serde_yaml::Value> = serde_yaml::from_str(content).unwrap_or_default();
+            let ids = values.iter().map(|item| {
+                item.get(id_field).and_then(|id| match id {
+                    serde_yaml::Value::String(s) => Some(s.clone()),
+                    serde_yaml::Value::Number(n) => Some(n.to_string()),
+                    _ => None,
+                })
+            });
+            Ok(ids.chain(std::iter::repeat(None)).zip(items).collect())
+        }
+        DataFormat::Toml => {
+            let document: toml::Table = deserialize_str(content, format)?;
+            let mut arrays = document.into_iter().filter_map(|(key, value)| match value {
+                toml::Value::Array(items) => Some((key, items)),
+                _ => None,
+            });
+            let (Some((key, items)), None) = (arrays.next(), arrays.next()) else {
+                return Err(DeserializeFailure::message(
+                    "expected the document to contain exactly one array of tables",
+                ));
+            };
+            items
+                .into_iter()
+                .enumerate()
+                .map(|(index, item)| {
+                    let id = item.get(id_field).and_then(|id| match id {
+                        toml::Value::String(s) => Some(s.clone()),
+                        toml::Value::Integer(n) => Some(n.to_string()),
+                        _ => None,
+                    });
+                    let data = deserialize_toml_value(item).map_err(|mut failure| {
+                        let item_path = format!("{}[{}]", key, index);
+                        failure.field = Some(match failure.field {
+                            Some(field) => format!("{}.{}", item_path, field),
+                            None => item_path,
+                        });
+                        failure
+                    })?;
+                    Ok((id, data))
+                })
+                .collect()
+        }
+        DataFormat::Csv => {
+            let mut reader = csv::Reader::from_reader(content.as_bytes());
+            let headers = reader
+                .headers()
+                .map_err(|err| csv_failure(err, None))?
+                .clone();
+            let id_column = headers.iter().position(|header| header == id_field);
+            reader
+                .records()
+                .map(|record| {
+                    let record = record.map_err(|err| csv_failure(err, Some(&headers)))?;
+                    let id = id_column
+                        .and_then(|column| record.get(column))
+                        .map(str::to_string);
+                    let data = record
+                        .deserialize(Some(&headers))
+                        .map_err(|err| csv_failure(err, Some(&headers)))?;
+                    Ok((id, data))
+                })
+                .collect()
+        }
+    }
+}
+
+/// Convert a CSV error, using the name of the column as the field of the error.
+fn csv_failure(err: csv::Error, headers: Option<&csv::StringRecord>) -> DeserializeFailure {
+    let line = err.position().map(|position| position.line() as usize);
+    let (field, message) = match err.kind() {
+        csv::ErrorKind::Deserialize { err, .. } => (
+            err.field()
+                .and_then(|index| headers?.get(index as usize))
+                .map(str::to_string),
+            err.kind().to_string(),
+        ),
+        _ => (None, err.to_string()),
+    };
+
+    DeserializeFailure {
+        line,
+        column: None,
+        field,
+        expected: None,
+        message,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde::Deserialize;
+    use std::fs;
+
+    #[derive(Debug, Deserialize, PartialEq)]
+    struct Author {
+        name: String,
+        age: u32,
+    }
+
+    fn pattern(dir: &Path, glob: &str) -> String {
+        dir.join(glob).to_string_lossy().to_string()
+    }
+
+    /// Data entries are created eagerly, so their data can be read without a context.
+    fn data_of<'a>(entries: &'a [Entry<Author>], id: &str) -> Option<&'a Author> {
+        entries.iter().find(|e| e.id == id)?.cached_data.get()
+    }
+
+    #[test]
+    fn test_file_per_entry_formats() {
+        let dir = tempfile::tempdir().unwrap();
+        fs::write(dir.path().join("ada.json"), r#"{"name": "Ada", "age": 36}"#).unwrap();
+        fs::write(dir.path().join("grace.yaml"), "name: Grace\nage: 85\n").unwrap();
+        fs::write(dir.path().join("alan.toml"), "name = \"Alan\"\nage = 41\n").unwrap();
+        fs::write(dir.path().join("notes.txt"), "not data").unwrap();
+
+        let entries = glob_data::<Author>(&pattern(dir.path(), "*")).unwrap();
+
+        assert_eq!(entries.len(), 3);
+        assert_eq!(
+            data_of(&entries, "ada"),
+            Some(&Author {
+                name: "Ada".to_string(),
+                age: 36
+            })
+        );
+        assert_eq!(data_of(&entries, "grace").unwrap().age, 85);
+        assert_eq!(data_of(&entries, "alan").unwrap().name, "Alan");
+        assert!(entries.iter().all(|e| e.render.is_none()));
+        assert!(matches!(
+            &entries[0].dependencies[..],
+            [Dependency::File(_)]
+        ));
+    }
+
+    #[test]
+    fn test_array_mode_uses_id_field() {
+        let dir = tempfile::tempdir().unwrap();
+        fs::write(
+            dir.path().join("authors.json"),
+            r#"[{"id": "ada", "name": "Ada", "age": 36}, {"id": 2, "name": "Grace", "age": 85}]"#,
+        )
+        .unwrap();
+
+        let entries =
+            glob_data_with_options::<Author>(&pattern(dir.path(), "*.json"), DataOptions::array())
+                .unwrap();
+
+        assert_eq!(entries.len(), 2);
+        assert_eq!(data_of(&entries, "ada").unwrap().name, "Ada");
+        assert_eq!(data_of(&entries, "2").unwrap().name, "Grace");
+        // Every entry depends on the file it was read from
+        for entry in &entries {
+            assert!(
+                matches!(&entry.dependencies[..], [Dependency::File(p)] if p.ends_with("authors.json"))
+            );
+            assert!(entry.raw_content.is_none());
+        }
+    }
+
+    #[test]
+    fn test_array_mode_falls_back_to_position() {
+        let dir = tempfile::tempdir().unwrap();
+        fs::write(
+            dir.path().join("authors.yaml"),
+            "- name: Ada\n  age: 36\n- name: Grace\n  age: 85\n",
+        )
+        .unwrap();
+
+        let entries =
+            glob_data_with_options::<Author>(&pattern(dir.path(), "*.yaml"), DataOptions::array())
+                .unwrap();
+
+        assert_eq!(data_of(&entries, "authors-0").unwrap().name, "Ada");
+        assert_eq!(data_of(&entries, "authors-1").unwrap().name, "Grace");
+    }
+
+    #[test]
+    fn test_drafts_and_scheduled_entries() {
+        let dir = tempfile::tempdir().unwrap();
+        fs::write(
+            dir.path().join("ada.yaml"),
+            "name: Ada\nage: 36\ndraft: true\n",
+        )
+        .unwrap();
+        fs::write(
+            dir.path().join("grace.json"),
+            r#"{"name": "Grace", "age": 85, "draft": "no"}"#,
+        )
+        .unwrap();
+        let entries = glob_data::<Author>(&pattern(dir.path(), "*")).unwrap();
+        let is_draft = |entries: &[Entry<Author>], id: &str| {
+            entries.iter().find(|e| e.id == id).unwrap().is_draft()
+        };
+        assert!(is_draft(&entries, "ada"));
+        // Values that can't be read don't make an entry a draft
+        assert!(!is_draft(&entries, "grace"));
+
+        fs::write(
+            dir.path().join("authors.csv"),
+            "id,name,age,publish_date\nalan,Alan,41,2999-01-01\nlinus,Linus,55,\n",
+        )
+        .unwrap();
+        let entries =
+            glob_data_with_options::<Author>(&pattern(dir.path(), "*.csv"), DataOptions::array())
+                .unwrap();
+        assert!(is_draft(&entries, "alan"));
+        assert!(!is_draft(&entries, "linus"));
+    }
+
+    #[test]
+    fn test_array_mode_toml_array_of_tables() {
+        let dir = tempfile::tempdir().unwrap();
+        fs::write(
+            dir.path().join("authors.toml"),
+            "[[authors]]\nid = \"ada\"\nname = \"Ada\"\nage = 36\n\n[[authors]]\nid = \"alan\"\nname = \"Alan\"\nage = 41\n",
+        )
+        .unwrap();
+
+        let entries =
+            glob_data_with_options::<Author>(&pattern(dir.path(), "*.toml"), DataOptions::array())
+                .unwrap();
+
+        assert_eq!(entries.len(), 2);
+        assert_eq!(data_of(&entries, "alan").unwrap().age, 41);
+    }
+
+    #[test]
+    fn test_csv_rows_are_entries() {
+        let dir = tempfile::tempdir().unwrap();
+        fs::write(
+            dir.path().join("authors.csv"),
+            "id,name,age\nada,Ada,36\ngrace,Grace,85\n",
+        )
+        .unwrap();
+
+        // CSV files are read row by row, even with the default options
+        let entries = glob_data::<Author>(&pattern(dir.path(), "*.csv")).unwrap();
+
+        assert_eq!(entries.len(), 2);
+        assert_eq!(
+            data_of(&entries, "grace"),
+            Some(&Author {
+                name: "Grace".to_string(),
+                age: 85
+            })
+        );
+    }
+
+    #[test]
+    fn test_invalid_files_are_all_reported() {
+        let dir = tempfile::tempdir().unwrap();
+        fs::write(dir.path().join("ada.json"), r#"{"name": "Ada", "age": 36}"#).unwrap();
+        fs::write(dir.path().join("broken.json"), r#"{"name": "Grace"}"#).unwrap();
+        fs::write(dir.path().join("wrong.yaml"), "name: Alan\nage: old\n").unwrap();
+
+        let Err(ContentError::Multiple(errors)) = glob_data::<Author>(&pattern(dir.path(), "*"))
+        else {
+            panic!("expected every invalid file to be reported");
+        };
+
+        assert_eq!(errors.len(), 2);
+        let ContentError::InvalidData(invalid) = &errors[0] else {
+            panic!("expected an InvalidData error");
+        };
+        assert!(invalid.path.as_ref().unwrap().ends_with("broken.json"));
+        assert_eq!(invalid.field.as_deref(), Some("age"));
+        assert_eq!(invalid.line, Some(1));
+
+        let ContentError::InvalidData(invalid) = &errors[1] else {
+            panic!("expected an InvalidData error");
+        };
+        assert_eq!(invalid.field.as_deref(), Some("age"));
+        assert_eq!(invalid.expected.as_deref(), Some("u32"));
+        assert_eq!(invalid.line, Some(2));
+    }
+
+    #[test]
+    fn test_duplicate_ids_are_reported() {
+        let dir = tempfile::tempdir().unwrap();
+        fs::write(dir.path().join("ada.json"), r#"{"name": "Ada", "age": 36}"#).unwrap();
+        fs::write(dir.path().join("ada.yaml"), "name: Ada\nage: 37\n").unwrap();
+        fs::write(dir.path().join("grace.yaml"), "name: Grace\nage: 85\n").unwrap();
+
+        let Err(ContentError::DuplicateEntry {
+            id,
+            path,
+            other_path,
+        }) = glob_data::<Author>(&pattern(dir.path(), "*"))
+        else {
+            panic!("expected a DuplicateEntry error");
+        };
+        assert_eq!(id, "ada");
+        let mut paths = [path, other_path].map(|path| path.file_name().unwrap().to_owned());
+        paths.sort();
+        assert_eq!(paths, ["ada.json", "ada.yaml"]);
+    }
+
+    #[test]
+    fn test_unreadable_files_are_reported() {
+        let dir = tempfile::tempdir().unwrap();
+        fs::write(dir.path().join("ada.json"), r#"{"name": "Ada", "age": 36}"#).unwrap();
+        fs::write(dir.path().join("binary.json"), [0xff, 0xfe]).unwrap();
+
+        let Err(ContentError::ReadFailed { path, .. }) =
+            glob_data::<Author>(&pattern(dir.path(), "*"))
+        else {
+            panic!("expected a ReadFailed error");
+        };
+        assert!(path.ends_with("binary.json"));
+
+        assert!(matches!(
+            glob_data::<Author>(&pattern(dir.path(), "[*")),
+            Err(ContentError::InvalidPattern { .. })
+        ));
+    }
+
+    #[test]
+    fn test_invalid_array_entries_report_their_position() {
+        let dir = tempfile::tempdir().unwrap();
+        fs::write(
+            dir.path().join("authors.csv"),
+            "id,name,age\nada,Ada,36\ngrace,Grace,old\n",
+        )
+        .unwrap();
+
+        let Err(ContentError::InvalidData(invalid)) =
+            glob_data::<Author>(&pattern(dir.path(), "*.csv"))
+        else {
+            panic!("expected an InvalidData error");
+        };
+        assert_eq!(invalid.line, Some(3));
+        assert_eq!(invalid.field.as_deref(), Some("age"));
+
+        fs::write(
+            dir.path().join("authors.toml"),
+            "[[authors]]\nname = \"Ada\"\nage = 36\n\n[[authors]]\nname = \"Alan\"\n",
+        )
+        .unwrap();
+
+        let Err(ContentError::InvalidData(invalid)) =
+            glob_data_with_options::<Author>(&pattern(dir.path(), "*.toml"), DataOptions::array())
+        else {
+            panic!("expected an InvalidData error");
+        };
+        assert_eq!(invalid.field.as_deref(), Some("authors[1].age"));
+    }
+}
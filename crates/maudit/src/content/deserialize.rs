@@ -0,0 +1,258 @@
+//! Deserialization of frontmatter and data files, keeping track of where errors happen.
+use std::path::{Path, PathBuf};
+
+use serde::de::DeserializeOwned;
+
+use crate::{
+    content::DataFormat,
+    errors::{ContentError, InvalidContent},
+};
+
+/// Details of a failed deserialization, turned into a [`ContentError`] once the caller knows
+/// which file the content comes from.
+#[derive(Debug)]
+pub(crate) struct DeserializeFailure {
+    /// Line of the error in the deserialized text (1-based).
+    pub line: Option<usize>,
+    /// Column of the error in the deserialized text (1-based).
+    pub column: Option<usize>,
+    pub field: Option<String>,
+    pub expected: Option<String>,
+    pub message: String,
+}
+
+impl DeserializeFailure {
+    fn new(
+        path: &serde_path_to_error::Path,
+        message: String,
+        line: Option<usize>,
+        column: Option<usize>,
+    ) -> Self {
+        let path = path.to_string();
+        // serde_yaml prefixes its messages with the path to the field, which is reported separately
+        let message = message
+            .strip_prefix(&format!("{}: ", path))
+            .map_or(message.clone(), str::to_string);
+        // serde reports missing fields on the struct that's missing them, not on the field itself
+        let missing_field = message
+            .strip_prefix("missing field `")
+            .and_then(|rest| rest.split('`').next());
+        let field = match (path.as_str(), missing_field) {
+            (".", None) => None,
+            (".", Some(field)) => Some(field.to_string()),
+            (path, Some(field)) => Some(format!("{}.{}", path, field)),
+            (path, None) => Some(path.to_string()),
+        };
+
+        let expected = message
+            .split_once(", expected ")
+            .map(|(_, expected)| expected.to_string());
+
+        Self {
+            line,
+            column,
+            field,
+            expected,
+            message,
+        }
+    }
+
+    pub fn message(message: impl Into<String>) -> Self {
+        Self {
+            line: None,
+            column: None,
+            field: None,
+            expected: None,
+            message: message.into(),
+        }
+    }
+
+    /// Convert into an error about the frontmatter of a Markdown file, where `first_line` is
+    /// the line of the file the frontmatter starts at.
+    pub fn into_frontmatter_error(self, path: Option<&Path>, first_line: usize) -> ContentError {
+        let line = self.line.map(|line| line + first_line - 1);
+        ContentError::InvalidFrontmatter(Box::new(
+            self.into_invalid_content(path.map(Path::to_path_buf), line),
+        ))
+    }
+
+    pub fn into_data_error(self, path: PathBuf) -> ContentError {
+        let line = self.line;
+        ContentError::InvalidData(Box::new(self.into_invalid_content(Some(path), line)))
+    }
+
+    fn into_invalid_content(self, path: Option<PathBuf>, line: Option<usize>) -> InvalidContent {
+        InvalidContent {
+            path,
+            line,
+            column: self.column,
+            field: self.field,
+            expected: self.expected,
+            message: self.message,
+        }
+    }
+}
+
+/// Deserialize a JSON, YAML or TOML document.
+pub(crate) fn deserialize_str<T: DeserializeOwned>(
+    text: &str,
+    format: DataFormat,
+) -> Result<T, DeserializeFailure> {
+    match format {
+        DataFormat::Json => {
+            let mut deserializer = serde_json::Deserializer::from_str(text);
+            serde_path_to_error::deserialize(&mut deserializer).map_err(|err| {
+                let inner = err.inner();
+                let (line, column) = (inner.line(), inner.column());
+                let message = strip_location(&inner.to_string()).to_string();
+                DeserializeFailure::new(err.path(), message, Some(line), Some(column))
+            })
+        }
+        DataFormat::Yaml => serde_path_to_error::deserialize(serde_yaml::Deserializer::from_str(
+            text,
+        ))
+        .map_err(|err| {
+            let location = err.inner().location();
+            let message = strip_location(&err.inner().to_string()).to_string();
+            DeserializeFailure::new(
+                err.path(),
+                message,
+                location.as_ref().map(|l| l.line()),
+                location.as_ref().map(|l| l.column()),
+            )
+        }),
+        DataFormat::Toml => serde_path_to_error::deserialize(toml::Deserializer::new(text))
+            .map_err(|err| {
+                let (line, column) = err
+                    .inner()
+                    .span()
+                    .map(|span| line_and_column(text, span.start))
+                    .unzip();
+                DeserializeFailure::new(err.path(), err.inner().message().to_string(), line, column)
+            }),
+        DataFormat::Csv => Err(DeserializeFailure::message(
+            "CSV files can only be read as a list of entries",
+        )),
+    }
+}
+
+/// Deserialize an already parsed TOML value, for which positions are no longer known.
+pub(crate) fn deserialize_toml_value<T: DeserializeOwned>(
+    value: toml::Value,
+) -> Result<T, DeserializeFailure> {
+    serde_path_to_error::deserialize(value).map_err(|err| {
+        DeserializeFailure::new(err.path(), err.inner().message().to_string(), None, None)
+    })
+}
+
+/// Remove the ` at line X column Y` suffix some error messages include, since the
+/// position is reported separately.
+fn strip_location(message: &str) -> &str {
+    message
+        .rfind(" at line ")
+        .map_or(message, |index| &message[..index])
+}
+
+/// Compute the (1-based) line and column of a byte offset in a text.
+pub(crate) fn line_and_column(text: &str, offset: usize) -> (usize, usize) {
+    let before = &text[..offset.min(text.len())];
+    let line = before.matches('\n').count() + 1;
+    let column = before
+        .rfind('\n')
+        .map_or(before, |newline| &before[newline + 1..])
+        .chars()
+        .count()
+        + 1;
+    (line, column)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[allow(dead_code)]
+    #[derive(Debug, serde::Deserialize)]
+    struct Article {
+        title: String,
+        author: Author,
+    }
+
+    #[allow(dead_code)]
+    #[derive(Debug, serde::Deserialize)]
+    struct Author {
+        name: String,
+        age: u32,
+    }
+
+    #[test]
+    fn test_yaml_invalid_type() {
+        let failure = deserialize_str::<Article>(
+            "title: Hello\nauthor:\n  name: Ada\n  age: old\n",
+            DataFormat::Yaml,
+        )
+        .unwrap_err();
+
+        assert_eq!(failure.field.as_deref(), Some("author.age"));
+        assert_eq!(failure.expected.as_deref(), Some("u32"));
+        assert_eq!(failure.line, Some(4));
+        assert_eq!(failure.column, Some(8));
+        assert!(!failure.message.contains("at line"));
+    }
+
+    #[test]
+    fn test_missing_field() {
+        let failure =
+            deserialize_str::<Article>("title: Hello\nauthor:\n  name: Ada\n", DataFormat::Yaml)
+                .unwrap_err();
+        assert_eq!(failure.field.as_deref(), Some("author.age"));
+
+        let failure = deserialize_str::<Article>("{}", DataFormat::Json).unwrap_err();
+        assert_eq!(failure.field.as_deref(), Some("title"));
+        assert_eq!(failure.expected, None);
+    }
+
+    #[test]
+    fn test_toml_invalid_type() {
+        let failure = deserialize_str::<Article>(
+            "title = 1\n\n[author]\nname = \"Ada\"\nage = 36\n",
+            DataFormat::Toml,
+        )
+        .unwrap_err();
+
+        assert_eq!(failure.field.as_deref(), Some("title"));
+        assert_eq!(failure.expected.as_deref(), Some("a string"));
+        assert_eq!(failure.line, Some(1));
+    }
+
+    #[test]
+    fn test_json_invalid_type() {
+        let failure = deserialize_str::<Article>(
+            "{\n  \"title\": \"Hello\",\n  \"author\": { \"name\": 1, \"age\": 36 }\n}",
+            DataFormat::Json,
+        )
+        .unwrap_err();
+
+        assert_eq!(failure.field.as_deref(), Some("author.name"));
+        assert_eq!(failure.line, Some(3));
+    }
+
+    #[test]
+    fn test_frontmatter_error_is_offset_to_file_lines() {
+        let failure = deserialize_str::<Article>("title: [1, 2]\n", DataFormat::Yaml).unwrap_err();
+        let error = failure.into_frontmatter_error(Some(Path::new("post.md")), 2);
+
+        let ContentError::InvalidFrontmatter(invalid) = &error else {
+            panic!("expected an InvalidFrontmatter error");
+        };
+        assert_eq!(invalid.line, Some(2));
+        assert!(error.to_string().starts_with("post.md:2:"));
+    }
+
+    #[test]
+    fn test_line_and_column() {
+        let text = "first\nsecond line\nthird";
+        assert_eq!(line_and_column(text, 0), (1, 1));
+        assert_eq!(line_and_column(text, 13), (2, 8));
+        assert_eq!(line_and_column(text, text.len()), (3, 6));
+    }
+}
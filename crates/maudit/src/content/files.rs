@@ -0,0 +1,45 @@
+//! Reading the files matched by the glob patterns of loaders.
+use std::path::{Path, PathBuf};
+
+use glob::glob as glob_fs;
+
+use crate::errors::ContentError;
+
+/// Glob for files, reporting an invalid pattern or an unreadable path as an error instead of panicking.
+pub(crate) fn glob_files(
+    pattern: &str,
+) -> Result<impl Iterator<Item = Result<PathBuf, ContentError>>, ContentError> {
+    // TODO: `glob` is kinda slow, but alternatives are either unmaintained, have annoying bugs or not faster.
+    let paths = glob_fs(pattern).map_err(|source| ContentError::InvalidPattern {
+        pattern: pattern.to_string(),
+        source,
+    })?;
+
+    Ok(paths.map(|path| {
+        path.map_err(|err| ContentError::ReadFailed {
+            path: err.path().to_path_buf(),
+            source: err.into_error(),
+        })
+    }))
+}
+
+/// ID of the entry read from a file, its name without the extension.
+pub(crate) fn file_id(path: &Path) -> Result<String, ContentError> {
+    path.file_stem()
+        .and_then(|stem| stem.to_str())
+        .map(str::to_string)
+        .ok_or_else(|| ContentError::ReadFailed {
+            path: path.to_path_buf(),
+            source: std::io::Error::new(
+                std::io::ErrorKind::InvalidData,
+                "the file name is not valid UTF-8",
+            ),
+        })
+}
+
+pub(crate) fn read_file(path: &Path) -> Result<String, ContentError> {
+    std::fs::read_to_string(path).map_err(|source| ContentError::ReadFailed {
+        path: path.to_path_buf(),
+        source,
+    })
+}
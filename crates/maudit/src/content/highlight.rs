@@ -1,61 +1,314 @@
-use core::panic;
-use std::sync::OnceLock;
+use std::{
+    ops::RangeInclusive,
+    path::{Path, PathBuf},
+    sync::{Arc, Mutex, OnceLock},
+};
+
+use rustc_hash::FxHashMap;
 use syntect::{
-    Error,
     easy::HighlightLines,
-    highlighting::ThemeSet,
-    html::{IncludeBackground, styled_line_to_highlighted_html},
-    parsing::SyntaxSet,
+    highlighting::{Theme, ThemeSet},
+    html::{
+        ClassStyle, IncludeBackground, css_for_theme_with_class_style,
+        line_tokens_to_classed_spans, styled_line_to_highlighted_html,
+    },
+    parsing::{
+        ParseState, Scope, ScopeStack, SyntaxDefinition, SyntaxReference, SyntaxSet,
+        SyntaxSetBuilder,
+    },
     util::LinesWithEndings,
 };
 
-static SYNTAX_SET: OnceLock<SyntaxSet> = OnceLock::new();
-static THEME_SET: OnceLock<ThemeSet> = OnceLock::new();
+use crate::{build::cache::hash_bytes, errors::HighlightError};
+
+/// Caches of the values loaded from files, keyed by the additional files they were loaded from.
+type Cache<K, V> = OnceLock<Mutex<FxHashMap<K, Arc<V>>>>;
+
+static SYNTAX_SET: Cache<Vec<PathBuf>, SyntaxSet> = OnceLock::new();
+static THEME_SET: Cache<Vec<PathBuf>, ThemeSet> = OnceLock::new();
+/// Themes by name or path, as themes that aren't part of a theme set are loaded from their path.
+static THEMES: Cache<(String, Vec<PathBuf>), Theme> = OnceLock::new();
+/// Stylesheets already written to disk, by cache directory and theme pair, as every page using them includes them.
+static STYLESHEETS: Cache<(PathBuf, String, Option<String>, Vec<PathBuf>), PathBuf> =
+    OnceLock::new();
+
+/// Prefix of the classes used by [`HighlightMode::Classes`], to avoid conflicts with the classes of the website.
+const CLASS_STYLE: ClassStyle = ClassStyle::SpacedPrefixed { prefix: "hl-" };
 
-fn get_syntax_set() -> &'static SyntaxSet {
-    SYNTAX_SET.get_or_init(SyntaxSet::load_defaults_newlines)
+/// How highlighted code is styled.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum HighlightMode {
+    /// Colors are set using inline `style` attributes.
+    #[default]
+    Inline,
+    /// Tokens get CSS classes, styled by a stylesheet generated from the theme, see [`highlight_stylesheet`].
+    ///
+    /// Compatible with Content Security Policies forbidding inline styles, and allows switching to a dark theme.
+    Classes,
 }
 
-fn get_theme_set() -> &'static ThemeSet {
-    THEME_SET.get_or_init(ThemeSet::load_defaults)
+/// Get a value from a cache, loading it the first time it's requested.
+fn get_cached<K, V>(
+    cache: &Cache<K, V>,
+    key: K,
+    load: impl FnOnce(&K) -> Result<V, HighlightError>,
+) -> Result<Arc<V>, HighlightError>
+where
+    K: Eq + std::hash::Hash,
+{
+    let mut cache = cache.get_or_init(Default::default).lock().unwrap();
+    if let Some(value) = cache.get(&key) {
+        return Ok(value.clone());
+    }
+
+    let value = Arc::new(load(&key)?);
+    cache.insert(key, value.clone());
+    Ok(value)
 }
 
-pub fn highlight_code(content: &str, options: &HighlightOptions) -> Result<String, Error> {
-    let ss = get_syntax_set();
-    let ts = get_theme_set();
+/// Syntect's default syntaxes, along with the `.sublime-syntax` files or directories of files given.
+fn get_syntax_set(syntaxes: &[PathBuf]) -> Result<Arc<SyntaxSet>, HighlightError> {
+    get_cached(&SYNTAX_SET, syntaxes.to_vec(), |syntaxes| {
+        let defaults = SyntaxSet::load_defaults_newlines();
+        if syntaxes.is_empty() {
+            return Ok(defaults);
+        }
+
+        let mut builder = defaults.into_builder();
+        for path in syntaxes {
+            add_syntaxes(&mut builder, path).map_err(|message| {
+                HighlightError::SyntaxLoadFailed {
+                    path: path.clone(),
+                    message,
+                }
+            })?;
+        }
+        Ok(builder.build())
+    })
+}
+
+fn add_syntaxes(builder: &mut SyntaxSetBuilder, path: &Path) -> Result<(), String> {
+    if path.is_dir() {
+        return builder
+            .add_from_folder(path, true)
+            .map_err(|err| err.to_string());
+    }
+
+    let source = std::fs::read_to_string(path).map_err(|err| err.to_string())?;
+    let name = path.file_stem().map(|stem| stem.to_string_lossy());
+    let syntax = SyntaxDefinition::load_from_str(&source, true, name.as_deref())
+        .map_err(|err| err.to_string())?;
+    builder.add(syntax);
+    Ok(())
+}
+
+/// Syntect's default themes, along with the `.tmTheme` files or directories of files given, named after their file.
+fn get_theme_set(themes: &[PathBuf]) -> Result<Arc<ThemeSet>, HighlightError> {
+    get_cached(&THEME_SET, themes.to_vec(), |themes| {
+        let mut theme_set = ThemeSet::load_defaults();
+        for path in themes {
+            let load_failed = |err: syntect::LoadingError| HighlightError::ThemeLoadFailed {
+                path: path.clone(),
+                message: err.to_string(),
+            };
+
+            if path.is_dir() {
+                let loaded = ThemeSet::load_from_folder(path).map_err(load_failed)?;
+                theme_set.themes.extend(loaded.themes);
+            } else {
+                let theme = ThemeSet::get_theme(path).map_err(load_failed)?;
+                let name = path.file_stem().unwrap_or_default().to_string_lossy();
+                theme_set.themes.insert(name.into_owned(), theme);
+            }
+        }
+        Ok(theme_set)
+    })
+}
 
-    let syntax = ss
-        .find_syntax_by_token(&options.language)
+/// Get a theme by name, or load it from a `.tmTheme` file if there's no theme with this name.
+fn get_theme(theme_path: &str, themes: &[PathBuf]) -> Result<Arc<Theme>, HighlightError> {
+    get_cached(
+        &THEMES,
+        (theme_path.to_string(), themes.to_vec()),
+        |(theme_path, themes)| {
+            if let Some(theme) = get_theme_set(themes)?.themes.get(theme_path) {
+                return Ok(theme.clone());
+            }
+
+            if !Path::new(theme_path).is_file() {
+                return Err(HighlightError::ThemeNotFound(theme_path.clone()));
+            }
+            ThemeSet::get_theme(theme_path).map_err(|err| HighlightError::ThemeLoadFailed {
+                path: PathBuf::from(theme_path),
+                message: err.to_string(),
+            })
+        },
+    )
+}
+
+/// The syntaxes and themes of some options, loaded once and shared by every code block highlighted with them.
+pub(crate) struct Highlighter {
+    pub(crate) options: HighlightOptions,
+    syntax_set: Arc<SyntaxSet>,
+    theme: Arc<Theme>,
+    dark_theme: Option<Arc<Theme>>,
+}
+
+impl Highlighter {
+    /// Load the syntaxes and themes of the options, to report any missing file before anything is highlighted.
+    pub(crate) fn new(options: HighlightOptions) -> Result<Self, HighlightError> {
+        let syntax_set = get_syntax_set(&options.syntaxes)?;
+        let theme = get_theme(&options.theme_path, &options.themes)?;
+        let dark_theme = options
+            .dark_theme_path
+            .as_deref()
+            .map(|dark_theme_path| get_theme(dark_theme_path, &options.themes))
+            .transpose()?;
+
+        Ok(Self {
+            options,
+            syntax_set,
+            theme,
+            dark_theme,
+        })
+    }
+
+    fn highlight_lines(
+        &self,
+        content: &str,
+        language: &str,
+    ) -> Result<Vec<String>, HighlightError> {
+        let theme = (self.options.mode == HighlightMode::Inline).then_some(self.theme.as_ref());
+        highlight_lines(content, language, &self.syntax_set, theme)
+    }
+}
+
+fn find_syntax<'a>(ss: &'a SyntaxSet, language: &str, content: &str) -> &'a SyntaxReference {
+    ss.find_syntax_by_token(language)
         // Maybe token is enough, looking around at other users of Syntect, it seems like they often just use by_token, not sure.
-        .or_else(|| ss.find_syntax_by_name(&options.language))
-        .or_else(|| ss.find_syntax_by_extension(&options.language))
+        .or_else(|| ss.find_syntax_by_name(language))
+        .or_else(|| ss.find_syntax_by_extension(language))
         .or_else(|| ss.find_syntax_by_first_line(content))
-        .unwrap_or_else(|| ss.find_syntax_plain_text());
-
-    let theme = match ts.themes.get(&options.theme_path) {
-        Some(theme) => theme,
-        None => &match ThemeSet::get_theme(&options.theme_path) {
-            Ok(theme) => theme,
-            Err(_) => panic!(
-                "Theme '{}' not found in default themes and could not be loaded from file.",
-                options.theme_path
-            ),
-        },
+        .unwrap_or_else(|| ss.find_syntax_plain_text())
+}
+
+pub fn highlight_code(content: &str, options: &HighlightOptions) -> Result<String, HighlightError> {
+    let ss = get_syntax_set(&options.syntaxes)?;
+    // With classes, the theme is only used by the stylesheet
+    let theme = match options.mode {
+        HighlightMode::Inline => Some(get_theme(&options.theme_path, &options.themes)?),
+        HighlightMode::Classes => None,
+    };
+    Ok(highlight_lines(content, &options.language, &ss, theme.as_deref())?.concat())
+}
+
+/// Highlight code line by line, using inline styles from the theme, or classes without one. Every line is valid HTML
+/// on its own, and can be wrapped in an element.
+fn highlight_lines(
+    content: &str,
+    language: &str,
+    ss: &SyntaxSet,
+    theme: Option<&Theme>,
+) -> Result<Vec<String>, HighlightError> {
+    let syntax = find_syntax(ss, language, content);
+    let mut lines = Vec::new();
+
+    let Some(theme) = theme else {
+        let mut parse_state = ParseState::new(syntax);
+        let mut scope_stack = ScopeStack::new();
+        for line in LinesWithEndings::from(content) {
+            // Scopes spanning multiple lines (ex: block comments) are closed at the end of every line and reopened on the next one
+            let mut html: String = scope_stack.as_slice().iter().map(classed_span).collect();
+            let ops = parse_state
+                .parse_line(line, ss)
+                .map_err(syntect::Error::from)?;
+            let (spans, _) =
+                line_tokens_to_classed_spans(line, &ops, CLASS_STYLE, &mut scope_stack)?;
+            html.push_str(&spans);
+            html.push_str(&"</span>".repeat(scope_stack.len()));
+            lines.push(html);
+        }
+        return Ok(lines);
     };
 
     let mut h = HighlightLines::new(syntax, theme);
 
-    let mut highlighted = String::new();
     for line in LinesWithEndings::from(content) {
         let regions = h.highlight_line(line, ss)?;
         let html = styled_line_to_highlighted_html(&regions, IncludeBackground::No)?; // TODO: Handle the background coloring
-        highlighted.push_str(&html);
+        lines.push(html);
     }
 
-    Ok(highlighted)
+    Ok(lines)
+}
+
+/// Opening tag of the span of a scope, the same way Syntect's [`line_tokens_to_classed_spans`] does.
+fn classed_span(scope: &Scope) -> String {
+    let classes = scope
+        .build_string()
+        .split('.')
+        .map(|atom| format!("hl-{atom}"))
+        .collect::<Vec<_>>()
+        .join(" ");
+    format!("<span class=\"{classes}\">")
 }
 
-fn opening_html(language: Option<&str>) -> String {
+/// Generate the stylesheet styling code highlighted using [`HighlightMode::Classes`] with the theme of the options.
+///
+/// When the options have a dark theme, it is used instead when the user prefers a dark color scheme (`prefers-color-scheme: dark`).
+pub fn highlight_stylesheet(options: &HighlightOptions) -> Result<String, HighlightError> {
+    let theme = get_theme(&options.theme_path, &options.themes)?;
+    let dark_theme = options
+        .dark_theme_path
+        .as_deref()
+        .map(|dark_theme_path| get_theme(dark_theme_path, &options.themes))
+        .transpose()?;
+    stylesheet(&theme, dark_theme.as_deref())
+}
+
+fn stylesheet(theme: &Theme, dark_theme: Option<&Theme>) -> Result<String, HighlightError> {
+    let mut css = css_for_theme_with_class_style(theme, CLASS_STYLE)?;
+
+    if let Some(dark_theme) = dark_theme {
+        let dark_css = css_for_theme_with_class_style(dark_theme, CLASS_STYLE)?;
+        css.push_str(&format!(
+            "\n@media (prefers-color-scheme: dark) {{\n{}}}\n",
+            dark_css
+        ));
+    }
+
+    Ok(css)
+}
+
+/// Write the stylesheet of a highlighter to disk, so that it can be added to pages like any other style.
+pub(crate) fn highlight_stylesheet_path(
+    highlighter: &Highlighter,
+    cache_dir: &Path,
+) -> Result<Arc<PathBuf>, HighlightError> {
+    let options = &highlighter.options;
+    let key = (
+        cache_dir.to_path_buf(),
+        options.theme_path.clone(),
+        options.dark_theme_path.clone(),
+        options.themes.clone(),
+    );
+    get_cached(&STYLESHEETS, key, |_| {
+        let css = stylesheet(&highlighter.theme, highlighter.dark_theme.as_deref())?;
+
+        // Named after their content, so that routes rendered in parallel share them
+        let dir = cache_dir.join("highlight");
+        std::fs::create_dir_all(&dir)?;
+        let path = dir.join(format!("{}.css", hash_bytes(css.as_bytes())));
+        if std::fs::read_to_string(&path).ok().as_deref() != Some(css.as_str()) {
+            let temp_path = path.with_extension(format!("{}.tmp", std::process::id()));
+            std::fs::write(&temp_path, css)?;
+            std::fs::rename(&temp_path, &path)?;
+        }
+        Ok(path)
+    })
+}
+
+fn opening_html(language: Option<&str>, mode: HighlightMode) -> String {
     let mut attrs = Vec::new();
 
     // Follow EC here on the attribute name, though EC only adds it to the pre tag. I figure there's no harm in adding it to the code tag too.
@@ -76,52 +329,537 @@ fn opening_html(language: Option<&str>) -> String {
         }
     };
 
-    let pre_attrs_str = format_attrs(&attrs);
     let code_attrs_str = format_attrs(&attrs);
 
+    // The stylesheet styles the background and default color of the code through this class
+    if mode == HighlightMode::Classes {
+        attrs.push((String::from("class"), String::from("\"hl-code\"")));
+    }
+    let pre_attrs_str = format_attrs(&attrs);
+
     format!("<pre{pre_attrs_str}><code{code_attrs_str}>")
 }
 
+/// The info string following the opening of a fenced Markdown code block.
+///
+/// e.g. for ```` ```rust {3-5} title="main.rs" showLineNumbers ````, you'd get language: `Some("rust")`, meta: `{3-5} title="main.rs" showLineNumbers`,
+/// with the meta parsed into the other fields. The supported options are:
+/// - `{1,3-5}`: Lines to highlight, starting from 1.
+/// - `title="main.rs"`: A caption shown above the code, quotes can be omitted if it has no spaces.
+/// - `showLineNumbers`: Number every line.
+/// - `diff`: Lines starting with `+` or `-` are marked as inserted or deleted. Their marker, as well as the space
+///   starting unchanged lines, is removed.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct CodeFenceInfo {
+    pub language: Option<String>,
+    /// Everything after the language, trimmed.
+    pub meta: String,
+    pub title: Option<String>,
+    pub highlighted_lines: Vec<RangeInclusive<usize>>,
+    pub show_line_numbers: bool,
+    pub diff: bool,
+}
+
+impl CodeFenceInfo {
+    pub fn parse(fence: &str) -> Self {
+        let fence = fence.trim();
+        let (language, meta) = fence.split_once(char::is_whitespace).unwrap_or((fence, ""));
+
+        let mut info = Self {
+            language: (!language.is_empty()).then(|| language.to_string()),
+            meta: meta.trim().to_string(),
+            ..Default::default()
+        };
+
+        for (key, value) in parse_meta(&info.meta) {
+            match (key, value) {
+                (ranges, None) if ranges.starts_with('{') => {
+                    info.highlighted_lines
+                        .extend(parse_line_ranges(&ranges[1..ranges.len() - 1]));
+                }
+                ("title", Some(title)) => info.title = Some(title.to_string()),
+                ("showLineNumbers", None) => info.show_line_numbers = true,
+                ("diff", None) => info.diff = true,
+                // Unknown options are left to custom code block components, which can parse `meta` themselves
+                _ => {}
+            }
+        }
+
+        info
+    }
+
+    pub fn is_line_highlighted(&self, line: usize) -> bool {
+        self.highlighted_lines
+            .iter()
+            .any(|range| range.contains(&line))
+    }
+
+    /// Whether the lines of the block need to be wrapped individually.
+    fn has_line_options(&self) -> bool {
+        !self.highlighted_lines.is_empty() || self.show_line_numbers || self.diff
+    }
+}
+
+/// Split the meta of a fence into `key`, `key=value`, `key="value with spaces"` and `{ranges}` options.
+fn parse_meta(meta: &str) -> Vec<(&str, Option<&str>)> {
+    let mut options = Vec::new();
+    let mut rest = meta.trim_start();
+
+    while !rest.is_empty() {
+        if rest.starts_with('{') {
+            let end = rest.find('}').map_or(rest.len(), |end| end + 1);
+            let ranges = &rest[..end];
+            // Unclosed ranges are ignored
+            if ranges.ends_with('}') {
+                options.push((ranges, None));
+            }
+            rest = rest[end..].trim_start();
+            continue;
+        }
+
+        let key_end = rest
+            .find(|c: char| c.is_whitespace() || c == '=')
+            .unwrap_or(rest.len());
+        let key = &rest[..key_end];
+        rest = &rest[key_end..];
+
+        let Some(value) = rest.strip_prefix('=') else {
+            options.push((key, None));
+            rest = rest.trim_start();
+            continue;
+        };
+
+        let (value, remaining) = match value.chars().next() {
+            Some(quote @ ('"' | '\'')) => {
+                let value = &value[1..];
+                let end = value.find(quote).unwrap_or(value.len());
+                (&value[..end], value.get(end + 1..).unwrap_or_default())
+            }
+            _ => {
+                let end = value.find(char::is_whitespace).unwrap_or(value.len());
+                (&value[..end], &value[end..])
+            }
+        };
+        options.push((key, Some(value)));
+        rest = remaining.trim_start();
+    }
+
+    options
+}
+
+/// Parse line ranges like `1,3-5`, ignoring invalid ones.
+fn parse_line_ranges(ranges: &str) -> Vec<RangeInclusive<usize>> {
+    ranges
+        .split(',')
+        .filter_map(|range| {
+            let range = range.trim();
+            let (start, end) = range.split_once('-').unwrap_or((range, range));
+            let (start, end) = (start.trim().parse().ok()?, end.trim().parse().ok()?);
+            (start <= end).then_some(start..=end)
+        })
+        .collect()
+}
+
+pub(super) fn escape_html(s: &str) -> String {
+    s.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
 pub struct HighlightOptions {
     pub language: String,
+    /// Name of a theme, or path to a `.tmTheme` file. In [`HighlightMode::Classes`], the theme is only used by the stylesheet.
     pub theme_path: String,
+    /// Theme used instead of `theme_path` when the user prefers a dark color scheme. Only supported by [`HighlightMode::Classes`].
+    pub dark_theme_path: Option<String>,
+    pub mode: HighlightMode,
+    /// `.sublime-syntax` files, or directories of files, loaded in addition to Syntect's default syntaxes.
+    pub syntaxes: Vec<PathBuf>,
+    /// `.tmTheme` files, or directories of files, loaded in addition to Syntect's default themes. They are named after their file, without its extension.
+    pub themes: Vec<PathBuf>,
 }
 
 impl HighlightOptions {
-    /// Parse the value after the opening of a fenced Markdown code block
-    /// e.g. for ```rust ins=0, you'd get lang: "rs", ins: "0"
+    /// Parse the value after the opening of a fenced Markdown code block, see [`CodeFenceInfo`].
     pub fn new_from_fence(fence: &str, theme_path: impl Into<String>) -> Self {
-        // TODO: Write the parser for this, lol
-        let language = fence.to_string();
-        Self {
-            language,
-            // TODO: We could somehow allow specifying the theme in the fence too, it'd be funny
-            theme_path: theme_path.into(),
-        }
+        // TODO: We could somehow allow specifying the theme in the fence too, it'd be funny
+        Self::new(
+            CodeFenceInfo::parse(fence).language.unwrap_or_default(),
+            theme_path,
+        )
     }
 
-    #[allow(dead_code)]
     pub fn new(language: impl Into<String>, theme_path: impl Into<String>) -> Self {
         Self {
             language: language.into(),
             theme_path: theme_path.into(),
+            dark_theme_path: None,
+            mode: HighlightMode::default(),
+            syntaxes: Vec::new(),
+            themes: Vec::new(),
         }
     }
+
+    pub fn with_mode(mut self, mode: HighlightMode) -> Self {
+        self.mode = mode;
+        self
+    }
+
+    pub fn with_dark_theme(mut self, dark_theme_path: impl Into<String>) -> Self {
+        self.dark_theme_path = Some(dark_theme_path.into());
+        self
+    }
+
+    pub fn with_syntaxes(mut self, syntaxes: Vec<PathBuf>) -> Self {
+        self.syntaxes = syntaxes;
+        self
+    }
+
+    pub fn with_themes(mut self, themes: Vec<PathBuf>) -> Self {
+        self.themes = themes;
+        self
+    }
 }
 
-pub struct CodeBlock {
-    pub highlight_options: HighlightOptions,
+pub struct CodeBlock<'a> {
+    pub info: CodeFenceInfo,
+    highlighter: &'a Highlighter,
 }
 
-impl CodeBlock {
-    pub fn new(fence: &str, theme_path: &str) -> (Self, String) {
-        let highlight_options = HighlightOptions::new_from_fence(fence, theme_path);
-        let opening_html = opening_html(Some(&highlight_options.language));
+impl<'a> CodeBlock<'a> {
+    pub(crate) fn new(fence: &str, highlighter: &'a Highlighter) -> Self {
+        Self {
+            info: CodeFenceInfo::parse(fence),
+            highlighter,
+        }
+    }
+
+    fn language(&self) -> &str {
+        self.info.language.as_deref().unwrap_or_default()
+    }
+
+    /// Highlight the code of the block, returning the complete `<pre><code>` element.
+    ///
+    /// When the fence has line options, every line is wrapped in a `<span class="line">`, with the additional classes
+    /// `highlighted`, `inserted` or `deleted`, and the line number in a `<span class="line-number">` when enabled.
+    pub fn highlight(&self, content: &str) -> Result<String, HighlightError> {
+        let highlighted = if self.info.has_line_options() {
+            self.highlight_lines(content)?
+        } else {
+            self.highlighter
+                .highlight_lines(content, self.language())?
+                .concat()
+        };
+
+        Ok(format!(
+            "{}{}</code></pre>\n",
+            opening_html(Some(self.language()), self.highlighter.options.mode),
+            highlighted
+        ))
+    }
+
+    fn highlight_lines(&self, content: &str) -> Result<String, HighlightError> {
+        let mut diff_classes = Vec::new();
+        let content = if self.info.diff {
+            let mut code = String::with_capacity(content.len());
+            for line in LinesWithEndings::from(content) {
+                let (class, line) = match line.split_at_checked(1) {
+                    Some(("+", rest)) => (Some("inserted"), rest),
+                    Some(("-", rest)) => (Some("deleted"), rest),
+                    Some((" ", rest)) => (None, rest),
+                    _ => (None, line),
+                };
+                diff_classes.push(class);
+                code.push_str(line);
+            }
+            code
+        } else {
+            content.to_string()
+        };
 
-        (Self { highlight_options }, opening_html)
+        let mut html = String::new();
+        for (i, line) in self
+            .highlighter
+            .highlight_lines(&content, self.language())?
+            .into_iter()
+            .enumerate()
+        {
+            let number = i + 1;
+            let mut classes = vec!["line"];
+            if self.info.is_line_highlighted(number) {
+                classes.push("highlighted");
+            }
+            if let Some(Some(class)) = diff_classes.get(i) {
+                classes.push(class);
+            }
+
+            html.push_str(&format!("<span class=\"{}\">", classes.join(" ")));
+            if self.info.show_line_numbers {
+                html.push_str(&format!("<span class=\"line-number\">{number}</span>"));
+            }
+            // The line ending is kept outside of the line, so that lines can be styled as blocks
+            let ending = if line.contains('\n') { "\n" } else { "" };
+            html.push_str(&line.replace(['\r', '\n'], ""));
+            html.push_str("</span>");
+            html.push_str(ending);
+        }
+
+        Ok(html)
     }
 
-    pub fn highlight(&self, content: &str) -> Result<String, Error> {
-        highlight_code(content, &self.highlight_options)
+    /// Wrap the rendered block in a `<figure>` with its title as caption, if it has one.
+    pub fn with_title(&self, html: String) -> String {
+        match &self.info.title {
+            Some(title) => format!(
+                "<figure class=\"code-block\"><figcaption>{}</figcaption>{}</figure>\n",
+                escape_html(title),
+                html.trim_end()
+            ),
+            None => html,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_fence_info() {
+        assert_eq!(
+            CodeFenceInfo::parse("rust"),
+            CodeFenceInfo {
+                language: Some("rust".to_string()),
+                ..Default::default()
+            }
+        );
+        assert_eq!(
+            CodeFenceInfo::parse(" rust  title=\"main.rs\" {3-5} "),
+            CodeFenceInfo {
+                language: Some("rust".to_string()),
+                meta: "title=\"main.rs\" {3-5}".to_string(),
+                title: Some("main.rs".to_string()),
+                highlighted_lines: vec![3..=5],
+                ..Default::default()
+            }
+        );
+        assert_eq!(CodeFenceInfo::parse(""), CodeFenceInfo::default());
+    }
+
+    #[test]
+    fn test_parse_fence_meta() {
+        let info = CodeFenceInfo::parse(
+            "js {1, 4-6,x} showLineNumbers title='My file.js' diff collapse=3 {9}",
+        );
+        assert_eq!(info.highlighted_lines, vec![1..=1, 4..=6, 9..=9]);
+        assert!(info.is_line_highlighted(5));
+        assert!(!info.is_line_highlighted(3));
+        assert_eq!(info.title.as_deref(), Some("My file.js"));
+        assert!(info.show_line_numbers);
+        assert!(info.diff);
+
+        assert_eq!(
+            CodeFenceInfo::parse("sh title=build.sh").title.as_deref(),
+            Some("build.sh")
+        );
+        // Unclosed quotes and ranges don't swallow the rest of the meta or fail
+        let info = CodeFenceInfo::parse("rs {2-3 title=\"unclosed");
+        assert!(info.highlighted_lines.is_empty());
+        assert_eq!(
+            CodeFenceInfo::parse("rs title=\"unclosed").title.as_deref(),
+            Some("unclosed")
+        );
+    }
+
+    #[test]
+    fn test_code_block_lines() {
+        let highlighter = Highlighter::new(HighlightOptions::new("", "base16-ocean.dark")).unwrap();
+        let block = |fence: &str| CodeBlock::new(fence, &highlighter);
+
+        // Without line options, the output is left as is
+        let html = block("txt").highlight("a\nb\n").unwrap();
+        assert_eq!(
+            html,
+            "<pre data-language=\"txt\"><code data-language=\"txt\"><span style=\"color:#c0c5ce;\">a\n</span><span style=\"color:#c0c5ce;\">b\n</span></code></pre>\n"
+        );
+
+        let html = block("txt {2} showLineNumbers")
+            .highlight("a\nb\n")
+            .unwrap();
+        assert_eq!(
+            html,
+            "<pre data-language=\"txt\"><code data-language=\"txt\"><span class=\"line\"><span class=\"line-number\">1</span><span style=\"color:#c0c5ce;\">a</span></span>\n<span class=\"line highlighted\"><span class=\"line-number\">2</span><span style=\"color:#c0c5ce;\">b</span></span>\n</code></pre>\n"
+        );
+
+        let html = block("txt diff").highlight("+a\n-b\n c").unwrap();
+        assert!(html.contains(
+            "<span class=\"line inserted\"><span style=\"color:#c0c5ce;\">a</span></span>\n"
+        ));
+        assert!(html.contains(
+            "<span class=\"line deleted\"><span style=\"color:#c0c5ce;\">b</span></span>\n"
+        ));
+        assert!(html.contains(
+            "<span class=\"line\"><span style=\"color:#c0c5ce;\">c</span></span></code>"
+        ));
+    }
+
+    #[test]
+    fn test_code_block_lines_with_classes() {
+        let options =
+            HighlightOptions::new("", "base16-ocean.dark").with_mode(HighlightMode::Classes);
+        let highlighter = Highlighter::new(options).unwrap();
+        let block = CodeBlock::new("rust {2}", &highlighter);
+        let html = block.highlight("/* a\nb */\nfn main() {}\n").unwrap();
+
+        // The comment spanning two lines is split, so that every line is balanced
+        let lines: Vec<&str> = html.lines().collect();
+        for line in &lines[..3] {
+            assert_eq!(
+                line.matches("<span").count(),
+                line.matches("</span>").count()
+            );
+        }
+        assert!(lines[1].starts_with(
+            "<span class=\"line highlighted\"><span class=\"hl-source hl-rust\"><span class=\"hl-comment hl-block hl-rust\">b "
+        ));
+    }
+
+    #[test]
+    fn test_code_block_title() {
+        let highlighter = Highlighter::new(HighlightOptions::new("", "base16-ocean.dark")).unwrap();
+        let block = CodeBlock::new("rust title=\"<main>.rs\"", &highlighter);
+        let html = block.with_title(block.highlight("fn main() {}").unwrap());
+        assert!(html.starts_with(
+            "<figure class=\"code-block\"><figcaption>&lt;main&gt;.rs</figcaption><pre data-language=\"rust\">"
+        ));
+        assert!(html.ends_with("</code></pre></figure>\n"));
+
+        let block = CodeBlock::new("rust", &highlighter);
+        assert_eq!(block.with_title("<pre></pre>\n".into()), "<pre></pre>\n");
+    }
+
+    #[test]
+    fn test_highlight_with_classes() {
+        let options = HighlightOptions::new("rust", "base16-ocean.dark");
+        let inline = highlight_code("fn main() {}\n", &options).unwrap();
+        assert!(inline.contains("style=\""));
+
+        let classes =
+            highlight_code("fn main() {}\n", &options.with_mode(HighlightMode::Classes)).unwrap();
+        assert!(!classes.contains("style="));
+        assert!(classes.contains("class=\"hl-"));
+    }
+
+    #[test]
+    fn test_highlight_stylesheet() {
+        let options = HighlightOptions::new("", "InspiredGitHub");
+        let light = highlight_stylesheet(&options).unwrap();
+        assert!(light.contains(".hl-code {"));
+        assert!(!light.contains("prefers-color-scheme"));
+
+        let options = options.with_dark_theme("base16-ocean.dark");
+        let pair = highlight_stylesheet(&options).unwrap();
+        let (light_css, dark_css) = pair
+            .split_once("@media (prefers-color-scheme: dark) {")
+            .unwrap();
+        assert_eq!(light_css.trim_end(), light.trim_end());
+        assert!(dark_css.contains("theme \"Base16 Ocean Dark\""));
+
+        let cache_dir = tempfile::tempdir().unwrap();
+        let highlighter = Highlighter::new(options).unwrap();
+        let path = highlight_stylesheet_path(&highlighter, cache_dir.path()).unwrap();
+        assert!(path.starts_with(cache_dir.path()));
+        assert_eq!(std::fs::read_to_string(path.as_path()).unwrap(), pair);
+    }
+
+    const TEST_SYNTAX: &str = r#"%YAML 1.2
+---
+name: Maudit DSL
+file_extensions: [mdsl]
+scope: source.mdsl
+contexts:
+  main:
+    - match: '\broute\b'
+      scope: keyword.control.mdsl
+"#;
+
+    const TEST_THEME: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<!DOCTYPE plist PUBLIC "-//Apple//DTD PLIST 1.0//EN" "http://www.apple.com/DTDs/PropertyList-1.0.dtd">
+<plist version="1.0">
+<dict>
+  <key>name</key>
+  <string>Maudit Test</string>
+  <key>settings</key>
+  <array>
+    <dict>
+      <key>settings</key>
+      <dict>
+        <key>foreground</key>
+        <string>#111111</string>
+      </dict>
+    </dict>
+    <dict>
+      <key>scope</key>
+      <string>keyword</string>
+      <key>settings</key>
+      <dict>
+        <key>foreground</key>
+        <string>#ff0000</string>
+      </dict>
+    </dict>
+  </array>
+</dict>
+</plist>
+"#;
+
+    #[test]
+    fn test_custom_syntaxes_and_themes() {
+        let dir = tempfile::tempdir().unwrap();
+        let syntaxes_dir = dir.path().join("syntaxes");
+        std::fs::create_dir(&syntaxes_dir).unwrap();
+        std::fs::write(syntaxes_dir.join("mdsl.sublime-syntax"), TEST_SYNTAX).unwrap();
+        let theme_path = dir.path().join("maudit-test.tmTheme");
+        std::fs::write(&theme_path, TEST_THEME).unwrap();
+
+        let options = HighlightOptions::new("mdsl", "maudit-test")
+            .with_syntaxes(vec![syntaxes_dir])
+            .with_themes(vec![theme_path.clone()]);
+        let html = highlight_code("route home\n", &options).unwrap();
+        assert!(html.contains("<span style=\"color:#ff0000;\">route</span>"));
+
+        // Single files work as well, and themes can still be loaded from their path directly
+        let options = HighlightOptions::new("mdsl", theme_path.to_string_lossy())
+            .with_syntaxes(vec![dir.path().join("syntaxes/mdsl.sublime-syntax")]);
+        let html = highlight_code("route home\n", &options).unwrap();
+        assert!(html.contains("<span style=\"color:#ff0000;\">route</span>"));
+    }
+
+    #[test]
+    fn test_missing_syntaxes_and_themes() {
+        let missing_theme = HighlightOptions::new("rust", "not-a-theme");
+        assert!(highlight_code("fn main() {}", &missing_theme).is_err());
+        assert!(matches!(
+            Highlighter::new(missing_theme),
+            Err(HighlightError::ThemeNotFound(theme)) if theme == "not-a-theme"
+        ));
+
+        let missing_dark_theme =
+            HighlightOptions::new("rust", "InspiredGitHub").with_dark_theme("not-a-theme");
+        assert!(Highlighter::new(missing_dark_theme).is_err());
+
+        let missing_syntax = HighlightOptions::new("rust", "InspiredGitHub")
+            .with_syntaxes(vec![PathBuf::from("does/not/exist.sublime-syntax")]);
+        assert!(matches!(
+            Highlighter::new(missing_syntax),
+            Err(HighlightError::SyntaxLoadFailed { .. })
+        ));
+
+        let missing_theme_file = HighlightOptions::new("rust", "InspiredGitHub")
+            .with_themes(vec![PathBuf::from("does/not/exist.tmTheme")]);
+        assert!(matches!(
+            Highlighter::new(missing_theme_file),
+            Err(HighlightError::ThemeLoadFailed { .. })
+        ));
     }
 }
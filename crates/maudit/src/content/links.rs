@@ -0,0 +1,142 @@
+//! Links between content entries, e.g. `[see](./other-post.md)` in Markdown, resolved to the URL of the pages of the entries.
+use std::{
+    fmt::{self, Display, Formatter},
+    path::{Path, PathBuf},
+};
+
+use rustc_hash::FxHashMap;
+
+use crate::content::{ContentSources, Dependency};
+
+/// A link to a file which isn't the source of any page.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct BrokenLink {
+    /// File containing the link.
+    pub file: PathBuf,
+    pub href: String,
+}
+
+impl Display for BrokenLink {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "`{}` in {}", self.href, self.file.display())
+    }
+}
+
+/// Name of the content source and ID of an entry.
+type EntryKey = (String, String);
+
+/// URLs of the pages generated from entries, by the files the entries were loaded from.
+#[derive(Debug, Default)]
+pub(crate) struct EntryUrls {
+    /// URLs of the pages of every entry, by variant.
+    pages: FxHashMap<EntryKey, Vec<(Option<String>, String)>>,
+    files: FxHashMap<PathBuf, EntryKey>,
+}
+
+impl EntryUrls {
+    /// Record the page generated from an entry, ex: using `into_pages`.
+    pub(crate) fn add_page(&mut self, entry: &EntryKey, variant: Option<&str>, url: &str) {
+        self.pages
+            .entry(entry.clone())
+            .or_default()
+            .push((variant.map(str::to_string), url.to_string()));
+    }
+
+    /// Index the files of the entries which have pages, once every page was added.
+    pub(crate) fn index_files(&mut self, content_sources: &ContentSources) {
+        for source in content_sources.sources() {
+            for (id, dependencies) in source.entry_dependencies() {
+                let entry = (source.get_name().to_string(), id);
+                if !self.pages.contains_key(&entry) {
+                    continue;
+                }
+
+                for dependency in dependencies {
+                    if let Dependency::File(path) = dependency
+                        && let Ok(path) = path.canonicalize()
+                    {
+                        self.files.insert(path, entry.clone());
+                    }
+                }
+            }
+        }
+    }
+
+    /// Get the entry loaded from a file and the URL of its page, preferring the page of the given variant, then the base route.
+    pub(crate) fn resolve(&self, file: &Path, variant: Option<&str>) -> Option<(&EntryKey, &str)> {
+        let entry = self.files.get(&file.canonicalize().ok()?)?;
+        let pages = self.pages.get(entry)?;
+        let (_, url) = pages
+            .iter()
+            .find(|(page_variant, _)| page_variant.as_deref() == variant)
+            .or_else(|| {
+                pages
+                    .iter()
+                    .find(|(page_variant, _)| page_variant.is_none())
+            })
+            .or(pages.first())?;
+
+        Some((entry, url))
+    }
+}
+
+/// Split a relative link to a Markdown file, e.g. `../posts/post.md#section`, into its path and the rest of the link.
+/// Returns `None` for any other link.
+pub(crate) fn markdown_link(href: &str) -> Option<(&str, &str)> {
+    let has_scheme = href
+        .split_once(':')
+        .is_some_and(|(scheme, _)| !scheme.contains('/'));
+    if has_scheme || href.starts_with('/') || href.starts_with('#') {
+        return None;
+    }
+
+    let (path, rest) = href.split_at(href.find(['#', '?']).unwrap_or(href.len()));
+    path.ends_with(".md").then_some((path, rest))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_markdown_link() {
+        assert_eq!(markdown_link("./post.md"), Some(("./post.md", "")));
+        assert_eq!(
+            markdown_link("../posts/post.md#intro"),
+            Some(("../posts/post.md", "#intro"))
+        );
+        assert_eq!(markdown_link("post.md?a=b"), Some(("post.md", "?a=b")));
+
+        assert_eq!(markdown_link("https://example.com/post.md"), None);
+        assert_eq!(markdown_link("mailto:me@post.md"), None);
+        assert_eq!(markdown_link("/posts/post.md"), None);
+        assert_eq!(markdown_link("#post.md"), None);
+        assert_eq!(markdown_link("./image.png"), None);
+    }
+
+    #[test]
+    fn test_resolve_variants() {
+        let dir = tempfile::tempdir().unwrap();
+        let file = dir.path().join("post.md");
+        std::fs::write(&file, "# Post").unwrap();
+
+        let entry = ("posts".to_string(), "post".to_string());
+        let mut entry_urls = EntryUrls::default();
+        entry_urls.add_page(&entry, Some("fr"), "/fr/posts/post");
+        entry_urls.add_page(&entry, None, "/posts/post");
+        entry_urls
+            .files
+            .insert(file.canonicalize().unwrap(), entry.clone());
+
+        let url = |variant| entry_urls.resolve(&file, variant).map(|(_, url)| url);
+        assert_eq!(url(None), Some("/posts/post"));
+        assert_eq!(url(Some("fr")), Some("/fr/posts/post"));
+        assert_eq!(url(Some("de")), Some("/posts/post"));
+        assert_eq!(
+            entry_urls
+                .resolve(&dir.path().join("other.md"), None)
+                .map(|(_, url)| url),
+            None
+        );
+    }
+}
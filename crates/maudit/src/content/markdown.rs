@@ -1,32 +1,52 @@
-use std::{path::Path, sync::Arc};
+use std::{
+    path::{Path, PathBuf},
+    sync::{Arc, OnceLock},
+};
 
-use glob::glob as glob_fs;
-use log::warn;
+use log::{error, warn};
 use pulldown_cmark::{CodeBlockKind, Event, Options, Parser, Tag, TagEnd, html::push_html};
 use serde::de::DeserializeOwned;
 
 pub mod components;
+mod footnotes;
 pub mod shortcodes;
+mod text;
+pub mod toc;
 
 use components::{LinkType, ListType, MarkdownComponents, TableAlignment};
+pub use text::{TextOptions, render_markdown_text};
+pub use toc::{TableOfContents, TocEntry};
 
 use crate::{
-    assets::Asset,
+    assets::{Asset, StyleOptions},
+    build::cache::CachedMarkdown,
     content::{
-        ContentContext, ContentEntry, Dependency, Entry,
-        shortcodes::{MarkdownShortcodes, preprocess_shortcodes},
+        ContentContext, DataFormat, Dependency, Entry, EntryInner,
+        deserialize::deserialize_str,
+        reference,
+        shortcodes::{MarkdownShortcodes, expand_shortcodes},
     },
+    errors::{ContentError, HighlightError, MarkdownError},
     route::PageContext,
 };
 
-use super::{highlight::CodeBlock, slugger};
+use super::{
+    files::{file_id, glob_files, read_file},
+    highlight::{
+        CodeBlock, HighlightMode, HighlightOptions, Highlighter, highlight_stylesheet_path,
+    },
+    links,
+    math::{MathDisplay, render_math},
+    publication::Publication,
+    slugger,
+};
 
 #[cfg(test)]
 mod shortcodes_tests;
 
 /// Represents a Markdown heading.
 ///
-/// Can be used to generate a table of contents.
+/// Can be used to generate a table of contents, see [`TableOfContents`] to get the headings nested by level.
 ///
 /// ## Example
 /// ```rust
@@ -74,6 +94,49 @@ pub struct MarkdownHeading {
     pub classes: Vec<String>,
 }
 
+/// Summary of the body of a Markdown entry, computed once when the data of the entry is loaded.
+///
+/// Can be used to list entries without rendering them, for instance on index pages.
+///
+/// ## Example
+/// ```rust
+/// use maudit::route::prelude::*;
+/// use maud::{html, Markup};
+/// # use maudit::content::markdown_entry;
+/// #
+/// # #[markdown_entry]
+/// # pub struct ArticleContent {
+/// #    pub title: String,
+/// # }
+///
+/// #[route("/articles")]
+/// pub struct Articles;
+///
+/// impl Route<PageParams, Markup> for Articles {
+///   fn render(&self, ctx: &mut PageContext) -> Markup {
+///     let articles = ctx.content::<ArticleContent>("articles");
+///     html! {
+///       @for article in articles.entries() {
+///         @let data = article.data(ctx);
+///         article {
+///           h2 { (data.title) }
+///           p { (data.get_summary().excerpt) }
+///           small { (data.get_summary().reading_time) " min read" }
+///         }
+///       }
+///     }
+///   }
+/// }
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct MarkdownSummary {
+    /// Plain text of the body up to a `<!-- more -->` marker, or if there's none, its first [`MarkdownOptions::excerpt_length`] characters.
+    pub excerpt: String,
+    pub word_count: usize,
+    /// Estimated reading time in minutes, based on [`MarkdownOptions::words_per_minute`].
+    pub reading_time: usize,
+}
+
 #[derive(Debug)]
 struct InternalHeadingEvent {
     start: usize,
@@ -100,6 +163,7 @@ impl InternalHeadingEvent {
 /// We expose it because [`maudit_macros::markdown_entry`] implements it for the user behind the scenes.
 pub trait MarkdownContent {
     fn get_headings(&self) -> &Vec<MarkdownHeading>;
+    fn get_summary(&self) -> &MarkdownSummary;
 }
 
 #[doc(hidden)]
@@ -107,6 +171,7 @@ pub trait MarkdownContent {
 /// We expose it because [`maudit_macros::markdown_entry`] implements it for the user behind the scenes.
 pub trait InternalMarkdownContent {
     fn set_headings(&mut self, headings: Vec<MarkdownHeading>);
+    fn set_summary(&mut self, summary: MarkdownSummary);
 }
 
 /// Represents untyped Markdown content.
@@ -132,32 +197,68 @@ pub trait InternalMarkdownContent {
 pub struct UntypedMarkdownContent {
     #[serde(skip)]
     __internal_headings: Vec<MarkdownHeading>,
+    #[serde(skip)]
+    __internal_summary: MarkdownSummary,
 }
 
 impl MarkdownContent for UntypedMarkdownContent {
     fn get_headings(&self) -> &Vec<MarkdownHeading> {
         &self.__internal_headings
     }
+
+    fn get_summary(&self) -> &MarkdownSummary {
+        &self.__internal_summary
+    }
 }
 
 impl InternalMarkdownContent for UntypedMarkdownContent {
     fn set_headings(&mut self, headings: Vec<MarkdownHeading>) {
         self.__internal_headings = headings;
     }
+
+    fn set_summary(&mut self, summary: MarkdownSummary) {
+        self.__internal_summary = summary;
+    }
 }
 
 pub struct MarkdownOptions {
     pub highlight_theme: String,
+    /// Theme used instead of [`highlight_theme`](Self::highlight_theme) when the user prefers a dark color scheme.
+    /// Only supported by [`HighlightMode::Classes`].
+    pub highlight_dark_theme: Option<String>,
+    /// How highlighted code is styled. With [`HighlightMode::Classes`], the stylesheet of the theme is included in every page containing code blocks.
+    pub highlight_mode: HighlightMode,
+    /// `.sublime-syntax` files, or directories of files, to highlight languages Syntect doesn't support by default.
+    pub highlight_syntaxes: Vec<PathBuf>,
+    /// `.tmTheme` files, or directories of files, usable as [`highlight_theme`](Self::highlight_theme) by their file name without its extension.
+    pub highlight_themes: Vec<PathBuf>,
     pub components: MarkdownComponents,
     pub shortcodes: MarkdownShortcodes,
+    /// Maximum number of characters of the excerpt of entries without a `<!-- more -->` marker. Defaults to 200.
+    pub excerpt_length: usize,
+    /// Reading speed used to estimate the reading time of entries. Defaults to 200.
+    pub words_per_minute: usize,
+    /// Move footnote definitions to an ordered list at the end of the document, with links back to their references.
+    /// Footnotes that are never referenced are left out.
+    pub collect_footnotes: bool,
+    /// Markdown syntax extensions enabled when parsing, see [`MarkdownExtensions`].
+    pub extensions: MarkdownExtensions,
 }
 
 impl Default for MarkdownOptions {
     fn default() -> Self {
         Self {
             highlight_theme: "base16-ocean.dark".to_string(),
+            highlight_dark_theme: None,
+            highlight_mode: HighlightMode::default(),
+            highlight_syntaxes: Vec::new(),
+            highlight_themes: Vec::new(),
             components: MarkdownComponents::default(),
             shortcodes: MarkdownShortcodes::default(),
+            excerpt_length: 200,
+            words_per_minute: 200,
+            collect_footnotes: false,
+            extensions: MarkdownExtensions::default(),
         }
     }
 }
@@ -174,6 +275,163 @@ impl MarkdownOptions {
             ..Self::default()
         }
     }
+
+    fn highlight_options(&self) -> HighlightOptions {
+        let options = HighlightOptions::new("", &self.highlight_theme)
+            .with_mode(self.highlight_mode)
+            .with_syntaxes(self.highlight_syntaxes.clone())
+            .with_themes(self.highlight_themes.clone());
+
+        match &self.highlight_dark_theme {
+            Some(dark_theme) => options.with_dark_theme(dark_theme),
+            None => options,
+        }
+    }
+
+    /// Hash of the options that affect the rendered HTML. Components and shortcodes are code, covered by the hash of the binary.
+    fn fingerprint(&self) -> String {
+        use rapidhash::fast::RapidHasher;
+        use std::hash::{Hash, Hasher};
+
+        let mut hasher = RapidHasher::default();
+        self.highlight_theme.hash(&mut hasher);
+        self.highlight_dark_theme.hash(&mut hasher);
+        std::mem::discriminant(&self.highlight_mode).hash(&mut hasher);
+        self.collect_footnotes.hash(&mut hasher);
+        self.extensions.hash(&mut hasher);
+        // Themes can be paths to `.tmTheme` files, whose content changes the HTML without changing their path
+        let themes = std::iter::once(&self.highlight_theme).chain(&self.highlight_dark_theme);
+        for path in themes
+            .map(Path::new)
+            .filter(|path| path.is_file())
+            .chain(self.highlight_syntaxes.iter().map(PathBuf::as_path))
+            .chain(self.highlight_themes.iter().map(PathBuf::as_path))
+        {
+            hash_files(path, &mut hasher);
+        }
+        format!("{:016x}", hasher.finish())
+    }
+}
+
+/// Hash the path and content of a file, or of every file in a directory and its subdirectories.
+fn hash_files(path: &Path, hasher: &mut impl std::hash::Hasher) {
+    use std::hash::Hash;
+
+    path.hash(hasher);
+    if path.is_dir() {
+        let mut children: Vec<PathBuf> = std::fs::read_dir(path)
+            .map(|children| {
+                children
+                    .filter_map(|child| Some(child.ok()?.path()))
+                    .collect()
+            })
+            .unwrap_or_default();
+        children.sort();
+        for child in children {
+            hash_files(&child, hasher);
+        }
+    } else if let Ok(content) = std::fs::read(path) {
+        content.hash(hasher);
+    }
+}
+
+/// Markdown syntax extensions, on top of [CommonMark](https://commonmark.org).
+///
+/// By default, the extensions of [GitHub Flavored Markdown](https://github.github.com/gfm/), footnotes and math are enabled.
+///
+/// ## Example
+/// ```rust
+/// use maudit::content::{MarkdownExtensions, MarkdownOptions};
+///
+/// let options = MarkdownOptions {
+///     extensions: MarkdownExtensions {
+///         // Prices such as $5 shouldn't start math expressions
+///         math: false,
+///         smart_punctuation: true,
+///         definition_lists: true,
+///         ..Default::default()
+///     },
+///     ..Default::default()
+/// };
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct MarkdownExtensions {
+    /// Tables, i.e. `| a | b |` rows.
+    pub tables: bool,
+    /// Footnote references and definitions, i.e. `[^note]` and `[^note]: Text`.
+    pub footnotes: bool,
+    /// Strikethrough, i.e. `~~text~~`.
+    pub strikethrough: bool,
+    /// Task list markers, i.e. `- [x] item`.
+    pub task_lists: bool,
+    /// Inline and display math, i.e. `$x$` and `$$x$$`.
+    pub math: bool,
+    /// Blockquote alerts from GitHub, i.e. `> [!NOTE]`.
+    pub gfm: bool,
+    /// Typographic quotes, dashes and ellipses, e.g. `"quotes"` to “quotes” and `--` to –.
+    pub smart_punctuation: bool,
+    /// IDs and classes on headings, i.e. `## Heading {#id .class}`.
+    pub heading_attributes: bool,
+    /// Definition lists, i.e. a term followed by lines starting with `: `.
+    pub definition_lists: bool,
+    /// Links to other pages by name, i.e. `[[Page]]` and `[[Page|text]]`.
+    pub wikilinks: bool,
+    /// Superscript, i.e. `^text^`.
+    pub superscript: bool,
+    /// Subscript, i.e. `~text~`. When enabled alongside [`strikethrough`](Self::strikethrough), only `~~text~~` is a strikethrough.
+    pub subscript: bool,
+}
+
+impl Default for MarkdownExtensions {
+    fn default() -> Self {
+        Self {
+            tables: true,
+            footnotes: true,
+            strikethrough: true,
+            task_lists: true,
+            math: true,
+            gfm: true,
+            smart_punctuation: false,
+            heading_attributes: false,
+            definition_lists: false,
+            wikilinks: false,
+            superscript: false,
+            subscript: false,
+        }
+    }
+}
+
+impl MarkdownExtensions {
+    /// Options of the parser, frontmatter blocks are always enabled.
+    pub(crate) fn parser_options(&self) -> Options {
+        let mut options = Options::ENABLE_YAML_STYLE_METADATA_BLOCKS
+            | Options::ENABLE_PLUSES_DELIMITED_METADATA_BLOCKS;
+        for (enabled, option) in [
+            (self.tables, Options::ENABLE_TABLES),
+            (self.footnotes, Options::ENABLE_FOOTNOTES),
+            (self.strikethrough, Options::ENABLE_STRIKETHROUGH),
+            (self.task_lists, Options::ENABLE_TASKLISTS),
+            (self.math, Options::ENABLE_MATH),
+            (self.gfm, Options::ENABLE_GFM),
+            (self.smart_punctuation, Options::ENABLE_SMART_PUNCTUATION),
+            (self.heading_attributes, Options::ENABLE_HEADING_ATTRIBUTES),
+            (self.definition_lists, Options::ENABLE_DEFINITION_LIST),
+            (self.wikilinks, Options::ENABLE_WIKILINKS),
+            (self.superscript, Options::ENABLE_SUPERSCRIPT),
+            (self.subscript, Options::ENABLE_SUBSCRIPT),
+        ] {
+            options.set(option, enabled);
+        }
+        options
+    }
+
+    /// Dependency of the entries rendered with these extensions, so that changing them re-renders their pages in incremental builds.
+    fn dependency(&self) -> Dependency {
+        Dependency::Custom {
+            key: "markdown-extensions".to_string(),
+            hash: format!("{:x}", self.parser_options().bits()),
+        }
+    }
 }
 
 /// Glob for Markdown files and return a vector of [`Entry`]s.
@@ -204,17 +462,31 @@ impl MarkdownOptions {
 ///   )
 /// }
 /// ```
-pub fn glob_markdown_with_options<T>(pattern: &str, options: MarkdownOptions) -> Vec<Entry<T>>
+pub fn glob_markdown_with_options<T>(
+    pattern: &str,
+    options: MarkdownOptions,
+) -> Result<Vec<Entry<T>>, ContentError>
 where
     T: DeserializeOwned + MarkdownContent + InternalMarkdownContent + Send + Sync + 'static,
 {
+    // Every entry shares the same syntaxes and themes, loaded upfront to report missing files at once
+    let highlighter = Arc::new(Highlighter::new(options.highlight_options())?);
+
+    let extensions = options.extensions;
+    let options_fingerprint = options.fingerprint();
     let options = Some(options);
     let mut entries = vec![];
+    let mut errors = vec![];
     let options = options.map(Arc::new);
 
-    // TODO: `glob` is kinda slow, but alternatives are either unmaintained, have annoying bugs or not faster.
-    for entry in glob_fs(pattern).unwrap() {
-        let entry = entry.unwrap();
+    for entry in glob_files(pattern)? {
+        let entry = match entry {
+            Ok(entry) => entry,
+            Err(err) => {
+                errors.push(err);
+                continue;
+            }
+        };
 
         if let Some(extension) = entry.extension()
             && extension != "md"
@@ -223,32 +495,165 @@ where
             continue;
         }
 
-        let id = entry.file_stem().unwrap().to_str().unwrap().to_string();
-        let content = std::fs::read_to_string(&entry).unwrap();
+        let (id, content) = match file_id(&entry).and_then(|id| Ok((id, read_file(&entry)?))) {
+            Ok(file) => file,
+            Err(err) => {
+                errors.push(err);
+                continue;
+            }
+        };
+
+        // The frontmatter is validated upfront so that every invalid file gets reported at once,
+        // but the data is still loaded lazily, as extracting headings requires parsing the whole file.
+        if let Err(err) = deserialize_frontmatter::<T>(&content, Some(&entry)) {
+            errors.push(err);
+            continue;
+        }
+
+        let draft = deserialize_frontmatter::<Publication>(&content, Some(&entry))
+            .unwrap_or_default()
+            .is_draft(chrono::Utc::now(), &entry);
 
         // Clone content for the closure
         let content_clone = content.clone();
+        let loader_opts = options.clone();
         let data_loader = Box::new(move |_: &mut dyn ContentContext| {
-            parse_markdown_with_frontmatter(&content_clone)
+            parse_markdown_entry(&content_clone, loader_opts.as_deref())
         });
+        let fingerprint_opts = options.clone();
+        let data_fingerprint =
+            Box::new(move |content: &str| data_fingerprint(content, fingerprint_opts.as_deref()));
 
         // Perhaps not ideal, but I don't know better. We're at the "get it working" stage - erika, 2025-08-24
         // Ideally, we'd at least avoid the allocation here whenever `options` is None, not sure how to do that ergonomically
         let opts = options.clone();
         let path = entry.clone();
+        let options_fingerprint = options_fingerprint.clone();
+        let highlighter = highlighter.clone();
+        let text_opts = options.clone();
+        let text_path = entry.clone();
 
-        entries.push(Entry::create_lazy(
+        entries.push(Arc::new(EntryInner {
             id,
-            Some(Box::new(move |content: &str, route_ctx| {
-                render_markdown(content, opts.as_deref(), Some(&path), Some(route_ctx))
+            render: Some(Box::new(move |content: &str, route_ctx| {
+                render_entry(
+                    content,
+                    opts.as_deref(),
+                    &highlighter,
+                    &options_fingerprint,
+                    &path,
+                    route_ctx,
+                )
             })),
-            Some(content),
-            data_loader,
-            vec![Dependency::File(entry)],
-        ));
+            render_text: Some(Box::new(move |content: &str, text_options| {
+                render_markdown_text(
+                    content,
+                    text_opts.as_deref(),
+                    Some(&text_path),
+                    text_options,
+                )
+            })),
+            raw_content: Some(content),
+            data_loader: Some(data_loader),
+            cached_data: OnceLock::new(),
+            dependencies: vec![Dependency::File(entry), extensions.dependency()],
+            data_fingerprint: Some(data_fingerprint),
+            draft,
+        }));
+    }
+
+    ContentError::aggregate(errors).map(|_| entries)
+}
+
+/// Render a Markdown entry, reusing the HTML it was rendered to from the same content, in this build or the previous one, if possible.
+// The sets of images are only moved around, the hash of images doesn't use their mutable data
+#[allow(clippy::mutable_key_type)]
+fn render_entry(
+    content: &str,
+    options: Option<&MarkdownOptions>,
+    highlighter: &Highlighter,
+    options_fingerprint: &str,
+    path: &Path,
+    route_ctx: &mut PageContext,
+) -> String {
+    let Some(cache) = route_ctx.markdown_cache else {
+        return render_markdown_with_highlighter(
+            content,
+            options,
+            Some(highlighter),
+            Some(path),
+            Some(route_ctx),
+        );
+    };
+
+    let hash = cache.hash(content, options_fingerprint);
+    if let Some(cached) = cache.get(route_ctx.current_path, &hash)
+        && cached.styles.iter().all(|(style, tailwind)| {
+            route_ctx
+                .assets
+                .include_style_with_options(
+                    style,
+                    StyleOptions {
+                        tailwind: *tailwind,
+                    },
+                )
+                .is_ok()
+        })
+    {
+        return cached.html;
+    }
+
+    // What the page has is set aside to know what the rendering adds, even when the page already had it
+    let images = std::mem::take(&mut route_ctx.assets.images);
+    let scripts = std::mem::take(&mut route_ctx.assets.scripts);
+    let styles = std::mem::take(&mut route_ctx.assets.styles);
+    let side_effects = |ctx: &PageContext| {
+        let log = ctx.access_log.borrow();
+        log.entries_read.len()
+            + log.sources_iterated.len()
+            + log.sources_queried.len()
+            + ctx.broken_links.len()
+            + ctx.shortcode_errors.len()
+            + ctx.highlight_errors.len()
+    };
+    let previous_side_effects = side_effects(route_ctx);
+
+    let html = render_markdown_with_highlighter(
+        content,
+        options,
+        Some(highlighter),
+        Some(path),
+        Some(route_ctx),
+    );
+
+    let rendered_images = std::mem::replace(&mut route_ctx.assets.images, images);
+    let rendered_scripts = std::mem::replace(&mut route_ctx.assets.scripts, scripts);
+    let rendered_styles = std::mem::replace(&mut route_ctx.assets.styles, styles);
+
+    // The URLs of images are part of the HTML and change with the images, which the hash doesn't cover
+    if rendered_images.is_empty()
+        && rendered_scripts.is_empty()
+        && rendered_styles.iter().all(|style| style.included)
+        && side_effects(route_ctx) == previous_side_effects
+    {
+        cache.insert(
+            route_ctx.current_path,
+            hash,
+            CachedMarkdown {
+                html: html.clone(),
+                styles: rendered_styles
+                    .iter()
+                    .map(|style| (style.path.clone(), style.tailwind))
+                    .collect(),
+            },
+        );
     }
 
-    entries
+    route_ctx.assets.images.extend(rendered_images);
+    route_ctx.assets.scripts.extend(rendered_scripts);
+    route_ctx.assets.styles.extend(rendered_styles);
+
+    html
 }
 
 /// Glob for Markdown files and return a vector of [`Entry`]s.
@@ -278,7 +683,7 @@ where
 ///   )
 /// }
 /// ```
-pub fn glob_markdown<T>(pattern: &str) -> Vec<Entry<T>>
+pub fn glob_markdown<T>(pattern: &str) -> Result<Vec<Entry<T>>, ContentError>
 where
     T: DeserializeOwned + MarkdownContent + InternalMarkdownContent + Send + Sync + 'static,
 {
@@ -326,6 +731,9 @@ fn find_headings(events: &[Event]) -> Vec<InternalHeadingEvent> {
 ///
 /// To be able to resolve and include images, a path to the Markdown file and a mutable reference to the current [`PageContext`](crate::route::PageContext) must be provided.
 ///
+/// Shortcodes that fail to expand and code blocks that fail to highlight make the build fail once the current page is
+/// rendered. Without a page context, they're logged, use [`try_render_markdown`] to handle them instead.
+///
 /// ## Example
 /// ```rust
 /// use maudit::content::{render_markdown, MarkdownOptions, MarkdownComponents};
@@ -363,43 +771,174 @@ pub fn render_markdown(
     content: &str,
     options: Option<&MarkdownOptions>,
     path: Option<&Path>,
+    route_ctx: Option<&mut PageContext>,
+) -> String {
+    render_markdown_with_highlighter(content, options, None, path, route_ctx)
+}
+
+/// Same as [`render_markdown`], but returns the shortcodes that failed to expand and the code blocks that failed to
+/// highlight as an error, instead of reporting them to the page.
+///
+/// ## Example
+/// ```rust
+/// use maudit::content::{try_render_markdown, MarkdownOptions};
+///
+/// let options = MarkdownOptions {
+///     highlight_theme: "not-a-theme".into(),
+///     ..Default::default()
+/// };
+/// let result = try_render_markdown("```rust\nfn main() {}\n```", Some(&options), None, None);
+/// assert!(result.is_err());
+/// ```
+pub fn try_render_markdown(
+    content: &str,
+    options: Option<&MarkdownOptions>,
+    path: Option<&Path>,
+    route_ctx: Option<&mut PageContext>,
+) -> Result<String, MarkdownError> {
+    let mut errors = MarkdownError::default();
+    let html =
+        render_markdown_collecting_errors(content, options, None, path, route_ctx, &mut errors);
+    if errors.is_empty() {
+        Ok(html)
+    } else {
+        Err(errors)
+    }
+}
+
+/// Render Markdown content, highlighting code blocks with an already loaded highlighter if there's one.
+fn render_markdown_with_highlighter(
+    content: &str,
+    options: Option<&MarkdownOptions>,
+    highlighter: Option<&Highlighter>,
+    path: Option<&Path>,
     mut route_ctx: Option<&mut PageContext>,
+) -> String {
+    let mut errors = MarkdownError::default();
+    let html = render_markdown_collecting_errors(
+        content,
+        options,
+        highlighter,
+        path,
+        route_ctx.as_deref_mut(),
+        &mut errors,
+    );
+    report_errors(errors, route_ctx);
+    html
+}
+
+/// Report the errors found while rendering Markdown to the page being rendered, whose build fails once it's rendered.
+/// Without a page, there's no build to fail, so they're logged.
+pub(crate) fn report_errors(errors: MarkdownError, route_ctx: Option<&mut PageContext>) {
+    match route_ctx {
+        Some(ctx) => {
+            ctx.shortcode_errors.extend(errors.shortcodes);
+            ctx.highlight_errors.extend(errors.highlight);
+        }
+        None if !errors.is_empty() => error!("Failed to render Markdown:\n{}", errors),
+        None => {}
+    }
+}
+
+/// Render Markdown content, adding the errors found along the way to `errors`.
+fn render_markdown_collecting_errors(
+    content: &str,
+    options: Option<&MarkdownOptions>,
+    highlighter: Option<&Highlighter>,
+    path: Option<&Path>,
+    mut route_ctx: Option<&mut PageContext>,
+    errors: &mut MarkdownError,
 ) -> String {
     let content = if let Some(shortcodes) = options.map(|o| &o.shortcodes)
         && !shortcodes.is_empty()
     {
-        preprocess_shortcodes(
+        match expand_shortcodes(
             content,
             shortcodes,
+            options,
+            highlighter,
             route_ctx.as_deref_mut(),
             path.and_then(|p| p.to_str()),
-        )
-        .unwrap_or_else(|e| panic!("Failed to preprocess shortcodes for {:?}: {}", path, e))
+            &mut errors.highlight,
+        ) {
+            Ok(content) => content,
+            Err(err) => {
+                errors.shortcodes.push(err);
+                return String::new();
+            }
+        }
     } else {
         content.to_string()
     };
 
+    // JSON frontmatter isn't a metadata block pulldown-cmark knows about, so it's removed beforehand.
+    let content = split_json_frontmatter(&content).map_or(content.as_str(), |(_, body)| body);
+
+    render_expanded_markdown(
+        content,
+        options,
+        highlighter,
+        path,
+        route_ctx,
+        &mut errors.highlight,
+    )
+}
+
+/// Render Markdown whose shortcodes were already expanded, e.g. the body of a block shortcode.
+pub(crate) fn render_expanded_markdown(
+    content: &str,
+    options: Option<&MarkdownOptions>,
+    highlighter: Option<&Highlighter>,
+    path: Option<&Path>,
+    mut route_ctx: Option<&mut PageContext>,
+    highlight_errors: &mut Vec<HighlightError>,
+) -> String {
     let mut slugger = slugger::Slugger::new();
     let mut html_output = String::new();
-    let parser_options = Options::ENABLE_YAML_STYLE_METADATA_BLOCKS
-        | Options::ENABLE_STRIKETHROUGH
-        | Options::ENABLE_TASKLISTS
-        | Options::ENABLE_TABLES
-        | Options::ENABLE_GFM
-        | Options::ENABLE_MATH
-        | Options::ENABLE_FOOTNOTES;
+    let parser_options = options
+        .map(|options| options.extensions)
+        .unwrap_or_default()
+        .parser_options();
 
     let mut code_block = None;
     let mut code_block_content = String::new();
+    let mut highlight_stylesheet_included = false;
     let mut in_frontmatter = false;
     let mut in_image = false;
-    let mut events = Parser::new_ext(&content, parser_options).collect::<Vec<Event>>();
+    let mut events = Parser::new_ext(content, parser_options).collect::<Vec<Event>>();
+
+    // Without a highlighter loaded beforehand, syntaxes and themes are loaded once for all the code blocks
+    let has_code_blocks = events.iter().any(|event| {
+        matches!(
+            event,
+            Event::Start(Tag::CodeBlock(CodeBlockKind::Fenced(_)))
+        )
+    });
+    let loaded_highlighter = match highlighter {
+        None if has_code_blocks => {
+            let highlight_options = options
+                .map(MarkdownOptions::highlight_options)
+                .unwrap_or_else(|| MarkdownOptions::default().highlight_options());
+            match Highlighter::new(highlight_options) {
+                Ok(highlighter) => Some(highlighter),
+                Err(err) => {
+                    highlight_errors.push(err);
+                    None
+                }
+            }
+        }
+        _ => None,
+    };
+    let highlighter = highlighter.or(loaded_highlighter.as_ref());
 
     let options_with_components = options
         .as_ref()
         .filter(|o| o.components.has_any_components());
 
     for i in 0..events.len() {
+        // Image added to the assets of the page for the current event, passed to the image component
+        let mut image_asset = None;
+
         match &events[i] {
             Event::Start(Tag::MetadataBlock(_)) => {
                 in_frontmatter = true;
@@ -418,54 +957,128 @@ pub fn render_markdown(
                 title,
             }) => {
                 // TODO: Figure out a cleaner way to do this, it's a lot of if-lets and checks - erika, 2025-08-27
-                let new_event = if dest_url.starts_with("./") || dest_url.starts_with("../") {
-                    path.and_then(|p| p.parent())
-                        .and_then(|parent| {
-                            let resolved = parent.join(dest_url.to_string());
-                            route_ctx
-                                .as_mut()
-                                .and_then(|ctx| ctx.assets.add_image(resolved).ok())
-                                .map(|image| image.url().to_owned())
-                        })
-                        .map(|image_url| {
-                            Event::Start(Tag::Image {
-                                dest_url: image_url.into(),
-                                title: title.clone(),
-                                link_type: *link_type,
-                                id: id.clone(),
-                            })
-                        })
+                let image = if dest_url.starts_with("./") || dest_url.starts_with("../") {
+                    path.and_then(|p| p.parent()).and_then(|parent| {
+                        let resolved = parent.join(dest_url.to_string());
+                        route_ctx
+                            .as_mut()
+                            .and_then(|ctx| ctx.assets.add_image(resolved).ok())
+                    })
                 } else {
                     None
                 };
 
-                if let Some(event) = new_event {
-                    events[i] = event;
+                if let Some(image) = image {
+                    events[i] = Event::Start(Tag::Image {
+                        dest_url: image.url().to_owned().into(),
+                        title: title.clone(),
+                        link_type: *link_type,
+                        id: id.clone(),
+                    });
+                    image_asset = Some(image);
+                }
+            }
+
+            // Links to Markdown files, e.g. `./other-post.md`, point to the page generated from their entry
+            Event::Start(Tag::Link {
+                link_type,
+                dest_url,
+                title,
+                id,
+            }) => {
+                if let Some((link_path, rest)) = links::markdown_link(dest_url)
+                    && let Some(file) = path
+                    && let Some(ctx) = route_ctx.as_mut()
+                    && let Some(entry_urls) = ctx.entry_urls
+                {
+                    let target = file.parent().unwrap_or(Path::new("")).join(link_path);
+                    match entry_urls.resolve(&target, ctx.variant.as_deref()) {
+                        Some(((source, entry_id), url)) => {
+                            // The link follows the page it points to, if its entry is removed or renamed
+                            ctx.record_entry_read(source, entry_id);
+                            events[i] = Event::Start(Tag::Link {
+                                link_type: *link_type,
+                                dest_url: format!("{url}{rest}").into(),
+                                title: title.clone(),
+                                id: id.clone(),
+                            });
+                        }
+                        None => {
+                            ctx.broken_links.push(links::BrokenLink {
+                                file: file.to_path_buf(),
+                                href: dest_url.to_string(),
+                            });
+
+                            // The file may be added to a content source later on, which then fixes the link
+                            let mut log = ctx.access_log.borrow_mut();
+                            for source in ctx.content.sources() {
+                                if !log.sources_queried.iter().any(|s| s == source.get_name()) {
+                                    log.sources_queried.push(source.get_name().to_string());
+                                }
+                            }
+                        }
+                    }
                 }
             }
 
-            // TODO: Handle this differently so it's compatible with the component system - erika, 2025-08-24
+            // The code of fenced blocks is collected from their text events, and the whole block is rendered at its end
             Event::Start(Tag::CodeBlock(CodeBlockKind::Fenced(fence))) => {
-                let (block, begin) = CodeBlock::new(
-                    fence,
-                    &options
-                        .unwrap_or(&MarkdownOptions::default())
-                        .highlight_theme,
-                );
-                code_block = Some(block);
-                events[i] = Event::Html(begin.into());
+                // If the highlighter failed to load, which fails the build, the block is left as is
+                let Some(highlighter) = highlighter else {
+                    continue;
+                };
+
+                if highlighter.options.mode == HighlightMode::Classes
+                    && !highlight_stylesheet_included
+                    && let Some(ctx) = route_ctx.as_mut()
+                {
+                    match highlight_stylesheet_path(highlighter, &ctx.assets.options.cache_dir) {
+                        Ok(stylesheet) => ctx.assets.include_style_unchecked(stylesheet.as_path()),
+                        Err(err) => highlight_errors.push(err),
+                    }
+                    highlight_stylesheet_included = true;
+                }
+
+                code_block = Some(CodeBlock::new(fence, highlighter));
+                events[i] = Event::Html("".into());
             }
 
             Event::End(TagEnd::CodeBlock) => {
                 if let Some(ref mut code_block) = code_block {
-                    let html = code_block.highlight(&code_block_content);
-                    events[i] =
-                        Event::Html(format!("{}{}", html.unwrap(), "</code></pre>\n").into());
+                    // Missing themes and syntaxes are reported when loading the highlighter, see `Highlighter::new`
+                    let html = code_block
+                        .highlight(&code_block_content)
+                        .unwrap_or_else(|err| {
+                            highlight_errors.push(err);
+                            String::new()
+                        });
+                    let html = match options.and_then(|opts| opts.components.code_block.as_ref()) {
+                        Some(component) => {
+                            component.render(&code_block.info, &code_block_content, &html)
+                        }
+                        None => code_block.with_title(html),
+                    };
+                    events[i] = Event::Html(html.into());
                 }
                 code_block = None;
                 code_block_content.clear();
             }
 
+            // Math is rendered at build time, so that no client-side library is needed
+            Event::InlineMath(latex) | Event::DisplayMath(latex) => {
+                let display = if matches!(events[i], Event::DisplayMath(_)) {
+                    MathDisplay::Block
+                } else {
+                    MathDisplay::Inline
+                };
+                let mathml = render_math(latex, display);
+                let html = match options.and_then(|opts| opts.components.math.as_ref()) {
+                    Some(component) => component.render(latex, display, &mathml),
+                    None => mathml,
+                };
+                events[i] = Event::InlineHtml(html.into());
+            }
+
             // TODO: User should be able to replace the text component too perhaps, but it'd require merging the text events
             Event::Text(text) => {
                 if !in_frontmatter {
@@ -594,7 +1207,12 @@ pub fn render_markdown(
                         } else {
                             Some(title.as_ref())
                         };
-                        let custom_html = component.render(dest_url.as_ref(), &alt_text, title_str);
+                        let custom_html = match (image_asset.take(), route_ctx.as_deref_mut()) {
+                            (Some(image), Some(ctx)) => {
+                                component.render_image(&image, &alt_text, title_str, ctx.assets)
+                            }
+                            _ => component.render(dest_url.as_ref(), &alt_text, title_str),
+                        };
                         events[i] = Event::Html(custom_html.into());
                     }
                 }
@@ -726,6 +1344,76 @@ pub fn render_markdown(
                     }
                 }
 
+                // Superscript, i.e. ^superscript^
+                Event::Start(Tag::Superscript) => {
+                    if let Some(component) = &options.components.superscript {
+                        let custom_html = component.render_start();
+                        events[i] = Event::Html(custom_html.into());
+                    }
+                }
+                Event::End(TagEnd::Superscript) => {
+                    if let Some(component) = &options.components.superscript {
+                        let custom_html = component.render_end();
+                        events[i] = Event::Html(custom_html.into());
+                    }
+                }
+
+                // Subscript, i.e. ~subscript~
+                Event::Start(Tag::Subscript) => {
+                    if let Some(component) = &options.components.subscript {
+                        let custom_html = component.render_start();
+                        events[i] = Event::Html(custom_html.into());
+                    }
+                }
+                Event::End(TagEnd::Subscript) => {
+                    if let Some(component) = &options.components.subscript {
+                        let custom_html = component.render_end();
+                        events[i] = Event::Html(custom_html.into());
+                    }
+                }
+
+                // Definition Lists, i.e. a term followed by `: definition` lines
+                Event::Start(Tag::DefinitionList) => {
+                    if let Some(component) = &options.components.definition_list {
+                        let custom_html = component.render_start();
+                        events[i] = Event::Html(custom_html.into());
+                    }
+                }
+                Event::End(TagEnd::DefinitionList) => {
+                    if let Some(component) = &options.components.definition_list {
+                        let custom_html = component.render_end();
+                        events[i] = Event::Html(custom_html.into());
+                    }
+                }
+
+                // Definition List Titles, i.e. the term of a definition
+                Event::Start(Tag::DefinitionListTitle) => {
+                    if let Some(component) = &options.components.definition_list_title {
+                        let custom_html = component.render_start();
+                        events[i] = Event::Html(custom_html.into());
+                    }
+                }
+                Event::End(TagEnd::DefinitionListTitle) => {
+                    if let Some(component) = &options.components.definition_list_title {
+                        let custom_html = component.render_end();
+                        events[i] = Event::Html(custom_html.into());
+                    }
+                }
+
+                // Definition List Definitions, i.e. : definition
+                Event::Start(Tag::DefinitionListDefinition) => {
+                    if let Some(component) = &options.components.definition_list_definition {
+                        let custom_html = component.render_start();
+                        events[i] = Event::Html(custom_html.into());
+                    }
+                }
+                Event::End(TagEnd::DefinitionListDefinition) => {
+                    if let Some(component) = &options.components.definition_list_definition {
+                        let custom_html = component.render_end();
+                        events[i] = Event::Html(custom_html.into());
+                    }
+                }
+
                 // (GFM) Task List Markers, i.e. - [ ] item
                 Event::TaskListMarker(checked) => {
                     if let Some(component) = &options.components.task_list_marker {
@@ -814,15 +1502,20 @@ pub fn render_markdown(
         _ => true,
     });
 
+    footnotes::render_footnotes(&mut events, options);
+
     push_html(&mut html_output, events.into_iter());
     html_output
 }
 
 /// Parse Markdown content with frontmatter and extract headings.
 ///
-/// This function extracts YAML frontmatter from markdown content, deserializes it into the specified type,
+/// This function extracts the frontmatter from markdown content, deserializes it into the specified type,
 /// and automatically populates the headings for table of contents generation.
 ///
+/// The format of the frontmatter is detected from its delimiters: YAML between `---` lines, TOML between `+++` lines,
+/// or a JSON object whose opening brace is alone on the first line of the file.
+///
 /// ## Example
 /// ```rust
 /// use maudit::content::{parse_markdown_with_frontmatter, markdown_entry, MarkdownContent};
@@ -851,36 +1544,90 @@ pub fn parse_markdown_with_frontmatter<T>(content: &str) -> T
 where
     T: DeserializeOwned + MarkdownContent + InternalMarkdownContent,
 {
-    let mut slugger = slugger::Slugger::new();
+    parse_markdown_entry(content, None)
+}
+
+fn parse_markdown_entry<T>(content: &str, options: Option<&MarkdownOptions>) -> T
+where
+    T: DeserializeOwned + MarkdownContent + InternalMarkdownContent,
+{
+    let mut parsed: T =
+        deserialize_frontmatter(content, None).unwrap_or_else(|err| panic!("{}", err));
+    parsed.set_headings(headings_from_events(&body_events(content, options)));
+    parsed.set_summary(entry_summary(content, options));
+    parsed
+}
+
+/// Deserialize the frontmatter of a Markdown file, reporting errors at their position in the file.
+fn deserialize_frontmatter<T: DeserializeOwned>(
+    content: &str,
+    path: Option<&Path>,
+) -> Result<T, ContentError> {
+    // Content without frontmatter is deserialized as an empty YAML document
+    let frontmatter = split_frontmatter(content).0.unwrap_or(Frontmatter {
+        format: DataFormat::Yaml,
+        text: "",
+        first_line: 1,
+    });
+
+    reference::with_origin(path, || {
+        deserialize_str(frontmatter.text, frontmatter.format)
+    })
+    .map_err(|failure| failure.into_frontmatter_error(path, frontmatter.first_line))
+}
+
+/// Everything the data of a Markdown entry is derived from: its frontmatter, its headings and its summary.
+fn data_fingerprint(content: &str, options: Option<&MarkdownOptions>) -> String {
+    let frontmatter = split_frontmatter(content)
+        .0
+        .map_or("", |frontmatter| frontmatter.text);
+
+    let events = body_events(content, options);
+    let mut fingerprint = frontmatter.to_string();
+    for heading in headings_from_events(&events) {
+        fingerprint.push_str(&format!(
+            "\n{} {} {} {}",
+            heading.level,
+            heading.id,
+            heading.title,
+            heading.classes.join(".")
+        ));
+    }
+    let summary = entry_summary(content, options);
+    fingerprint.push_str(&format!(
+        "\n{} {}\n{}",
+        summary.word_count, summary.reading_time, summary.excerpt
+    ));
+    fingerprint
+}
+
+/// Parse the body of a Markdown file with the extensions it's rendered with, leaving out its frontmatter.
+fn body_events<'a>(content: &'a str, options: Option<&MarkdownOptions>) -> Vec<Event<'a>> {
+    let content = split_json_frontmatter(content).map_or(content, |(_, body)| body);
 
-    let mut options = Options::empty();
-    options.insert(Options::ENABLE_YAML_STYLE_METADATA_BLOCKS | Options::ENABLE_HEADING_ATTRIBUTES);
+    let parser_options = options
+        .map(|options| options.extensions)
+        .unwrap_or_default()
+        .parser_options();
 
-    let mut frontmatter = String::new();
     let mut in_frontmatter = false;
 
     let mut content_events = Vec::new();
-    for (event, _) in Parser::new_ext(content, options).into_offset_iter() {
+    for (event, _) in Parser::new_ext(content, parser_options).into_offset_iter() {
         match event {
             Event::Start(Tag::MetadataBlock(_)) => in_frontmatter = true,
             Event::End(TagEnd::MetadataBlock(_)) => in_frontmatter = false,
-            Event::Text(ref text) => {
-                if in_frontmatter {
-                    frontmatter.push_str(text);
-                } else {
-                    content_events.push(event);
-                }
-            }
+            Event::Text(_) if in_frontmatter => {}
             _ => content_events.push(event),
         }
     }
 
-    // TODO: Prettier errors for serialization errors (e.g. missing fields)
-    // TODO: Support TOML frontmatters
-    let mut parsed = serde_yaml::from_str::<T>(&frontmatter)
-        .unwrap_or_else(|e| panic!("Failed to parse YAML frontmatter: {}, {}", e, frontmatter));
+    content_events
+}
 
-    let headings_internal = find_headings(&content_events);
+fn headings_from_events(content_events: &[Event]) -> Vec<MarkdownHeading> {
+    let mut slugger = slugger::Slugger::new();
+    let headings_internal = find_headings(content_events);
 
     let mut headings = vec![];
     for heading in headings_internal {
@@ -895,8 +1642,203 @@ where
         });
     }
 
-    parsed.set_headings(headings);
-    parsed
+    headings
+}
+
+/// Summary of the body of an entry, as it reads once its shortcodes are expanded.
+fn entry_summary(content: &str, options: Option<&MarkdownOptions>) -> MarkdownSummary {
+    let expanded = match options.map(|options| &options.shortcodes) {
+        // Shortcodes failing to expand are reported when rendering the entry, the summary uses the content as is until then
+        Some(shortcodes) if !shortcodes.is_empty() => expand_shortcodes(
+            content,
+            shortcodes,
+            options,
+            None,
+            None,
+            None,
+            &mut Vec::new(),
+        )
+        .ok(),
+        _ => None,
+    };
+
+    summary_from_events(
+        &body_events(expanded.as_deref().unwrap_or(content), options),
+        options,
+    )
+}
+
+fn summary_from_events(
+    content_events: &[Event],
+    options: Option<&MarkdownOptions>,
+) -> MarkdownSummary {
+    let default_options;
+    let options = match options {
+        Some(options) => options,
+        None => {
+            default_options = MarkdownOptions::default();
+            &default_options
+        }
+    };
+
+    let mut text = String::new();
+    let mut text_before_marker = None;
+    let mut in_code_block = false;
+    for event in content_events {
+        match event {
+            // Code blocks aren't read like prose, so they count neither as words nor as part of the excerpt
+            Event::Start(Tag::CodeBlock(_)) => in_code_block = true,
+            Event::End(TagEnd::CodeBlock) => in_code_block = false,
+            _ if in_code_block => {}
+
+            Event::Text(t) | Event::Code(t) => text.push_str(t),
+            Event::Html(html) | Event::InlineHtml(html)
+                if text_before_marker.is_none() && is_more_marker(html) =>
+            {
+                text_before_marker = Some(text.len());
+            }
+            // Separate the words of different blocks and lines
+            Event::SoftBreak
+            | Event::HardBreak
+            | Event::End(
+                TagEnd::Paragraph
+                | TagEnd::Heading(_)
+                | TagEnd::Item
+                | TagEnd::TableCell
+                | TagEnd::BlockQuote(_),
+            ) => text.push(' '),
+            _ => {}
+        }
+    }
+
+    let words: Vec<&str> = text.split_whitespace().collect();
+    let word_count = words.len();
+    let reading_time = word_count.div_ceil(options.words_per_minute.max(1));
+
+    let excerpt = match text_before_marker {
+        Some(end) => text[..end].split_whitespace().collect::<Vec<_>>().join(" "),
+        None => truncate_words(&words, options.excerpt_length),
+    };
+
+    MarkdownSummary {
+        excerpt,
+        word_count,
+        reading_time,
+    }
+}
+
+fn is_more_marker(html: &str) -> bool {
+    html.split_whitespace().collect::<String>() == "<!--more-->"
+}
+
+/// Join words up to a number of characters, without cutting words unless the first one is already too long.
+fn truncate_words(words: &[&str], max_chars: usize) -> String {
+    let mut excerpt = String::new();
+    let mut length = 0;
+    for word in words {
+        let separator = usize::from(!excerpt.is_empty());
+        let word_length = word.chars().count();
+        if length + separator + word_length > max_chars {
+            if excerpt.is_empty() {
+                excerpt.extend(word.chars().take(max_chars));
+            }
+            break;
+        }
+
+        if separator == 1 {
+            excerpt.push(' ');
+        }
+        excerpt.push_str(word);
+        length += separator + word_length;
+    }
+    excerpt
+}
+
+/// Frontmatter at the start of a Markdown file.
+struct Frontmatter<'a> {
+    format: DataFormat,
+    text: &'a str,
+    /// Line of the file `text` starts at, used to report errors at their position in the file.
+    first_line: usize,
+}
+
+/// Split the frontmatter from the rest of the content.
+///
+/// YAML and TOML frontmatter follow the same rules as pulldown-cmark's metadata blocks, so that
+/// what's deserialized here is also what's skipped when rendering.
+fn split_frontmatter(content: &str) -> (Option<Frontmatter<'_>>, &str) {
+    if let Some((text, body)) = split_json_frontmatter(content) {
+        let frontmatter = Frontmatter {
+            format: DataFormat::Json,
+            text,
+            first_line: 1,
+        };
+        return (Some(frontmatter), body);
+    }
+
+    let (format, fence) = if content.starts_with("---") {
+        (DataFormat::Yaml, "---")
+    } else if content.starts_with("+++") {
+        (DataFormat::Toml, "+++")
+    } else {
+        return (None, content);
+    };
+
+    let mut lines = content.split_inclusive('\n');
+    let opening = lines.next().unwrap_or_default();
+    if !opening[3..].trim().is_empty() {
+        return (None, content);
+    }
+
+    let is_closing = |line: &str| {
+        let line = line.trim_end_matches(['\n', '\r']);
+        let rest = line.strip_prefix(fence).or_else(|| {
+            line.strip_prefix("...")
+                .filter(|_| format == DataFormat::Yaml)
+        });
+        rest.is_some_and(|rest| rest.chars().all(|c| c == ' '))
+    };
+
+    let start = opening.len();
+    let mut offset = start;
+    for (index, line) in lines.enumerate() {
+        if index == 0 && (line.trim().is_empty() || is_closing(line)) {
+            break;
+        }
+        if is_closing(line) {
+            let frontmatter = Frontmatter {
+                format,
+                text: &content[start..offset],
+                first_line: 2,
+            };
+            return (Some(frontmatter), &content[offset + line.len()..]);
+        }
+        offset += line.len();
+    }
+
+    (None, content)
+}
+
+/// Split a JSON frontmatter from the rest of the content.
+///
+/// To not be mistaken for Markdown starting with a brace (e.g. a shortcode), JSON frontmatter
+/// must have its opening brace alone on the first line of the file.
+fn split_json_frontmatter(content: &str) -> Option<(&str, &str)> {
+    let after_brace = content.strip_prefix('{')?;
+    if !after_brace.starts_with('\n') && !after_brace.starts_with("\r\n") {
+        return None;
+    }
+
+    let mut stream =
+        serde_json::Deserializer::from_str(content).into_iter::<serde::de::IgnoredAny>();
+    match stream.next() {
+        Some(Ok(_)) => {
+            let end = stream.byte_offset();
+            Some((&content[..end], &content[end..]))
+        }
+        // Treat the whole file as frontmatter, so that deserializing it reports the syntax error
+        _ => Some((content, "")),
+    }
 }
 
 fn find_matching_heading_end(events: &[Event], start_index: usize) -> Option<usize> {
@@ -916,6 +1858,8 @@ fn find_matching_image_end(events: &[Event], start_index: usize) -> Option<usize
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::content::ContentSources;
+    use crate::errors::HighlightError;
 
     #[test]
     fn test_basic_markdown_rendering() {
@@ -984,6 +1928,607 @@ More content here."#;
         assert!(html_with_empty_options.contains("id=\""));
     }
 
+    #[derive(serde::Deserialize)]
+    struct FrontmatterTestContent {
+        title: String,
+        tags: Vec<String>,
+        #[serde(skip)]
+        headings: Vec<MarkdownHeading>,
+        #[serde(skip)]
+        summary: MarkdownSummary,
+    }
+
+    impl MarkdownContent for FrontmatterTestContent {
+        fn get_headings(&self) -> &Vec<MarkdownHeading> {
+            &self.headings
+        }
+
+        fn get_summary(&self) -> &MarkdownSummary {
+            &self.summary
+        }
+    }
+
+    impl InternalMarkdownContent for FrontmatterTestContent {
+        fn set_headings(&mut self, headings: Vec<MarkdownHeading>) {
+            self.headings = headings;
+        }
+
+        fn set_summary(&mut self, summary: MarkdownSummary) {
+            self.summary = summary;
+        }
+    }
+
+    const FRONTMATTER_BODY: &str = "\n## Introduction\n\nSome text.\n\n## Conclusion\n";
+
+    fn assert_frontmatter_parsed(markdown: &str) {
+        let parsed: FrontmatterTestContent = parse_markdown_with_frontmatter(markdown);
+        assert_eq!(parsed.title, "Hello");
+        assert_eq!(parsed.tags, vec!["rust", "web"]);
+
+        let headings: Vec<_> = parsed
+            .get_headings()
+            .iter()
+            .map(|h| (h.title.as_str(), h.id.as_str(), h.level))
+            .collect();
+        assert_eq!(
+            headings,
+            vec![
+                ("Introduction", "introduction", 2),
+                ("Conclusion", "conclusion", 2)
+            ]
+        );
+
+        let html = render_markdown(markdown, None, None, None);
+        assert!(!html.contains("Hello"), "frontmatter was rendered: {html}");
+        assert!(html.contains("Some text."));
+    }
+
+    #[test]
+    fn test_yaml_frontmatter() {
+        assert_frontmatter_parsed(&format!(
+            "---\ntitle: Hello\ntags: [rust, web]\n---\n{FRONTMATTER_BODY}"
+        ));
+    }
+
+    #[test]
+    fn test_toml_frontmatter() {
+        assert_frontmatter_parsed(&format!(
+            "+++\ntitle = \"Hello\"\ntags = [\"rust\", \"web\"]\n+++\n{FRONTMATTER_BODY}"
+        ));
+    }
+
+    #[test]
+    fn test_json_frontmatter() {
+        assert_frontmatter_parsed(&format!(
+            "{{\n  \"title\": \"Hello\",\n  \"tags\": [\"rust\", \"web\"]\n}}\n{FRONTMATTER_BODY}"
+        ));
+    }
+
+    #[test]
+    fn test_headings_are_parsed_with_the_enabled_extensions() {
+        let markdown = "---\ntitle: Hello\ntags: []\n---\n## Conclusion {#the-end}\n";
+        let heading = |options: &MarkdownOptions| {
+            let parsed: FrontmatterTestContent = parse_markdown_entry(markdown, Some(options));
+            let heading = &parsed.get_headings()[0];
+            (heading.title.clone(), heading.id.clone())
+        };
+
+        assert_eq!(
+            heading(&MarkdownOptions::default()),
+            ("Conclusion {#the-end}".into(), "conclusion-the-end".into())
+        );
+        assert_eq!(
+            heading(&MarkdownOptions {
+                extensions: MarkdownExtensions {
+                    heading_attributes: true,
+                    ..Default::default()
+                },
+                ..Default::default()
+            }),
+            ("Conclusion".into(), "the-end".into())
+        );
+    }
+
+    #[test]
+    fn test_brace_on_first_line_is_not_json_frontmatter() {
+        assert!(split_json_frontmatter("{{ greet name=World /}}\n\nText").is_none());
+        assert!(split_json_frontmatter("{\"title\": \"inline\"}").is_none());
+    }
+
+    #[test]
+    #[should_panic(expected = "invalid frontmatter")]
+    fn test_invalid_json_frontmatter_panics() {
+        let _: FrontmatterTestContent =
+            parse_markdown_with_frontmatter("{\n  \"title\": \"Hello\",\n\n## Heading\n");
+    }
+
+    #[test]
+    fn test_frontmatter_errors_point_to_the_file() {
+        let markdown = "---\ntitle: Hello\ntags: rust\n---\n\n# Heading\n";
+        let error = deserialize_frontmatter::<FrontmatterTestContent>(
+            markdown,
+            Some(Path::new("content/post.md")),
+        )
+        .err()
+        .unwrap();
+
+        let ContentError::InvalidFrontmatter(invalid) = &error else {
+            panic!("expected an InvalidFrontmatter error");
+        };
+        assert_eq!(invalid.line, Some(3));
+        assert_eq!(invalid.field.as_deref(), Some("tags"));
+        assert_eq!(invalid.expected.as_deref(), Some("a sequence"));
+        assert!(error.to_string().starts_with("content/post.md:3:"));
+    }
+
+    #[test]
+    fn test_split_frontmatter_follows_metadata_block_rules() {
+        let (frontmatter, body) = split_frontmatter("+++\ntitle = \"Hi\"\n+++  \nText");
+        let frontmatter = frontmatter.unwrap();
+        assert_eq!(frontmatter.format, DataFormat::Toml);
+        assert_eq!(frontmatter.text, "title = \"Hi\"\n");
+        assert_eq!(body, "Text");
+
+        // YAML blocks can also be closed with `...`
+        assert!(split_frontmatter("---\ntitle: Hi\n...\n").0.is_some());
+        // Not frontmatter: a thematic break, an empty first line, or an unclosed block
+        assert!(split_frontmatter("----\ntitle: Hi\n----\n").0.is_none());
+        assert!(split_frontmatter("---\n\ntitle: Hi\n---\n").0.is_none());
+        assert!(split_frontmatter("---\ntitle: Hi\n").0.is_none());
+    }
+
+    #[test]
+    fn test_glob_markdown_reports_every_invalid_file() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(
+            dir.path().join("valid.md"),
+            "---\ntitle: Valid\ntags: []\n---\n",
+        )
+        .unwrap();
+        std::fs::write(dir.path().join("missing.md"), "---\ntitle: Missing\n---\n").unwrap();
+        std::fs::write(
+            dir.path().join("mistyped.md"),
+            "+++\ntitle = 1\ntags = []\n+++\n",
+        )
+        .unwrap();
+
+        let pattern = dir.path().join("*.md").to_string_lossy().to_string();
+        let Err(ContentError::Multiple(errors)) = glob_markdown::<FrontmatterTestContent>(&pattern)
+        else {
+            panic!("expected every invalid file to be reported");
+        };
+
+        let fields: Vec<_> = errors
+            .iter()
+            .map(|error| match error {
+                ContentError::InvalidFrontmatter(invalid) => invalid.field.as_deref().unwrap(),
+                _ => panic!("expected an InvalidFrontmatter error"),
+            })
+            .collect();
+        assert_eq!(fields, vec!["tags", "title"]);
+        assert!(errors[0].to_string().contains("missing.md"));
+        assert!(errors[1].to_string().contains("mistyped.md"));
+    }
+
+    #[test]
+    fn test_highlight_with_classes_includes_stylesheet() {
+        let options = MarkdownOptions {
+            highlight_mode: HighlightMode::Classes,
+            highlight_dark_theme: Some("base16-ocean.dark".to_string()),
+            ..Default::default()
+        };
+        let content = ContentSources::new(vec![]);
+        let mut assets = crate::assets::RouteAssets::default();
+        let path = "/".to_string();
+        let mut ctx = PageContext::from_static_route(&content, &mut assets, &path, &None, None);
+
+        let html = render_markdown(
+            "```rust\nfn main() {}\n```\n\n```js\nlet a = 1;\n```",
+            Some(&options),
+            None,
+            Some(&mut ctx),
+        );
+        assert!(html.contains("<pre data-language=\"rust\" class=\"hl-code\">"));
+        assert!(!html.contains("style="));
+        assert_eq!(assets.included_styles().count(), 1);
+
+        // Without code blocks, there's nothing to style
+        let mut assets = crate::assets::RouteAssets::default();
+        let mut ctx = PageContext::from_static_route(&content, &mut assets, &path, &None, None);
+        render_markdown("No code here", Some(&options), None, Some(&mut ctx));
+        assert_eq!(assets.included_styles().count(), 0);
+    }
+
+    #[test]
+    fn test_highlight_errors_are_reported_to_the_page() {
+        let options = MarkdownOptions {
+            highlight_theme: "missing-theme".to_string(),
+            ..Default::default()
+        };
+        let content = ContentSources::new(vec![]);
+        let mut assets = crate::assets::RouteAssets::default();
+        let path = "/".to_string();
+        let mut ctx = PageContext::from_static_route(&content, &mut assets, &path, &None, None);
+
+        render_markdown(
+            "```rust\nfn main() {}\n```",
+            Some(&options),
+            None,
+            Some(&mut ctx),
+        );
+        assert_eq!(ctx.highlight_errors.len(), 1);
+        assert!(
+            ctx.highlight_errors[0]
+                .to_string()
+                .contains("`missing-theme`")
+        );
+    }
+
+    #[test]
+    fn test_try_render_markdown_returns_errors() {
+        let options = MarkdownOptions {
+            highlight_theme: "missing-theme".to_string(),
+            ..Default::default()
+        };
+        let code = "```rust\nfn main() {}\n```";
+        let error = try_render_markdown(code, Some(&options), None, None).unwrap_err();
+        assert_eq!(error.highlight.len(), 1);
+        assert!(error.shortcodes.is_empty());
+        // Without a page context to fail, the errors are logged
+        render_markdown(code, Some(&options), None, None);
+
+        let mut shortcodes = MarkdownShortcodes::new();
+        shortcodes.register("known", |_, _| String::new());
+        let options = MarkdownOptions {
+            shortcodes,
+            ..Default::default()
+        };
+        let error = try_render_markdown("{{ unknown /}}", Some(&options), None, None).unwrap_err();
+        assert_eq!(error.shortcodes.len(), 1);
+        assert!(error.to_string().contains("Unknown shortcode: 'unknown'"));
+        assert_eq!(
+            try_render_markdown("{{ known /}}", Some(&options), None, None).unwrap(),
+            ""
+        );
+    }
+
+    #[test]
+    fn test_code_block_meta() {
+        let html = render_markdown(
+            "```rust {1} title=\"main.rs\"\nfn main() {}\n```",
+            None,
+            None,
+            None,
+        );
+        assert!(html.starts_with(
+            "<figure class=\"code-block\"><figcaption>main.rs</figcaption><pre data-language=\"rust\">"
+        ));
+        assert!(html.contains("<code data-language=\"rust\"><span class=\"line highlighted\">"));
+    }
+
+    #[test]
+    fn test_missing_highlight_theme_is_an_error() {
+        let options = MarkdownOptions {
+            highlight_theme: "not-a-theme".into(),
+            ..Default::default()
+        };
+        let result = glob_markdown_with_options::<UntypedMarkdownContent>("*.md", options);
+        assert!(matches!(
+            result,
+            Err(ContentError::Highlight(HighlightError::ThemeNotFound(_)))
+        ));
+
+        let options = MarkdownOptions {
+            highlight_syntaxes: vec!["does/not/exist".into()],
+            ..Default::default()
+        };
+        let result = glob_markdown_with_options::<UntypedMarkdownContent>("*.md", options);
+        assert!(matches!(
+            result,
+            Err(ContentError::Highlight(
+                HighlightError::SyntaxLoadFailed { .. }
+            ))
+        ));
+    }
+
+    #[test]
+    fn test_drafts_and_scheduled_entries() {
+        let now = "2025-06-01T00:00:00Z"
+            .parse::<chrono::DateTime<chrono::Utc>>()
+            .unwrap();
+        let is_draft = |content: &str| {
+            deserialize_frontmatter::<Publication>(content, None)
+                .unwrap()
+                .is_draft(now, Path::new("entry.md"))
+        };
+
+        assert!(!is_draft("# No frontmatter"));
+        assert!(!is_draft("---\ntitle: Published\n---\n"));
+        assert!(is_draft("---\ndraft: true\n---\n"));
+        assert!(!is_draft("---\npublish_date: 2025-05-31\n---\n"));
+        assert!(is_draft("---\npublish_date: 2025-06-02\n---\n"));
+        assert!(!is_draft(
+            "---\npublish_date: \"2025-06-01T01:00:00+02:00\"\n---\n"
+        ));
+        assert!(is_draft("+++\npublish_date = 2025-06-01T12:00:00\n+++\n"));
+        assert!(is_draft("{\n\"publish_date\": \"2026-01-01\"\n}\n\nText"));
+
+        // Values that can't be read are ignored, as the entry may use these fields for something else
+        assert!(!is_draft("---\npublish_date: next week\n---\n"));
+        assert!(!is_draft("---\npublish_date: 2026/01/05\n---\n"));
+        assert!(!is_draft("---\npublish_date: ~\n---\n"));
+        assert!(!is_draft("---\ndraft: \"yes\"\n---\n"));
+        assert!(is_draft(
+            "---\ndraft: [true]\npublish_date: 2026-01-01\n---\n"
+        ));
+    }
+
+    #[test]
+    fn test_glob_markdown_marks_drafts() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(
+            dir.path().join("published.md"),
+            "---\ntitle: Published\ntags: []\n---\n",
+        )
+        .unwrap();
+        std::fs::write(
+            dir.path().join("draft.md"),
+            "---\ntitle: Draft\ntags: []\ndraft: true\n---\n",
+        )
+        .unwrap();
+
+        let pattern = dir.path().join("*.md").to_string_lossy().to_string();
+        let entries = glob_markdown::<FrontmatterTestContent>(&pattern).unwrap();
+        let drafts: Vec<_> = entries
+            .iter()
+            .map(|entry| (entry.id.as_str(), entry.is_draft()))
+            .collect();
+        assert!(drafts.contains(&("draft", true)));
+        assert!(drafts.contains(&("published", false)));
+
+        // An invalid `draft` field doesn't fail the build, the entry is published
+        std::fs::write(
+            dir.path().join("draft.md"),
+            "---\ntitle: Draft\ntags: []\ndraft: maybe\n---\n",
+        )
+        .unwrap();
+        let entries = glob_markdown::<FrontmatterTestContent>(&pattern).unwrap();
+        assert!(entries.iter().all(|entry| !entry.is_draft()));
+    }
+
+    #[test]
+    fn test_markdown_extensions() {
+        let render = |content: &str, extensions: MarkdownExtensions| {
+            let options = MarkdownOptions {
+                extensions,
+                ..Default::default()
+            };
+            render_markdown(content, Some(&options), None, None)
+        };
+
+        let prices = "From $5-$10";
+        assert!(render(prices, MarkdownExtensions::default()).contains("<math"));
+        let without_math = MarkdownExtensions {
+            math: false,
+            ..Default::default()
+        };
+        assert_eq!(render(prices, without_math), "<p>From $5-$10</p>\n");
+
+        let extensions = MarkdownExtensions {
+            smart_punctuation: true,
+            heading_attributes: true,
+            definition_lists: true,
+            superscript: true,
+            subscript: true,
+            ..Default::default()
+        };
+        assert_eq!(
+            render("\"Quoted\" -- text...", extensions),
+            "<p>“Quoted” – text…</p>\n"
+        );
+        assert_eq!(
+            render("## Title {#custom .wide}", extensions),
+            "<h2 id=\"custom\" class=\"wide\">Title</h2>"
+        );
+        assert_eq!(
+            render("Term\n: Definition", extensions),
+            "<dl>\n<dt>Term</dt>\n<dd>Definition</dd>\n</dl>\n"
+        );
+        assert_eq!(
+            render("^Super^, ~sub~ and ~~gone~~", extensions),
+            "<p><sup>Super</sup>, <sub>sub</sub> and <del>gone</del></p>\n"
+        );
+
+        // Without the extensions, the syntax is left as is
+        assert_eq!(
+            render("Term\n: Definition", MarkdownExtensions::default()),
+            "<p>Term\n: Definition</p>\n"
+        );
+    }
+
+    #[test]
+    fn test_entry_render_text() {
+        use crate::content::ContentEntry;
+
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(
+            dir.path().join("post.md"),
+            "---\ntitle: Post\ntags: []\n---\n\n# Post\n\nSome <em>text</em>.\n",
+        )
+        .unwrap();
+
+        let pattern = dir.path().join("*.md").to_string_lossy().to_string();
+        let entries = glob_markdown::<FrontmatterTestContent>(&pattern).unwrap();
+        assert_eq!(
+            entries[0]
+                .render_text(&TextOptions::default())
+                .unwrap()
+                .unwrap(),
+            "Some text."
+        );
+
+        // Only Markdown entries can be rendered to text
+        let data_entry = Entry::create("data".into(), None, Some("Raw".into()), (), vec![]);
+        assert!(data_entry.render_text(&TextOptions::default()).is_none());
+    }
+
+    #[test]
+    fn test_entry_render_text_reports_shortcode_errors() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("post.md");
+        std::fs::write(&path, "---\ntitle: Post\ntags: []\n---\n\n{{ unknown /}}\n").unwrap();
+
+        let pattern = dir.path().join("*.md").to_string_lossy().to_string();
+        let mut shortcodes = MarkdownShortcodes::new();
+        shortcodes.register("known", |_, _| String::new());
+        let options = MarkdownOptions {
+            shortcodes,
+            ..Default::default()
+        };
+        let entries =
+            glob_markdown_with_options::<FrontmatterTestContent>(&pattern, options).unwrap();
+        let error = entries[0]
+            .render_text(&TextOptions::default())
+            .unwrap()
+            .unwrap_err();
+        assert_eq!(error.path.as_deref(), Some(path.as_path()));
+    }
+
+    #[test]
+    fn test_markdown_extensions_are_a_dependency_of_entries() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(
+            dir.path().join("post.md"),
+            "---\ntitle: Post\ntags: []\n---\n",
+        )
+        .unwrap();
+        let pattern = dir.path().join("*.md").to_string_lossy().to_string();
+
+        let dependency = |extensions: MarkdownExtensions| {
+            let options = MarkdownOptions {
+                extensions,
+                ..Default::default()
+            };
+            let entries =
+                glob_markdown_with_options::<FrontmatterTestContent>(&pattern, options).unwrap();
+            entries[0]
+                .dependencies
+                .iter()
+                .find_map(|dependency| match dependency {
+                    Dependency::Custom { key, hash } if key == "markdown-extensions" => {
+                        Some(hash.clone())
+                    }
+                    _ => None,
+                })
+                .unwrap()
+        };
+
+        let default = dependency(MarkdownExtensions::default());
+        assert_eq!(default, dependency(MarkdownExtensions::default()));
+        assert_ne!(
+            default,
+            dependency(MarkdownExtensions {
+                smart_punctuation: true,
+                ..Default::default()
+            })
+        );
+    }
+
+    #[test]
+    fn test_summary_excerpt_and_reading_time() {
+        let summary = |content: &str, options: &MarkdownOptions| {
+            summary_from_events(&body_events(content, Some(options)), Some(options))
+        };
+        let options = MarkdownOptions {
+            excerpt_length: 21,
+            words_per_minute: 4,
+            ..Default::default()
+        };
+
+        let truncated = summary(
+            "---\ntitle: Hello\n---\n# Title\n\nSome *emphasized* `code`\nand **bold**words.",
+            &options,
+        );
+        assert_eq!(truncated.excerpt, "Title Some emphasized");
+        assert_eq!(truncated.word_count, 6);
+        assert_eq!(truncated.reading_time, 2);
+
+        let with_marker = summary(
+            "First paragraph.\n\n<!-- more -->\n\nThe rest of the article.",
+            &options,
+        );
+        assert_eq!(with_marker.excerpt, "First paragraph.");
+        assert_eq!(with_marker.word_count, 7);
+
+        let long_word = summary("Supercalifragilisticexpialidocious", &options);
+        assert_eq!(long_word.excerpt, "Supercalifragilistice");
+        assert_eq!(long_word.reading_time, 1);
+
+        let empty = summary("---\ntitle: Empty\n---\n", &options);
+        assert_eq!(empty.excerpt, "");
+        assert_eq!(empty.word_count, 0);
+        assert_eq!(empty.reading_time, 0);
+    }
+
+    #[test]
+    fn test_summary_expands_shortcodes_and_leaves_out_code_blocks() {
+        let mut shortcodes = MarkdownShortcodes::new();
+        shortcodes.register("greeting", |args, _| {
+            format!("Hello {}!", args.get_str("name").unwrap_or_default())
+        });
+        let options = MarkdownOptions {
+            shortcodes,
+            ..Default::default()
+        };
+
+        let summary = entry_summary(
+            "{{ greeting name=\"world\" /}}\n\n```rust\nfn main() {}\n```\n\nThe `end`.",
+            Some(&options),
+        );
+        assert_eq!(summary.excerpt, "Hello world! The end.");
+        assert_eq!(summary.word_count, 4);
+    }
+
+    #[test]
+    fn test_options_fingerprint_covers_highlight_files() {
+        let dir = tempfile::tempdir().unwrap();
+        let syntaxes = dir.path().join("syntaxes");
+        std::fs::create_dir_all(syntaxes.join("nested")).unwrap();
+        std::fs::write(syntaxes.join("nested/a.sublime-syntax"), "first").unwrap();
+        let theme = dir.path().join("theme.tmTheme");
+        std::fs::write(&theme, "first").unwrap();
+
+        let options = MarkdownOptions {
+            highlight_theme: theme.to_string_lossy().to_string(),
+            highlight_syntaxes: vec![syntaxes.clone()],
+            ..Default::default()
+        };
+        let fingerprint = options.fingerprint();
+        assert_eq!(options.fingerprint(), fingerprint);
+
+        // Editing a file in a directory of syntaxes, or a theme given by its path, changes the fingerprint
+        std::fs::write(syntaxes.join("nested/a.sublime-syntax"), "second").unwrap();
+        let edited_syntax = options.fingerprint();
+        assert_ne!(edited_syntax, fingerprint);
+        std::fs::write(&theme, "second").unwrap();
+        assert_ne!(options.fingerprint(), edited_syntax);
+    }
+
+    #[test]
+    fn test_summary_is_part_of_the_data_fingerprint() {
+        let before = "---\ntitle: Hello\n---\nSome text.";
+        let after = "---\ntitle: Hello\n---\nSome other text.";
+        assert_ne!(
+            data_fingerprint(before, None),
+            data_fingerprint(after, None)
+        );
+
+        let parsed: FrontmatterTestContent =
+            parse_markdown_with_frontmatter("---\ntitle: Hello\ntags: []\n---\nSome text.");
+        assert_eq!(parsed.get_summary().excerpt, "Some text.");
+        assert_eq!(parsed.get_summary().word_count, 2);
+    }
+
     // Helper function to create test shortcodes
     fn create_test_shortcodes() -> MarkdownShortcodes {
         let mut shortcodes = MarkdownShortcodes::new();
@@ -1,3 +1,8 @@
+use crate::{
+    assets::{Asset, Image, RouteAssets},
+    content::{highlight::CodeFenceInfo, math::MathDisplay},
+};
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum BlockQuoteKind {
     Note,
@@ -132,6 +137,21 @@ pub trait LinkComponent {
 
 pub trait ImageComponent {
     fn render(&self, url: &str, alt: &str, title: Option<&str>) -> String;
+
+    /// Renders images with a path relative to the Markdown file, ex: `![alt](./image.png)`, once they were added to the
+    /// assets of the page.
+    ///
+    /// `image` gives access to the dimensions and placeholder of the image, and the assets of the page allow adding other
+    /// versions of it, for instance resized for a `srcset`. Defaults to calling [`render`](Self::render) with the URL of the image.
+    fn render_image(
+        &self,
+        image: &Image,
+        alt: &str,
+        title: Option<&str>,
+        _assets: &mut RouteAssets,
+    ) -> String {
+        self.render(image.url(), alt, title)
+    }
 }
 
 pub trait StrongComponent {
@@ -158,6 +178,75 @@ pub trait CodeComponent {
     fn render(&self, code: &str) -> String;
 }
 
+/// Renders fenced code blocks, ex: ```` ```rust ````.
+///
+/// `code` is the raw code of the block and `highlighted_html` the complete `<pre><code>` element Maudit renders by default,
+/// which can be wrapped or replaced, for instance to add copy buttons or filename tabs.
+///
+/// Line options of the fence (highlighted lines, line numbers, diffs) are applied to `highlighted_html`, but its title is not: it's
+/// available in [`CodeFenceInfo::title`] for the component to render.
+pub trait CodeBlockComponent {
+    fn render(&self, info: &CodeFenceInfo, code: &str, highlighted_html: &str) -> String;
+}
+
+/// Renders math expressions, ex: `$x^2$` or `$$\frac{a}{b}$$`.
+///
+/// `latex` is the source of the expression and `mathml` the MathML Maudit renders by default, see [`render_math`](crate::content::render_math).
+pub trait MathComponent {
+    fn render(&self, latex: &str, display: MathDisplay, mathml: &str) -> String;
+}
+
+/// Renders references to footnotes, ex: `[^note]`.
+///
+/// `label` is the label of the footnote, normalized to be usable in ids, and `number` its number, by order of appearance.
+/// `occurrence` counts the references to the same footnote starting from 1, to give every reference a unique id.
+pub trait FootnoteReferenceComponent {
+    fn render(&self, label: &str, number: usize, occurrence: usize) -> String {
+        format!(
+            "<sup class=\"footnote-reference\"><a href=\"#fn-{label}\" id=\"{}\">{number}</a></sup>",
+            footnote_reference_id(label, occurrence)
+        )
+    }
+}
+
+/// Renders footnote definitions, ex: `[^note]: The note.`
+///
+/// `content` is the rendered content of the definition. When footnotes are collected (see [`MarkdownOptions::collect_footnotes`](crate::content::MarkdownOptions::collect_footnotes)),
+/// definitions are rendered as the items of the ordered list at the end of the document, and `back_references` contains the links back to their references.
+pub trait FootnoteDefinitionComponent {
+    fn render(
+        &self,
+        label: &str,
+        number: usize,
+        content: &str,
+        back_references: Option<&str>,
+    ) -> String {
+        match back_references {
+            None => format!(
+                "<div class=\"footnote-definition\" id=\"fn-{label}\"><sup class=\"footnote-definition-label\">{number}</sup>{content}</div>\n"
+            ),
+            Some(back_references) => {
+                // Like GitHub, the links are added to the last paragraph of the footnote
+                let content = content.trim_end();
+                let content = match content.strip_suffix("</p>") {
+                    Some(content) => format!("{content} {back_references}</p>"),
+                    None => format!("{content}\n{back_references}"),
+                };
+                format!("<li id=\"fn-{label}\">\n{content}\n</li>\n")
+            }
+        }
+    }
+}
+
+/// Id of a footnote reference, which the back references of collected footnotes link to.
+pub fn footnote_reference_id(label: &str, occurrence: usize) -> String {
+    if occurrence == 1 {
+        format!("fnref-{label}")
+    } else {
+        format!("fnref-{label}-{occurrence}")
+    }
+}
+
 pub trait BlockquoteComponent {
     fn render_start(&self, kind: Option<BlockQuoteKind>) -> String {
         match kind {
@@ -229,6 +318,56 @@ pub trait StrikethroughComponent {
     }
 }
 
+pub trait SuperscriptComponent {
+    fn render_start(&self) -> String {
+        "<sup>".to_string()
+    }
+
+    fn render_end(&self) -> String {
+        "</sup>".to_string()
+    }
+}
+
+pub trait SubscriptComponent {
+    fn render_start(&self) -> String {
+        "<sub>".to_string()
+    }
+
+    fn render_end(&self) -> String {
+        "</sub>".to_string()
+    }
+}
+
+pub trait DefinitionListComponent {
+    fn render_start(&self) -> String {
+        "<dl>".to_string()
+    }
+
+    fn render_end(&self) -> String {
+        "</dl>".to_string()
+    }
+}
+
+pub trait DefinitionListTitleComponent {
+    fn render_start(&self) -> String {
+        "<dt>".to_string()
+    }
+
+    fn render_end(&self) -> String {
+        "</dt>".to_string()
+    }
+}
+
+pub trait DefinitionListDefinitionComponent {
+    fn render_start(&self) -> String {
+        "<dd>".to_string()
+    }
+
+    fn render_end(&self) -> String {
+        "</dd>".to_string()
+    }
+}
+
 pub trait TaskListMarkerComponent {
     fn render(&self, checked: bool) -> String {
         if checked {
@@ -311,12 +450,22 @@ pub struct MarkdownComponents {
     pub strong: Option<Box<dyn StrongComponent + Send + Sync>>,
     pub emphasis: Option<Box<dyn EmphasisComponent + Send + Sync>>,
     pub code: Option<Box<dyn CodeComponent + Send + Sync>>,
+    pub code_block: Option<Box<dyn CodeBlockComponent + Send + Sync>>,
+    pub math: Option<Box<dyn MathComponent + Send + Sync>>,
+    pub footnote_reference: Option<Box<dyn FootnoteReferenceComponent + Send + Sync>>,
+    pub footnote_definition: Option<Box<dyn FootnoteDefinitionComponent + Send + Sync>>,
     pub blockquote: Option<Box<dyn BlockquoteComponent + Send + Sync>>,
     pub hard_break: Option<Box<dyn HardBreakComponent + Send + Sync>>,
     pub horizontal_rule: Option<Box<dyn HorizontalRuleComponent + Send + Sync>>,
     pub list: Option<Box<dyn ListComponent + Send + Sync>>,
     pub list_item: Option<Box<dyn ListItemComponent + Send + Sync>>,
     pub strikethrough: Option<Box<dyn StrikethroughComponent + Send + Sync>>,
+    pub superscript: Option<Box<dyn SuperscriptComponent + Send + Sync>>,
+    pub subscript: Option<Box<dyn SubscriptComponent + Send + Sync>>,
+    pub definition_list: Option<Box<dyn DefinitionListComponent + Send + Sync>>,
+    pub definition_list_title: Option<Box<dyn DefinitionListTitleComponent + Send + Sync>>,
+    pub definition_list_definition:
+        Option<Box<dyn DefinitionListDefinitionComponent + Send + Sync>>,
     pub task_list_marker: Option<Box<dyn TaskListMarkerComponent + Send + Sync>>,
     pub table: Option<Box<dyn TableComponent + Send + Sync>>,
     pub table_head: Option<Box<dyn TableHeadComponent + Send + Sync>>,
@@ -337,12 +486,21 @@ impl MarkdownComponents {
             || self.strong.is_some()
             || self.emphasis.is_some()
             || self.code.is_some()
+            || self.code_block.is_some()
+            || self.math.is_some()
+            || self.footnote_reference.is_some()
+            || self.footnote_definition.is_some()
             || self.blockquote.is_some()
             || self.hard_break.is_some()
             || self.horizontal_rule.is_some()
             || self.list.is_some()
             || self.list_item.is_some()
             || self.strikethrough.is_some()
+            || self.superscript.is_some()
+            || self.subscript.is_some()
+            || self.definition_list.is_some()
+            || self.definition_list_title.is_some()
+            || self.definition_list_definition.is_some()
             || self.task_list_marker.is_some()
             || self.table.is_some()
             || self.table_head.is_some()
@@ -395,6 +553,39 @@ impl MarkdownComponents {
         self
     }
 
+    /// Set a custom code block component
+    pub fn code_block<C: CodeBlockComponent + Send + Sync + 'static>(
+        mut self,
+        component: C,
+    ) -> Self {
+        self.code_block = Some(Box::new(component));
+        self
+    }
+
+    /// Set a custom math component
+    pub fn math<C: MathComponent + Send + Sync + 'static>(mut self, component: C) -> Self {
+        self.math = Some(Box::new(component));
+        self
+    }
+
+    /// Set a custom footnote reference component
+    pub fn footnote_reference<C: FootnoteReferenceComponent + Send + Sync + 'static>(
+        mut self,
+        component: C,
+    ) -> Self {
+        self.footnote_reference = Some(Box::new(component));
+        self
+    }
+
+    /// Set a custom footnote definition component
+    pub fn footnote_definition<C: FootnoteDefinitionComponent + Send + Sync + 'static>(
+        mut self,
+        component: C,
+    ) -> Self {
+        self.footnote_definition = Some(Box::new(component));
+        self
+    }
+
     /// Set a custom blockquote component
     pub fn blockquote<C: BlockquoteComponent + Send + Sync + 'static>(
         mut self,
@@ -443,6 +634,53 @@ impl MarkdownComponents {
         self
     }
 
+    /// Set a custom superscript component
+    pub fn superscript<C: SuperscriptComponent + Send + Sync + 'static>(
+        mut self,
+        component: C,
+    ) -> Self {
+        self.superscript = Some(Box::new(component));
+        self
+    }
+
+    /// Set a custom subscript component
+    pub fn subscript<C: SubscriptComponent + Send + Sync + 'static>(
+        mut self,
+        component: C,
+    ) -> Self {
+        self.subscript = Some(Box::new(component));
+        self
+    }
+
+    /// Set a custom definition list component
+    pub fn definition_list<C: DefinitionListComponent + Send + Sync + 'static>(
+        mut self,
+        component: C,
+    ) -> Self {
+        self.definition_list = Some(Box::new(component));
+        self
+    }
+
+    /// Set a custom definition list title component
+    pub fn definition_list_title<C: DefinitionListTitleComponent + Send + Sync + 'static>(
+        mut self,
+        component: C,
+    ) -> Self {
+        self.definition_list_title = Some(Box::new(component));
+        self
+    }
+
+    /// Set a custom definition list definition component
+    pub fn definition_list_definition<
+        C: DefinitionListDefinitionComponent + Send + Sync + 'static,
+    >(
+        mut self,
+        component: C,
+    ) -> Self {
+        self.definition_list_definition = Some(Box::new(component));
+        self
+    }
+
     /// Set a custom task list marker component
     pub fn task_list_marker<C: TaskListMarkerComponent + Send + Sync + 'static>(
         mut self,
@@ -666,6 +904,87 @@ mod tests {
         );
     }
 
+    struct TestResponsiveImage;
+
+    impl ImageComponent for TestResponsiveImage {
+        fn render(&self, url: &str, alt: &str, _title: Option<&str>) -> String {
+            format!("<img src=\"{}\" alt=\"{}\">", url, alt)
+        }
+
+        fn render_image(
+            &self,
+            image: &Image,
+            alt: &str,
+            _title: Option<&str>,
+            assets: &mut RouteAssets,
+        ) -> String {
+            let (width, height) = image.dimensions();
+            let small = assets
+                .add_image_with_options(
+                    image.path.clone(),
+                    crate::assets::ImageOptions {
+                        width: Some(width / 2),
+                        format: Some(crate::assets::ImageFormat::WebP),
+                        ..Default::default()
+                    },
+                )
+                .unwrap();
+            let placeholder = image.placeholder().unwrap();
+
+            format!(
+                "<img src=\"{}\" srcset=\"{} {}w, {} {}w\" width=\"{}\" height=\"{}\" alt=\"{}\" loading=\"lazy\" style=\"background: url('{}')\">",
+                image.url(),
+                small.url(),
+                width / 2,
+                image.url(),
+                width,
+                width,
+                height,
+                alt,
+                placeholder.data_uri()
+            )
+        }
+    }
+
+    #[test]
+    fn test_image_component_with_assets() {
+        let dir = tempfile::tempdir().unwrap();
+        image::RgbaImage::from_pixel(40, 20, image::Rgba([255, 0, 0, 255]))
+            .save(dir.path().join("photo.png"))
+            .unwrap();
+
+        let options = MarkdownOptions {
+            components: MarkdownComponents::new().image(TestResponsiveImage),
+            ..Default::default()
+        };
+        let content_sources = crate::content::ContentSources::new(vec![]);
+        let mut assets = RouteAssets::default();
+        let url = "/".to_string();
+        let mut ctx = crate::route::PageContext::from_static_route(
+            &content_sources,
+            &mut assets,
+            &url,
+            &None,
+            None,
+        );
+
+        let html = render_markdown(
+            "![A photo](./photo.png) ![Remote](https://example.com/remote.png)",
+            Some(&options),
+            Some(&dir.path().join("post.md")),
+            Some(&mut ctx),
+        );
+
+        assert!(html.contains(".webp 20w, "), "{html}");
+        assert!(
+            html.contains(".png 40w\" width=\"40\" height=\"20\" alt=\"A photo\" loading=\"lazy\"")
+        );
+        assert!(html.contains("style=\"background: url('data:image/png;base64,"));
+        // Images that aren't assets are rendered from their URL
+        assert!(html.contains("<img src=\"https://example.com/remote.png\" alt=\"Remote\">"));
+        assert_eq!(assets.assets().count(), 2);
+    }
+
     #[test]
     fn test_custom_strong_component() {
         let options = MarkdownOptions {
@@ -927,6 +1246,49 @@ mod tests {
         assert!(content.contains("<del class=\"custom-strike\">"));
     }
 
+    struct TestDefinitionList;
+    impl DefinitionListComponent for TestDefinitionList {
+        fn render_start(&self) -> String {
+            "<dl class=\"glossary\">".to_string()
+        }
+    }
+
+    struct TestDefinitionListTitle;
+    impl DefinitionListTitleComponent for TestDefinitionListTitle {
+        fn render_start(&self) -> String {
+            "<dt><dfn>".to_string()
+        }
+        fn render_end(&self) -> String {
+            "</dfn></dt>".to_string()
+        }
+    }
+
+    struct TestSuperscript;
+    impl SuperscriptComponent for TestSuperscript {
+        fn render_start(&self) -> String {
+            "<sup class=\"custom-sup\">".to_string()
+        }
+    }
+
+    #[test]
+    fn test_definition_list_and_superscript_components() {
+        let options = MarkdownOptions {
+            components: MarkdownComponents::new()
+                .definition_list(TestDefinitionList)
+                .definition_list_title(TestDefinitionListTitle)
+                .superscript(TestSuperscript),
+            extensions: crate::content::MarkdownExtensions {
+                definition_lists: true,
+                superscript: true,
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let content = render_markdown("Term\n: The ^definition^", Some(&options), None, None);
+        assert!(content.contains("<dl class=\"glossary\"><dt><dfn>Term</dfn></dt>"));
+        assert!(content.contains("<dd>The <sup class=\"custom-sup\">definition</sup></dd>"));
+    }
+
     #[test]
     fn test_task_list_component() {
         let options = MarkdownOptions {
@@ -959,4 +1321,119 @@ mod tests {
         assert!(content.contains("<tr class=\"custom-row\">"));
         assert!(content.contains("<td class=\"custom-cell\">"));
     }
+
+    struct TestCodeBlock;
+    impl CodeBlockComponent for TestCodeBlock {
+        fn render(&self, info: &CodeFenceInfo, code: &str, highlighted_html: &str) -> String {
+            format!(
+                "<figure data-lang=\"{}\" data-meta='{}' data-title=\"{}\" data-code=\"{}\">{}</figure>",
+                info.language.as_deref().unwrap_or_default(),
+                info.meta,
+                info.title.as_deref().unwrap_or_default(),
+                code.trim(),
+                highlighted_html
+            )
+        }
+    }
+
+    struct TestFootnoteReference;
+    impl FootnoteReferenceComponent for TestFootnoteReference {
+        fn render(&self, label: &str, number: usize, occurrence: usize) -> String {
+            format!("<a class=\"note\" href=\"#note-{label}\">[{number}.{occurrence}]</a>")
+        }
+    }
+
+    struct TestFootnoteDefinition;
+    impl FootnoteDefinitionComponent for TestFootnoteDefinition {
+        fn render(
+            &self,
+            label: &str,
+            number: usize,
+            content: &str,
+            back_references: Option<&str>,
+        ) -> String {
+            format!(
+                "<aside id=\"note-{label}\" data-number=\"{number}\" data-collected=\"{}\">{}</aside>",
+                back_references.is_some(),
+                content.trim()
+            )
+        }
+    }
+
+    #[test]
+    fn test_custom_footnote_components() {
+        let components = || {
+            MarkdownComponents::new()
+                .footnote_reference(TestFootnoteReference)
+                .footnote_definition(TestFootnoteDefinition)
+        };
+        let markdown = "Text[^note] and[^note]\n\n[^note]: A note.";
+
+        let options = MarkdownOptions {
+            components: components(),
+            ..Default::default()
+        };
+        let content = render_markdown(markdown, Some(&options), None, None);
+        assert_eq!(
+            content,
+            "<p>Text<a class=\"note\" href=\"#note-note\">[1.1]</a> and<a class=\"note\" href=\"#note-note\">[1.2]</a></p>\n<aside id=\"note-note\" data-number=\"1\" data-collected=\"false\"><p>A note.</p></aside>"
+        );
+
+        let options = MarkdownOptions {
+            components: components(),
+            collect_footnotes: true,
+            ..Default::default()
+        };
+        let content = render_markdown(markdown, Some(&options), None, None);
+        assert!(content.ends_with(
+            "<section class=\"footnotes\">\n<ol>\n<aside id=\"note-note\" data-number=\"1\" data-collected=\"true\"><p>A note.</p></aside></ol>\n</section>\n"
+        ));
+    }
+
+    struct TestMath;
+    impl MathComponent for TestMath {
+        fn render(&self, latex: &str, display: MathDisplay, mathml: &str) -> String {
+            match display {
+                MathDisplay::Inline => format!("<span data-latex=\"{latex}\">{mathml}</span>"),
+                MathDisplay::Block => format!("<div data-latex=\"{latex}\">{mathml}</div>"),
+            }
+        }
+    }
+
+    #[test]
+    fn test_custom_math_component() {
+        let options = MarkdownOptions {
+            components: MarkdownComponents::new().math(TestMath),
+            ..Default::default()
+        };
+        let content = render_markdown("Inline $x$ and block $$y$$", Some(&options), None, None);
+        assert!(content.starts_with("<p>Inline <span data-latex=\"x\"><math"));
+        assert!(
+            content
+                .contains("</math></span> and block <div data-latex=\"y\"><math display=\"block\"")
+        );
+        assert!(content.ends_with("</math></div></p>\n"));
+    }
+
+    #[test]
+    fn test_custom_code_block_component() {
+        let options = MarkdownOptions {
+            components: MarkdownComponents::new().code_block(TestCodeBlock),
+            ..Default::default()
+        };
+        let content = render_markdown(
+            "```rust title=\"main.rs\"\nfn main() {}\n```",
+            Some(&options),
+            None,
+            None,
+        );
+        assert!(content.starts_with(
+            "<figure data-lang=\"rust\" data-meta='title=\"main.rs\"' data-title=\"main.rs\" data-code=\"fn main() {}\"><pre data-language=\"rust\"><code data-language=\"rust\">"
+        ));
+        assert!(content.contains("</code></pre>\n</figure>"));
+
+        // Indented code blocks have no info and keep their default rendering
+        let content = render_markdown("    let a = 1;", Some(&options), None, None);
+        assert_eq!(content, "<pre><code>let a = 1;</code></pre>\n");
+    }
 }
@@ -0,0 +1,198 @@
+use pulldown_cmark::{Event, Tag, TagEnd, html::push_html};
+use rustc_hash::FxHashMap;
+use slug::slugify;
+
+use super::{
+    MarkdownOptions,
+    components::{FootnoteDefinitionComponent, FootnoteReferenceComponent, footnote_reference_id},
+};
+
+struct DefaultFootnoteReference;
+impl FootnoteReferenceComponent for DefaultFootnoteReference {}
+
+struct DefaultFootnoteDefinition;
+impl FootnoteDefinitionComponent for DefaultFootnoteDefinition {}
+
+/// Render the footnote references and definitions of a document, moving the definitions to a section at its end when
+/// [`MarkdownOptions::collect_footnotes`] is enabled.
+///
+/// Runs once every other event has been rendered, as the content of definitions is rendered to a string as a whole.
+pub(super) fn render_footnotes(events: &mut Vec<Event>, options: Option<&MarkdownOptions>) {
+    if !events.iter().any(|event| {
+        matches!(
+            event,
+            Event::FootnoteReference(_) | Event::Start(Tag::FootnoteDefinition(_))
+        )
+    }) {
+        return;
+    }
+
+    let collect = options.is_some_and(|options| options.collect_footnotes);
+    let reference_component: &dyn FootnoteReferenceComponent =
+        match options.and_then(|options| options.components.footnote_reference.as_ref()) {
+            Some(component) => component.as_ref(),
+            None => &DefaultFootnoteReference,
+        };
+    let definition_component: &dyn FootnoteDefinitionComponent =
+        match options.and_then(|options| options.components.footnote_definition.as_ref()) {
+            Some(component) => component.as_ref(),
+            None => &DefaultFootnoteDefinition,
+        };
+
+    // Footnotes are numbered by order of appearance. Collected footnotes only appear through their references,
+    // like on GitHub, so that their numbers follow the order of the list.
+    let mut numbers: FxHashMap<String, usize> = FxHashMap::default();
+    let mut occurrences: FxHashMap<String, usize> = FxHashMap::default();
+    for event in events.iter_mut() {
+        match event {
+            Event::FootnoteReference(label) => {
+                let label = slugify(label.as_ref());
+                let next_number = numbers.len() + 1;
+                let number = *numbers.entry(label.clone()).or_insert(next_number);
+                let occurrence = occurrences.entry(label.clone()).or_default();
+                *occurrence += 1;
+
+                *event = Event::InlineHtml(
+                    reference_component
+                        .render(&label, number, *occurrence)
+                        .into(),
+                );
+            }
+            Event::Start(Tag::FootnoteDefinition(label)) if !collect => {
+                let next_number = numbers.len() + 1;
+                numbers
+                    .entry(slugify(label.as_ref()))
+                    .or_insert(next_number);
+            }
+            _ => {}
+        }
+    }
+
+    let mut output = Vec::with_capacity(events.len());
+    let mut collected: Vec<(usize, String, String)> = Vec::new();
+    let mut events_iter = std::mem::take(events).into_iter();
+    while let Some(event) = events_iter.next() {
+        let Event::Start(Tag::FootnoteDefinition(label)) = event else {
+            output.push(event);
+            continue;
+        };
+
+        let mut content = String::new();
+        push_html(
+            &mut content,
+            events_iter
+                .by_ref()
+                .take_while(|event| !matches!(event, Event::End(TagEnd::FootnoteDefinition))),
+        );
+
+        let label = slugify(label.as_ref());
+        // Collected footnotes that are never referenced are left out
+        let Some(&number) = numbers.get(&label) else {
+            continue;
+        };
+
+        if collect {
+            if !collected.iter().any(|(_, other, _)| *other == label) {
+                collected.push((number, label, content));
+            }
+        } else {
+            let html = definition_component.render(&label, number, &content, None);
+            output.push(Event::Html(html.into()));
+        }
+    }
+
+    if !collected.is_empty() {
+        collected.sort_by_key(|(number, _, _)| *number);
+
+        let mut section = String::from("<section class=\"footnotes\">\n<ol>\n");
+        for (number, label, content) in &collected {
+            let back_references = (1..=occurrences[label])
+                .map(|occurrence| back_reference(label, *number, occurrence))
+                .collect::<Vec<_>>()
+                .join(" ");
+            section.push_str(&definition_component.render(
+                label,
+                *number,
+                content,
+                Some(&back_references),
+            ));
+        }
+        section.push_str("</ol>\n</section>\n");
+        output.push(Event::Html(section.into()));
+    }
+
+    *events = output;
+}
+
+fn back_reference(label: &str, number: usize, occurrence: usize) -> String {
+    let (reference, suffix) = if occurrence == 1 {
+        (number.to_string(), String::new())
+    } else {
+        (
+            format!("{number}-{occurrence}"),
+            format!("<sup>{occurrence}</sup>"),
+        )
+    };
+
+    format!(
+        "<a href=\"#{}\" class=\"footnote-backref\" aria-label=\"Back to reference {reference}\">↩{suffix}</a>",
+        footnote_reference_id(label, occurrence)
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::content::{MarkdownOptions, render_markdown};
+
+    const DOCUMENT: &str = "First[^b], second[^a] and first again[^b].\n\n[^a]: Note A.\n\n[^unused]: Never referenced.\n\n[^b]: Note B.\n\n## Next section";
+
+    #[test]
+    fn test_footnotes_in_place() {
+        let html = render_markdown(DOCUMENT, None, None, None);
+
+        assert!(html.contains(
+            "First<sup class=\"footnote-reference\"><a href=\"#fn-b\" id=\"fnref-b\">1</a></sup>"
+        ));
+        assert!(html.contains(
+            "second<sup class=\"footnote-reference\"><a href=\"#fn-a\" id=\"fnref-a\">2</a></sup>"
+        ));
+        assert!(html.contains("<a href=\"#fn-b\" id=\"fnref-b-2\">1</a>"));
+        assert!(html.contains(
+            "<div class=\"footnote-definition\" id=\"fn-a\"><sup class=\"footnote-definition-label\">2</sup><p>Note A.</p>\n</div>"
+        ));
+        assert!(html.contains("id=\"fn-unused\"><sup class=\"footnote-definition-label\">3</sup>"));
+
+        // Definitions stay where they are in the source
+        let definition = html.find("id=\"fn-b\"").unwrap();
+        assert!(definition < html.find("<h2").unwrap());
+    }
+
+    #[test]
+    fn test_collected_footnotes() {
+        let options = MarkdownOptions {
+            collect_footnotes: true,
+            ..Default::default()
+        };
+        let html = render_markdown(DOCUMENT, Some(&options), None, None);
+
+        let (body, footnotes) = html.split_once("<section class=\"footnotes\">").unwrap();
+        assert!(body.contains("<h2"));
+        assert!(!body.contains("Note"));
+        assert!(!html.contains("Never referenced"));
+
+        assert_eq!(
+            footnotes,
+            "\n<ol>\n<li id=\"fn-b\">\n<p>Note B. <a href=\"#fnref-b\" class=\"footnote-backref\" aria-label=\"Back to reference 1\">↩</a> <a href=\"#fnref-b-2\" class=\"footnote-backref\" aria-label=\"Back to reference 1-2\">↩<sup>2</sup></a></p>\n</li>\n<li id=\"fn-a\">\n<p>Note A. <a href=\"#fnref-a\" class=\"footnote-backref\" aria-label=\"Back to reference 2\">↩</a></p>\n</li>\n</ol>\n</section>\n"
+        );
+    }
+
+    #[test]
+    fn test_no_footnotes() {
+        let options = MarkdownOptions {
+            collect_footnotes: true,
+            ..Default::default()
+        };
+        let html = render_markdown("No notes here.", Some(&options), None, None);
+        assert_eq!(html, "<p>No notes here.</p>\n");
+    }
+}
@@ -1,28 +1,93 @@
 use rustc_hash::FxHashMap;
-use std::str::FromStr;
+use serde::de::DeserializeOwned;
+use std::{borrow::Cow, fmt::Display, path::Path, str::FromStr};
 
-use crate::route::PageContext;
+use crate::{
+    content::{MarkdownOptions, highlight::Highlighter},
+    errors::{HighlightError, MarkdownError, ShortcodeError},
+    route::PageContext,
+};
 
 pub type ShortcodeFn =
-    Box<dyn Fn(&ShortcodeArgs, Option<&mut PageContext>) -> String + Send + Sync>;
+    Box<dyn Fn(&ShortcodeArgs, Option<&mut PageContext>) -> Result<String, String> + Send + Sync>;
+
+/// Output of a shortcode function: either the expanded text, or a `Result` whose error fails the build.
+pub trait ShortcodeOutput {
+    fn into_result(self) -> Result<String, String>;
+}
+
+impl ShortcodeOutput for String {
+    fn into_result(self) -> Result<String, String> {
+        Ok(self)
+    }
+}
+
+impl<E: Display> ShortcodeOutput for Result<String, E> {
+    fn into_result(self) -> Result<String, String> {
+        self.map_err(|err| err.to_string())
+    }
+}
+
+/// How the content between the opening and closing tags of a block shortcode is passed to it, as its `body` argument.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ShortcodeBody {
+    /// The Markdown source of the content, with the shortcodes it contains expanded.
+    #[default]
+    Markdown,
+    /// The content rendered to HTML, using the same Markdown options as the document.
+    Html,
+}
+
+struct RegisteredShortcode {
+    func: ShortcodeFn,
+    body: ShortcodeBody,
+}
 
 #[derive(Default)]
-pub struct MarkdownShortcodes(FxHashMap<String, ShortcodeFn>);
+pub struct MarkdownShortcodes(FxHashMap<String, RegisteredShortcode>);
 
 impl MarkdownShortcodes {
     pub fn new() -> Self {
         Self(FxHashMap::default())
     }
 
-    pub fn register<F>(&mut self, name: &str, func: F)
+    pub fn register<F, O>(&mut self, name: &str, func: F)
+    where
+        F: Fn(&ShortcodeArgs, Option<&mut PageContext>) -> O + Send + Sync + 'static,
+        O: ShortcodeOutput,
+    {
+        self.register_with_body(name, ShortcodeBody::Markdown, func);
+    }
+
+    /// Register a shortcode, choosing how the content of its block form is passed to it.
+    pub fn register_with_body<F, O>(&mut self, name: &str, body: ShortcodeBody, func: F)
     where
-        F: Fn(&ShortcodeArgs, Option<&mut PageContext>) -> String + Send + Sync + 'static,
+        F: Fn(&ShortcodeArgs, Option<&mut PageContext>) -> O + Send + Sync + 'static,
+        O: ShortcodeOutput,
     {
-        self.0.insert(name.to_string(), Box::new(func));
+        self.0.insert(
+            name.to_string(),
+            RegisteredShortcode {
+                func: Box::new(move |args, route_ctx| func(args, route_ctx).into_result()),
+                body,
+            },
+        );
     }
 
-    pub(crate) fn get(&self, name: &str) -> Option<&ShortcodeFn> {
-        self.0.get(name)
+    /// Register a shortcode whose arguments are deserialized into `A`, see [`ShortcodeArgs::deserialize`].
+    /// Arguments that can't be deserialized fail the build.
+    pub fn register_typed<A, F, O>(&mut self, name: &str, func: F)
+    where
+        A: DeserializeOwned,
+        F: Fn(A, Option<&mut PageContext>) -> O + Send + Sync + 'static,
+        O: ShortcodeOutput,
+    {
+        self.register(name, move |args, route_ctx| {
+            let args = args
+                .deserialize::<A>()
+                .map_err(|err| format!("invalid arguments: {}", err))?;
+            func(args, route_ctx).into_result()
+        });
     }
 
     pub(crate) fn is_empty(&self) -> bool {
@@ -30,295 +95,382 @@ impl MarkdownShortcodes {
     }
 }
 
-// Helper function to validate shortcode names
-// Valid names match ^[A-Za-z_][0-9A-Za-z_]+$ pattern
+// Valid names match ^[A-Za-z_][0-9A-Za-z_]+$
 fn is_valid_shortcode_name(name: &str) -> bool {
-    if name.len() < 2 {
-        return false; // Must have at least 2 characters
-    }
-
     let mut chars = name.chars();
+    name.len() >= 2
+        && chars
+            .next()
+            .is_some_and(|first| first.is_ascii_alphabetic() || first == '_')
+        && chars.all(|ch| ch.is_ascii_alphanumeric() || ch == '_')
+}
 
-    // First character must be A-Z, a-z, or _
-    let first = chars.next().unwrap();
-    if !first.is_ascii_alphabetic() && first != '_' {
-        return false;
+/// Error located at a byte offset of the source. The path of the file is added by [`expand_shortcodes`].
+fn error(source: &str, offset: usize, message: String) -> ShortcodeError {
+    let before = &source[..offset];
+    ShortcodeError {
+        path: None,
+        line: before.matches('\n').count() + 1,
+        column: before.rsplit('\n').next().unwrap_or("").chars().count() + 1,
+        message,
     }
+}
 
-    // Remaining characters must be A-Z, a-z, 0-9, or _
-    for ch in chars {
-        if !ch.is_ascii_alphanumeric() && ch != '_' {
-            return false;
-        }
-    }
+struct Tag<'a> {
+    name: &'a str,
+    args: FxHashMap<String, String>,
+    /// Byte offset of the opening `{{` of the tag.
+    offset: usize,
+}
 
-    true
+enum Token<'a> {
+    Text(Cow<'a, str>),
+    Open(Tag<'a>),
+    SelfClosing(Tag<'a>),
+    Close { name: &'a str, offset: usize },
 }
 
-pub fn preprocess_shortcodes(
-    content: &str,
-    shortcodes: &MarkdownShortcodes,
-    mut route_ctx: Option<&mut PageContext>,
-    markdown_path: Option<&str>,
-) -> Result<String, String> {
-    let mut output = String::new();
-    let mut rest = content;
-
-    // TODO: Rewrite all of this or at least review it carefully, it's a mess and it was generated by AI
-    while let Some(start) = rest.find("{{") {
-        // Check for escaped shortcode syntax like `\{{` - if found, skip this occurrence
-        if start > 0 && rest.chars().nth(start - 1) == Some('\\') {
-            // Remove the backslash and output the literal {{
-            output.push_str(&rest[..start - 1]); // up to the backslash
-            output.push_str("{{"); // output {{
-            rest = &rest[start + 2..];
+enum Node<'a> {
+    Text(Cow<'a, str>),
+    Shortcode {
+        tag: Tag<'a>,
+        body: Option<Vec<Node<'a>>>,
+    },
+}
+
+/// Split the source into text and shortcode tags.
+///
+/// A tag spans from `{{` to the next `}}` and can't contain another `{{`. Tags that aren't valid shortcodes, for
+/// instance `{{ 1 + 1 }}`, are kept as text, and `\{{` can be used to write a literal `{{` in any case.
+fn tokenize(source: &str) -> Result<Vec<Token<'_>>, ShortcodeError> {
+    let mut tokens = Vec::new();
+    let mut text_start = 0;
+    let mut position = 0;
+
+    while let Some(found) = source[position..].find("{{") {
+        let start = position + found;
+        let inner_start = start + 2;
+
+        if source[..start].ends_with('\\') {
+            tokens.push(Token::Text(Cow::Borrowed(&source[text_start..start - 1])));
+            tokens.push(Token::Text(Cow::Borrowed("{{")));
+            position = inner_start;
+            text_start = position;
             continue;
         }
 
-        // Add everything before the shortcode
-        output.push_str(&rest[..start]);
-
-        // Find the end of the opening shortcode tag
-        let remaining = &rest[start + 2..];
-        let Some(tag_end) = remaining.find("}}") else {
-            // No closing }}, treat as literal text
-            output.push_str("{{");
-            rest = remaining;
-            continue;
+        let inner = source[inner_start..]
+            .find("}}")
+            .map(|length| &source[inner_start..inner_start + length])
+            .filter(|inner| !inner.contains("{{"));
+        let token = match inner {
+            Some(inner) => parse_tag(source, inner, start)?,
+            None => None,
         };
 
-        let shortcode_content = remaining[..tag_end].trim();
+        match token {
+            Some(token) => {
+                tokens.push(Token::Text(Cow::Borrowed(&source[text_start..start])));
+                tokens.push(token);
+                position = inner_start + inner.map_or(0, str::len) + 2;
+                text_start = position;
+            }
+            None => position = inner_start,
+        }
+    }
 
-        // Check if this is a self-closing shortcode (ends with /)
-        let is_self_closing = shortcode_content.ends_with('/');
-        let shortcode_content = if is_self_closing {
-            shortcode_content.trim_end_matches('/').trim()
-        } else {
-            shortcode_content
-        };
+    tokens.push(Token::Text(Cow::Borrowed(&source[text_start..])));
+    Ok(tokens)
+}
 
-        // Parse shortcode name and arguments
-        let mut parts = shortcode_content.split_whitespace();
-        let name = parts.next().ok_or("Empty shortcode")?;
+/// Parse the content of a tag, between `{{` and `}}`. Returns `None` if the tag isn't a shortcode.
+fn parse_tag<'a>(
+    source: &str,
+    inner: &'a str,
+    offset: usize,
+) -> Result<Option<Token<'a>>, ShortcodeError> {
+    let inner = inner.trim();
+    if inner.is_empty() {
+        return Err(error(source, offset, "Empty shortcode".to_string()));
+    }
 
-        // Check if this is a closing tag
-        if name.starts_with('/') {
-            return Err(format!("Unexpected closing tag: {}", name));
+    if let Some(closing) = inner.strip_prefix('/') {
+        let closing = closing.trim();
+        let mut parts = closing.split_whitespace();
+        let name = parts.next().unwrap_or_default();
+        if !is_valid_shortcode_name(name) {
+            return Ok(None);
+        }
+        if parts.next().is_some() {
+            return Err(error(
+                source,
+                offset,
+                format!("Closing tag '/{}' can't have arguments", name),
+            ));
         }
 
-        // Validate shortcode name format
-        let actual_name = name.strip_prefix('/').unwrap_or(name);
+        return Ok(Some(Token::Close { name, offset }));
+    }
 
-        if !is_valid_shortcode_name(actual_name) {
-            // Invalid shortcode name, treat as literal text and continue
-            output.push_str("{{");
-            rest = remaining;
-            continue;
+    let (inner, is_self_closing) = match inner.strip_suffix('/') {
+        Some(inner) => (inner.trim_end(), true),
+        None => (inner, false),
+    };
+
+    let (name, args) = inner.split_once(char::is_whitespace).unwrap_or((inner, ""));
+    if !is_valid_shortcode_name(name) {
+        return Ok(None);
+    }
+
+    let args = parse_args(args)
+        .map_err(|err| error(source, offset, format!("{} in shortcode '{}'", err, name)))?;
+    let tag = Tag { name, args, offset };
+
+    Ok(Some(if is_self_closing {
+        Token::SelfClosing(tag)
+    } else {
+        Token::Open(tag)
+    }))
+}
+
+/// Parse `key=value` arguments. Values can be quoted with `"` or `'` to include spaces, in which case `\"`, `\'`,
+/// `\\`, `\n`, `\t` and `\r` are unescaped.
+fn parse_args(args: &str) -> Result<FxHashMap<String, String>, String> {
+    let mut parsed = FxHashMap::default();
+    let mut chars = args.chars().peekable();
+
+    loop {
+        while chars.next_if(|ch| ch.is_whitespace()).is_some() {}
+        if chars.peek().is_none() {
+            return Ok(parsed);
         }
 
-        // Parse arguments with support for quoted values
-        let mut args = FxHashMap::default();
-        let args_str = parts.collect::<Vec<_>>().join(" ");
-
-        if !args_str.is_empty() {
-            let mut chars = args_str.chars().peekable();
-            let mut current_key = String::new();
-            let mut current_value = String::new();
-            let mut in_key = true;
-            let mut in_quotes = false;
-            let mut quote_char = ' ';
-
-            while let Some(ch) = chars.next() {
-                match ch {
-                    '=' if in_key && !in_quotes => {
-                        in_key = false;
-                        // Check if next char is a quote
-                        if let Some(&next_ch) = chars.peek()
-                            && (next_ch == '"' || next_ch == '\'')
-                        {
-                            quote_char = next_ch;
-                            in_quotes = true;
-                            chars.next(); // consume the quote
-                        }
-                    }
-                    '\\' if !in_key && in_quotes => {
-                        // Handle escaped characters
-                        if let Some(escaped_ch) = chars.next() {
-                            match escaped_ch {
-                                '"' | '\'' => {
-                                    // Escaped quote - add literal quote character
-                                    current_value.push(escaped_ch);
-                                }
-                                '\\' => {
-                                    // Escaped backslash - add literal backslash
-                                    current_value.push('\\');
-                                }
-                                'n' => {
-                                    // Escaped newline
-                                    current_value.push('\n');
-                                }
-                                't' => {
-                                    // Escaped tab
-                                    current_value.push('\t');
-                                }
-                                'r' => {
-                                    // Escaped carriage return
-                                    current_value.push('\r');
-                                }
-                                _ => {
-                                    // For any other escaped character, keep the backslash and the character
-                                    current_value.push('\\');
-                                    current_value.push(escaped_ch);
-                                }
-                            }
-                        } else {
-                            // Trailing backslash - add it literally
-                            current_value.push('\\');
-                        }
-                    }
-                    '"' | '\'' if !in_key && in_quotes && ch == quote_char => {
-                        // End of quoted value
-                        in_quotes = false;
-                        args.insert(current_key.trim().to_string(), current_value.clone());
-                        current_key.clear();
-                        current_value.clear();
-                        in_key = true;
-
-                        // Skip any whitespace after the closing quote
-                        while let Some(&next_ch) = chars.peek() {
-                            if next_ch.is_whitespace() {
-                                chars.next();
-                            } else {
-                                break;
-                            }
-                        }
-                    }
-                    ' ' if !in_quotes => {
-                        if !in_key && !current_value.is_empty() {
-                            // End of unquoted value
-                            args.insert(
-                                current_key.trim().to_string(),
-                                current_value.trim().to_string(),
-                            );
-                            current_key.clear();
-                            current_value.clear();
-                            in_key = true;
-                        } else if in_key && !current_key.is_empty() {
-                            return Err(format!(
-                                "Invalid argument format: '{}'. Expected 'key=value'",
-                                current_key
-                            ));
-                        }
-                        // Skip multiple spaces
-                        while let Some(&next_ch) = chars.peek() {
-                            if next_ch == ' ' {
-                                chars.next();
-                            } else {
-                                break;
-                            }
-                        }
-                    }
-                    _ => {
-                        if in_key {
-                            current_key.push(ch);
-                        } else {
-                            current_value.push(ch);
+        let mut key = String::new();
+        while let Some(ch) = chars.next_if(|ch| *ch != '=' && !ch.is_whitespace()) {
+            key.push(ch);
+        }
+        if chars.next_if_eq(&'=').is_none() {
+            return Err(format!(
+                "Invalid argument format: '{}'. Expected 'key=value'",
+                key
+            ));
+        }
+
+        let mut value = String::new();
+        if let Some(quote) = chars.next_if(|ch| *ch == '"' || *ch == '\'') {
+            loop {
+                match chars.next() {
+                    Some(ch) if ch == quote => break,
+                    Some('\\') => match chars.next() {
+                        Some(escaped @ ('"' | '\'' | '\\')) => value.push(escaped),
+                        Some('n') => value.push('\n'),
+                        Some('t') => value.push('\t'),
+                        Some('r') => value.push('\r'),
+                        Some(other) => {
+                            value.push('\\');
+                            value.push(other);
                         }
-                    }
+                        None => return Err("Unclosed quote in argument value".to_string()),
+                    },
+                    Some(ch) => value.push(ch),
+                    None => return Err("Unclosed quote in argument value".to_string()),
                 }
             }
-
-            // Handle the last argument if there's one pending
-            if !in_key && (!current_value.is_empty() || !in_quotes) {
-                if in_quotes {
-                    return Err("Unclosed quote in argument value".to_string());
-                }
-                args.insert(
-                    current_key.trim().to_string(),
-                    current_value.trim().to_string(),
-                );
-            } else if !current_key.trim().is_empty() {
-                return Err(format!(
-                    "Invalid argument format: '{}'. Expected 'key=value'",
-                    current_key.trim()
-                ));
+        } else {
+            while let Some(ch) = chars.next_if(|ch| !ch.is_whitespace()) {
+                value.push(ch);
             }
         }
 
-        // Move past the opening tag
-        let after_opening_tag = &remaining[tag_end + 2..];
-
-        if is_self_closing {
-            // Self-closing shortcode - execute immediately
-            if let Some(func) = shortcodes.get(name) {
-                let mut shortcode_args = ShortcodeArgs::new(args);
-                shortcode_args.0.insert(
-                    "markdown_path".to_string(),
-                    markdown_path.unwrap_or("").to_string(),
-                );
-                let result = func(&shortcode_args, route_ctx.as_deref_mut());
-                output.push_str(&result);
-            } else {
-                return Err(format!("Unknown shortcode: '{}'", name));
+        parsed.insert(key, value);
+    }
+}
+
+/// Match opening and closing tags into a tree of shortcodes.
+fn parse<'a>(source: &str, tokens: Vec<Token<'a>>) -> Result<Vec<Node<'a>>, ShortcodeError> {
+    let mut open: Vec<(Tag<'a>, Vec<Node<'a>>)> = Vec::new();
+    let mut nodes = Vec::new();
+
+    for token in tokens {
+        let node = match token {
+            Token::Text(text) => Node::Text(text),
+            Token::SelfClosing(tag) => Node::Shortcode { tag, body: None },
+            Token::Open(tag) => {
+                open.push((tag, std::mem::take(&mut nodes)));
+                continue;
             }
+            Token::Close { name, offset } => {
+                let Some((tag, parent_nodes)) = open.pop() else {
+                    return Err(error(
+                        source,
+                        offset,
+                        format!("Unexpected closing tag: /{}", name),
+                    ));
+                };
+                if tag.name != name {
+                    let opening = error(source, tag.offset, String::new());
+                    return Err(error(
+                        source,
+                        offset,
+                        format!(
+                            "Unexpected closing tag: /{}, expected '{{{{ /{} }}}}' to close the shortcode opened at line {}, column {}",
+                            name, tag.name, opening.line, opening.column
+                        ),
+                    ));
+                }
 
-            // Continue after the opening tag
-            rest = after_opening_tag;
-        } else {
-            // Block shortcode - look for closing tag
-            let closing_tag_compact = format!("{{{{/{}}}}}", name);
-            let closing_tag_spaced = format!("{{{{ /{} }}}}", name);
-
-            let close_pos = after_opening_tag
-                .find(&closing_tag_compact)
-                .or_else(|| after_opening_tag.find(&closing_tag_spaced));
-
-            if let Some(close_pos) = close_pos {
-                // Determine which closing tag format was found to calculate the correct length
-                let closing_tag_len =
-                    if after_opening_tag[close_pos..].starts_with(&closing_tag_compact) {
-                        closing_tag_compact.len()
-                    } else {
-                        closing_tag_spaced.len()
-                    };
-
-                // Block shortcode - extract body and recursively process it
-                let body = &after_opening_tag[..close_pos];
-                let processed_body = preprocess_shortcodes(
-                    body,
-                    shortcodes,
-                    route_ctx.as_deref_mut(),
-                    markdown_path,
-                )?;
-
-                // Execute shortcode with processed body
-                if let Some(func) = shortcodes.get(name) {
-                    let mut shortcode_args = ShortcodeArgs::new(args);
-                    shortcode_args.0.insert("body".to_string(), processed_body);
-                    shortcode_args.0.insert(
-                        "markdown_path".to_string(),
-                        markdown_path.unwrap_or("").to_string(),
-                    );
-                    let result = func(&shortcode_args, route_ctx.as_deref_mut());
-                    output.push_str(&result);
-                } else {
-                    return Err(format!("Unknown shortcode: '{}'", name));
+                let body = std::mem::replace(&mut nodes, parent_nodes);
+                Node::Shortcode {
+                    tag,
+                    body: Some(body),
                 }
+            }
+        };
+        nodes.push(node);
+    }
+
+    if let Some((tag, _)) = open.pop() {
+        return Err(error(
+            source,
+            tag.offset,
+            format!(
+                "Block shortcode '{}' is missing its closing tag. Use '{{{{ {} /}}}}' for self-closing shortcodes or add '{{{{ /{} }}}}'",
+                tag.name, tag.name, tag.name
+            ),
+        ));
+    }
 
-                // Continue after the closing tag
-                rest = &after_opening_tag[close_pos + closing_tag_len..];
-            } else {
-                // No closing tag found for block shortcode - this is an error
-                return Err(format!(
-                    "Block shortcode '{}' is missing its closing tag. Use '{{{{ {} /}}}}' for self-closing shortcodes or add '{{{{/{}}}}}'",
-                    name, name, name
+    Ok(nodes)
+}
+
+struct Expander<'a> {
+    source: &'a str,
+    shortcodes: &'a MarkdownShortcodes,
+    options: Option<&'a MarkdownOptions>,
+    highlighter: Option<&'a Highlighter>,
+    markdown_path: Option<&'a str>,
+    /// Errors highlighting the code blocks of shortcodes whose body is rendered to HTML.
+    highlight_errors: &'a mut Vec<HighlightError>,
+}
+
+impl Expander<'_> {
+    fn expand(
+        &mut self,
+        nodes: Vec<Node>,
+        mut route_ctx: Option<&mut PageContext>,
+    ) -> Result<String, ShortcodeError> {
+        let mut output = String::new();
+
+        for node in nodes {
+            let (tag, body) = match node {
+                Node::Text(text) => {
+                    output.push_str(&text);
+                    continue;
+                }
+                Node::Shortcode { tag, body } => (tag, body),
+            };
+
+            let Some(shortcode) = self.shortcodes.0.get(tag.name) else {
+                return Err(error(
+                    self.source,
+                    tag.offset,
+                    format!("Unknown shortcode: '{}'", tag.name),
                 ));
+            };
+
+            let mut args = ShortcodeArgs::new(tag.args);
+            if let Some(body) = body {
+                let body = self.expand(body, route_ctx.as_deref_mut())?;
+                let body = match shortcode.body {
+                    ShortcodeBody::Markdown => body,
+                    ShortcodeBody::Html => super::render_expanded_markdown(
+                        &body,
+                        self.options,
+                        self.highlighter,
+                        self.markdown_path.map(Path::new),
+                        route_ctx.as_deref_mut(),
+                        self.highlight_errors,
+                    ),
+                };
+                args.0.insert("body".to_string(), body);
             }
+            args.0.insert(
+                "markdown_path".to_string(),
+                self.markdown_path.unwrap_or("").to_string(),
+            );
+
+            let expanded = (shortcode.func)(&args, route_ctx.as_deref_mut()).map_err(|err| {
+                error(
+                    self.source,
+                    tag.offset,
+                    format!("Shortcode '{}' failed: {}", tag.name, err),
+                )
+            })?;
+            output.push_str(&expanded);
         }
+
+        Ok(output)
     }
+}
 
-    output.push_str(rest);
-    Ok(output)
+/// Expand the shortcodes of a Markdown document.
+///
+/// Shortcodes are either self-closing, `{{ name key=value /}}`, or blocks, `{{ name }}...{{ /name }}`, in which case
+/// their content is passed to them as the `body` argument. Blocks can contain other shortcodes, including ones with the
+/// same name.
+pub fn preprocess_shortcodes(
+    content: &str,
+    shortcodes: &MarkdownShortcodes,
+    mut route_ctx: Option<&mut PageContext>,
+    markdown_path: Option<&str>,
+) -> Result<String, ShortcodeError> {
+    let mut highlight_errors = Vec::new();
+    let expanded = expand_shortcodes(
+        content,
+        shortcodes,
+        None,
+        None,
+        route_ctx.as_deref_mut(),
+        markdown_path,
+        &mut highlight_errors,
+    );
+    super::report_errors(
+        MarkdownError {
+            highlight: highlight_errors,
+            ..Default::default()
+        },
+        route_ctx,
+    );
+    expanded
+}
+
+pub(crate) fn expand_shortcodes(
+    content: &str,
+    shortcodes: &MarkdownShortcodes,
+    options: Option<&MarkdownOptions>,
+    highlighter: Option<&Highlighter>,
+    route_ctx: Option<&mut PageContext>,
+    markdown_path: Option<&str>,
+    highlight_errors: &mut Vec<HighlightError>,
+) -> Result<String, ShortcodeError> {
+    let expanded = tokenize(content)
+        .and_then(|tokens| parse(content, tokens))
+        .and_then(|nodes| {
+            let mut expander = Expander {
+                source: content,
+                shortcodes,
+                options,
+                highlighter,
+                markdown_path,
+                highlight_errors,
+            };
+            expander.expand(nodes, route_ctx)
+        });
+
+    expanded.map_err(|err| ShortcodeError {
+        path: markdown_path.map(Into::into),
+        ..err
+    })
 }
 
 pub struct ShortcodeArgs(FxHashMap<String, String>);
@@ -377,6 +529,104 @@ impl ShortcodeArgs {
     pub fn get_str_or<'a>(&'a self, key: &str, default: &'a str) -> &'a str {
         self.0.get(key).map(|s| s.as_str()).unwrap_or(default)
     }
+
+    /// Deserialize the arguments into a struct, including the `body` and `markdown_path` arguments.
+    ///
+    /// Values are parsed according to the type of the fields, e.g. `count=3` for a `u32`, `open=true` for a `bool`, or
+    /// `style=compact` for a unit enum variant. Optional fields are `None` when the argument is missing.
+    pub fn deserialize<T: DeserializeOwned>(&self) -> Result<T, serde::de::value::Error> {
+        T::deserialize(serde::de::value::MapDeserializer::new(
+            self.0
+                .iter()
+                .map(|(key, value)| (key.as_str(), ArgValue { key, value })),
+        ))
+    }
+}
+
+/// Deserializer of a single argument, parsing its value to the type requested by the deserialized struct.
+struct ArgValue<'a> {
+    key: &'a str,
+    value: &'a str,
+}
+
+macro_rules! deserialize_parsed {
+    ($($method:ident => $visit:ident),*) => {
+        $(
+            fn $method<V: serde::de::Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+                match self.value.parse() {
+                    Ok(value) => visitor.$visit(value),
+                    Err(err) => Err(serde::de::Error::custom(format_args!(
+                        "invalid value `{}` for `{}`: {}",
+                        self.value, self.key, err
+                    ))),
+                }
+            }
+        )*
+    };
+}
+
+impl<'de> serde::de::IntoDeserializer<'de> for ArgValue<'de> {
+    type Deserializer = Self;
+
+    fn into_deserializer(self) -> Self {
+        self
+    }
+}
+
+impl<'de> serde::Deserializer<'de> for ArgValue<'de> {
+    type Error = serde::de::value::Error;
+
+    fn deserialize_any<V: serde::de::Visitor<'de>>(
+        self,
+        visitor: V,
+    ) -> Result<V::Value, Self::Error> {
+        visitor.visit_borrowed_str(self.value)
+    }
+
+    fn deserialize_option<V: serde::de::Visitor<'de>>(
+        self,
+        visitor: V,
+    ) -> Result<V::Value, Self::Error> {
+        visitor.visit_some(self)
+    }
+
+    fn deserialize_enum<V: serde::de::Visitor<'de>>(
+        self,
+        _name: &'static str,
+        _variants: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Self::Error> {
+        visitor
+            .visit_enum(serde::de::IntoDeserializer::<Self::Error>::into_deserializer(self.value))
+    }
+
+    fn deserialize_newtype_struct<V: serde::de::Visitor<'de>>(
+        self,
+        _name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value, Self::Error> {
+        visitor.visit_newtype_struct(self)
+    }
+
+    deserialize_parsed! {
+        deserialize_bool => visit_bool,
+        deserialize_i8 => visit_i8,
+        deserialize_i16 => visit_i16,
+        deserialize_i32 => visit_i32,
+        deserialize_i64 => visit_i64,
+        deserialize_u8 => visit_u8,
+        deserialize_u16 => visit_u16,
+        deserialize_u32 => visit_u32,
+        deserialize_u64 => visit_u64,
+        deserialize_f32 => visit_f32,
+        deserialize_f64 => visit_f64,
+        deserialize_char => visit_char
+    }
+
+    serde::forward_to_deserialize_any! {
+        i128 u128 str string bytes byte_buf unit unit_struct seq tuple tuple_struct map struct
+        identifier ignored_any
+    }
 }
 
 // Macro to make typed shortcodes easier to write
@@ -2,7 +2,11 @@
 mod tests {
     use crate::{
         assets::RouteAssetsOptions,
-        content::shortcodes::{MarkdownShortcodes, preprocess_shortcodes},
+        content::{
+            MarkdownOptions, render_markdown,
+            shortcodes::{MarkdownShortcodes, ShortcodeBody, preprocess_shortcodes},
+        },
+        errors::ShortcodeError,
         route::PageContext,
     };
 
@@ -80,6 +84,11 @@ mod tests {
             access_log: std::rc::Rc::new(std::cell::RefCell::new(
                 crate::content::tracked::ContentAccessLog::new(),
             )),
+            entry_urls: None,
+            broken_links: Vec::new(),
+            shortcode_errors: Vec::new(),
+            highlight_errors: Vec::new(),
+            markdown_cache: None,
         };
 
         f(&mut ctx)
@@ -90,7 +99,7 @@ mod tests {
         content: &str,
         shortcodes: &MarkdownShortcodes,
     ) -> Result<String, String> {
-        preprocess_shortcodes(content, shortcodes, None, None)
+        preprocess_shortcodes(content, shortcodes, None, None).map_err(|err| err.to_string())
     }
 
     // Helper function that automatically wraps PageContext in Some() for existing tests
@@ -100,6 +109,7 @@ mod tests {
         route_ctx: &mut PageContext,
     ) -> Result<String, String> {
         preprocess_shortcodes(content, shortcodes, Some(route_ctx), None)
+            .map_err(|err| err.to_string())
     }
 
     #[test]
@@ -1286,4 +1296,194 @@ const y = 10;
         assert!(result.contains(r#"<code lang="js">"#));
         assert!(result.contains("const y = 10;"));
     }
+
+    #[test]
+    fn test_nested_shortcodes_with_same_name() {
+        let shortcodes = create_test_shortcodes();
+        let content = "{{ section title=Outer }}\n{{ section title=Inner }}\nText\n{{ /section }}\n{{ /section }}";
+        let result = preprocess_shortcodes_simple(content, &shortcodes).unwrap();
+        assert_eq!(
+            result,
+            "<section title=\"Outer\">\n<section title=\"Inner\">\nText\n</section>\n</section>"
+        );
+    }
+
+    #[test]
+    fn test_error_mismatched_closing_tag() {
+        let shortcodes = create_test_shortcodes();
+        let content = "{{ section }}\n{{ alert }}\nText\n{{ /section }}\n{{ /alert }}";
+        let error = preprocess_shortcodes_simple(content, &shortcodes).unwrap_err();
+        assert_eq!(
+            error,
+            "4:1: Unexpected closing tag: /section, expected '{{ /alert }}' to close the shortcode opened at line 2, column 1"
+        );
+    }
+
+    #[test]
+    fn test_error_positions() {
+        let shortcodes = create_test_shortcodes();
+
+        let error = preprocess_shortcodes(
+            "# Title\n\nSome {{ unknown /}}",
+            &shortcodes,
+            None,
+            Some("content/post.md"),
+        )
+        .unwrap_err();
+        assert_eq!(
+            error,
+            ShortcodeError {
+                path: Some("content/post.md".into()),
+                line: 3,
+                column: 6,
+                message: "Unknown shortcode: 'unknown'".to_string(),
+            }
+        );
+        assert_eq!(
+            error.to_string(),
+            "content/post.md:3:6: Unknown shortcode: 'unknown'"
+        );
+
+        let error = preprocess_shortcodes_simple("Text\n  {{ greet name=\"Alice /}}", &shortcodes)
+            .unwrap_err();
+        assert_eq!(
+            error,
+            "2:3: Unclosed quote in argument value in shortcode 'greet'"
+        );
+
+        let error =
+            preprocess_shortcodes_simple("{{ simple /}}\n{{ section }}", &shortcodes).unwrap_err();
+        assert!(error.starts_with("2:1: Block shortcode 'section' is missing"));
+    }
+
+    #[test]
+    fn test_fallible_shortcodes() {
+        let mut shortcodes = create_test_shortcodes();
+        shortcodes.register("percent", |args, _| {
+            let value: f64 = args
+                .get_str("value")
+                .ok_or("missing `value`")?
+                .parse()
+                .map_err(|_| "`value` isn't a number")?;
+            Ok::<_, &str>(format!("{}%", value * 100.0))
+        });
+
+        let result = preprocess_shortcodes_simple("{{ percent value=0.5 /}}", &shortcodes);
+        assert_eq!(result.unwrap(), "50%");
+
+        let error = preprocess_shortcodes_simple("Half:\n{{ percent value=half /}}", &shortcodes)
+            .unwrap_err();
+        assert_eq!(
+            error,
+            "2:1: Shortcode 'percent' failed: `value` isn't a number"
+        );
+    }
+
+    #[test]
+    fn test_typed_shortcodes() {
+        #[derive(serde::Deserialize)]
+        #[serde(rename_all = "lowercase")]
+        enum Level {
+            Info,
+            Warning,
+        }
+
+        #[derive(serde::Deserialize)]
+        struct CalloutArgs {
+            level: Level,
+            count: Option<u32>,
+            #[serde(default)]
+            dismissible: bool,
+            body: String,
+        }
+
+        let mut shortcodes = MarkdownShortcodes::new();
+        shortcodes.register_typed("callout", |args: CalloutArgs, _| {
+            let level = match args.level {
+                Level::Info => "info",
+                Level::Warning => "warning",
+            };
+            format!(
+                "<aside class=\"{}\" data-count=\"{}\" data-dismissible=\"{}\">{}</aside>",
+                level,
+                args.count.unwrap_or(1),
+                args.dismissible,
+                args.body.trim()
+            )
+        });
+
+        let result = preprocess_shortcodes_simple(
+            "{{ callout level=warning count=3 dismissible=true }}\nCareful!\n{{ /callout }}",
+            &shortcodes,
+        );
+        assert_eq!(
+            result.unwrap(),
+            "<aside class=\"warning\" data-count=\"3\" data-dismissible=\"true\">Careful!</aside>"
+        );
+
+        let result =
+            preprocess_shortcodes_simple("{{ callout level=info }}Note{{ /callout }}", &shortcodes);
+        assert_eq!(
+            result.unwrap(),
+            "<aside class=\"info\" data-count=\"1\" data-dismissible=\"false\">Note</aside>"
+        );
+
+        let error = preprocess_shortcodes_simple(
+            "{{ callout level=info count=many }}Note{{ /callout }}",
+            &shortcodes,
+        )
+        .unwrap_err();
+        assert!(
+            error.starts_with("1:1: Shortcode 'callout' failed: invalid arguments: invalid value `many` for `count`"),
+            "{}",
+            error
+        );
+
+        let error =
+            preprocess_shortcodes_simple("{{ callout level=info /}}", &shortcodes).unwrap_err();
+        assert_eq!(
+            error,
+            "1:1: Shortcode 'callout' failed: invalid arguments: missing field `body`"
+        );
+    }
+
+    #[test]
+    fn test_escaped_tags_in_block() {
+        let shortcodes = create_test_shortcodes();
+        let content = "{{ highlight }}\n\\{{ /highlight }}\n{{ /highlight }}";
+        let result = preprocess_shortcodes_simple(content, &shortcodes).unwrap();
+        assert_eq!(result, "<code lang=\"text\">\n{{ /highlight }}\n</code>");
+    }
+
+    #[test]
+    fn test_quoted_values_keep_whitespace() {
+        let shortcodes = create_test_shortcodes();
+        let content = "{{ greet name=\"Alice   and\n Bob\" /}}";
+        let result = preprocess_shortcodes_simple(content, &shortcodes).unwrap();
+        assert_eq!(result, "Hello, Alice   and\n Bob!");
+    }
+
+    #[test]
+    fn test_html_body() {
+        let mut shortcodes = create_test_shortcodes();
+        shortcodes.register_with_body("tab", ShortcodeBody::Html, |args, _| {
+            format!(
+                "<div class=\"tab\" data-label=\"{}\">{}</div>",
+                args.get_str("label").unwrap_or(""),
+                args.get_str("body").unwrap_or("")
+            )
+        });
+
+        let options = MarkdownOptions {
+            shortcodes,
+            ..Default::default()
+        };
+        let content = "{{ tab label=Rust }}\n**Fast** and {{ greet name=Safe /}}\n{{ /tab }}";
+        let html = render_markdown(content, Some(&options), None, None);
+
+        assert_eq!(
+            html,
+            "<div class=\"tab\" data-label=\"Rust\"><p><strong>Fast</strong> and Hello, Safe!</p>\n</div>"
+        );
+    }
 }
@@ -0,0 +1,211 @@
+use std::path::Path;
+
+use pulldown_cmark::{Event, Parser, Tag, TagEnd};
+
+use super::{MarkdownOptions, expand_shortcodes, split_json_frontmatter};
+use crate::errors::ShortcodeError;
+
+/// Options of [`render_markdown_text`], all disabled by default.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct TextOptions {
+    /// Keep the text of headings.
+    pub headings: bool,
+    /// Keep the code of code blocks. Inline code is always kept.
+    pub code: bool,
+    /// Separate blocks, such as paragraphs, headings and list items, with an empty line instead of a space.
+    pub paragraph_breaks: bool,
+}
+
+/// Render Markdown content to plain text, for instance for meta descriptions, feeds or search indexes.
+///
+/// Shortcodes are expanded and the Markdown is parsed as by [`render_markdown`](super::render_markdown), but only the text of the
+/// document is kept: HTML, including the one generated by shortcodes, is left out, and whitespace is collapsed to single spaces.
+///
+/// The path of the Markdown file, if any, is used to report shortcodes that fail to expand.
+///
+/// ## Example
+/// ```rust
+/// use maudit::content::{render_markdown_text, TextOptions};
+///
+/// let markdown = "# Hello\n\nSome *emphasized*\ntext. <span>Inline HTML</span>\n\n```rust\nfn main() {}\n```";
+/// assert_eq!(
+///     render_markdown_text(markdown, None, None, &TextOptions::default()).unwrap(),
+///     "Some emphasized text. Inline HTML"
+/// );
+///
+/// let options = TextOptions {
+///     headings: true,
+///     paragraph_breaks: true,
+///     ..Default::default()
+/// };
+/// assert_eq!(
+///     render_markdown_text(markdown, None, None, &options).unwrap(),
+///     "Hello\n\nSome emphasized text. Inline HTML"
+/// );
+/// ```
+pub fn render_markdown_text(
+    content: &str,
+    options: Option<&MarkdownOptions>,
+    path: Option<&Path>,
+    text_options: &TextOptions,
+) -> Result<String, ShortcodeError> {
+    let content = if let Some(shortcodes) = options.map(|o| &o.shortcodes)
+        && !shortcodes.is_empty()
+    {
+        expand_shortcodes(
+            content,
+            shortcodes,
+            options,
+            None,
+            None,
+            path.and_then(|p| p.to_str()),
+            // Code blocks aren't part of the HTML of shortcodes in the text, failing to highlight them doesn't matter
+            &mut Vec::new(),
+        )?
+    } else {
+        content.to_string()
+    };
+    let content = split_json_frontmatter(&content).map_or(content.as_str(), |(_, body)| body);
+
+    let parser_options = options
+        .map(|options| options.extensions)
+        .unwrap_or_default()
+        .parser_options();
+
+    let mut blocks = Vec::new();
+    let mut block = String::new();
+    let mut in_frontmatter = false;
+    let mut skipping = false;
+    for event in Parser::new_ext(content, parser_options) {
+        match event {
+            Event::Start(Tag::MetadataBlock(_)) => in_frontmatter = true,
+            Event::End(TagEnd::MetadataBlock(_)) => in_frontmatter = false,
+            _ if in_frontmatter => {}
+
+            Event::Start(Tag::Heading { .. }) if !text_options.headings => skipping = true,
+            Event::Start(Tag::CodeBlock(_)) if !text_options.code => skipping = true,
+            Event::End(TagEnd::Heading(_) | TagEnd::CodeBlock) if skipping => skipping = false,
+            _ if skipping => {}
+
+            Event::Text(text)
+            | Event::Code(text)
+            | Event::InlineMath(text)
+            | Event::DisplayMath(text) => block.push_str(&text),
+            Event::SoftBreak | Event::HardBreak | Event::End(TagEnd::TableCell) => block.push(' '),
+            // A block starting or ending ends the text of the current one, e.g. the text of a list item before a nested list
+            Event::Start(tag) if is_block(&tag.to_end()) => end_block(&mut block, &mut blocks),
+            Event::End(tag) if is_block(&tag) => end_block(&mut block, &mut blocks),
+            _ => {}
+        }
+    }
+    end_block(&mut block, &mut blocks);
+
+    Ok(blocks.join(if text_options.paragraph_breaks {
+        "\n\n"
+    } else {
+        " "
+    }))
+}
+
+fn is_block(tag: &TagEnd) -> bool {
+    matches!(
+        tag,
+        TagEnd::Paragraph
+            | TagEnd::Heading(_)
+            | TagEnd::BlockQuote(_)
+            | TagEnd::CodeBlock
+            | TagEnd::HtmlBlock
+            | TagEnd::List(_)
+            | TagEnd::Item
+            | TagEnd::FootnoteDefinition
+            | TagEnd::DefinitionList
+            | TagEnd::DefinitionListTitle
+            | TagEnd::DefinitionListDefinition
+            | TagEnd::Table
+            | TagEnd::TableHead
+            | TagEnd::TableRow
+    )
+}
+
+fn end_block(block: &mut String, blocks: &mut Vec<String>) {
+    let text = block.split_whitespace().collect::<Vec<_>>().join(" ");
+    if !text.is_empty() {
+        blocks.push(text);
+    }
+    block.clear();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::content::shortcodes::MarkdownShortcodes;
+
+    const DOCUMENT: &str = "---\ntitle: Post\n---\n\n## Introduction\n\nSome **bold** text\nand `code`.\n\n- First\n  - Nested\n- [Second](./second.md)\n\n```rust\nfn main() {}\n```\n\n<div class=\"note\">\nA note\n</div>\n\n| A | B |\n|---|---|\n| 1 | 2 |\n";
+
+    #[test]
+    fn test_render_text() {
+        assert_eq!(
+            render_markdown_text(DOCUMENT, None, None, &TextOptions::default()).unwrap(),
+            "Some bold text and code. First Nested Second A B 1 2"
+        );
+    }
+
+    #[test]
+    fn test_render_text_with_headings_code_and_paragraph_breaks() {
+        let options = TextOptions {
+            headings: true,
+            code: true,
+            paragraph_breaks: true,
+        };
+        assert_eq!(
+            render_markdown_text(DOCUMENT, None, None, &options).unwrap(),
+            "Introduction\n\nSome bold text and code.\n\nFirst\n\nNested\n\nSecond\n\nfn main() {}\n\nA B\n\n1 2"
+        );
+    }
+
+    #[test]
+    fn test_render_text_leaves_out_shortcode_html() {
+        let mut shortcodes = MarkdownShortcodes::new();
+        shortcodes.register("alert", |args, _| {
+            format!(
+                "<div class=\"alert\">\n\n{}\n\n</div>",
+                args.get_str("body").unwrap_or_default()
+            )
+        });
+        let options = MarkdownOptions {
+            shortcodes,
+            ..Default::default()
+        };
+
+        assert_eq!(
+            render_markdown_text(
+                "Before.\n\n{{ alert }}Careful *now*.{{ /alert }}\n\nAfter.",
+                Some(&options),
+                None,
+                &TextOptions::default()
+            )
+            .unwrap(),
+            "Before. Careful now. After."
+        );
+    }
+
+    #[test]
+    fn test_render_text_reports_shortcode_errors() {
+        let mut shortcodes = MarkdownShortcodes::new();
+        shortcodes.register("known", |_, _| String::new());
+        let options = MarkdownOptions {
+            shortcodes,
+            ..Default::default()
+        };
+
+        let error = render_markdown_text(
+            "{{ unknown /}}",
+            Some(&options),
+            Some(Path::new("content/post.md")),
+            &TextOptions::default(),
+        )
+        .unwrap_err();
+        assert_eq!(error.path.as_deref(), Some(Path::new("content/post.md")));
+        assert!(error.to_string().contains("Unknown shortcode: 'unknown'"));
+    }
+}
@@ -0,0 +1,170 @@
+use super::MarkdownHeading;
+
+/// A nested table of contents, built from the headings of a Markdown entry.
+///
+/// Headings are nested under the closest previous heading of a lower level, so skipped levels (ex: a `h4` right after a `h2`)
+/// are nested directly under the previous heading instead of under an empty entry.
+///
+/// With the `maud` feature, it can be rendered as nested lists of links to the headings using [`maud::Render`](https://docs.rs/maud/latest/maud/trait.Render.html).
+///
+/// ## Example
+/// ```rust
+/// use maudit::route::prelude::*;
+/// use maudit::content::TableOfContents;
+/// use maud::{html, Markup};
+/// # use maudit::content::markdown_entry;
+/// #
+/// # #[markdown_entry]
+/// # pub struct ArticleContent {
+/// #    pub title: String,
+/// # }
+///
+/// #[route("/articles/my-article")]
+/// pub struct Article;
+///
+/// impl Route<PageParams, Markup> for Article {
+///   fn render(&self, ctx: &mut PageContext) -> Markup {
+///     let article = ctx.content::<ArticleContent>("articles").get_entry("my-article");
+///     // Only include `h2` and `h3` headings
+///     let toc = TableOfContents::with_depth(article.data(ctx).get_headings(), 2, 3);
+///     html! {
+///       nav { (toc) }
+///     }
+///   }
+/// }
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct TableOfContents {
+    pub entries: Vec<TocEntry>,
+}
+
+/// A heading of a [`TableOfContents`], along with the headings nested under it.
+#[derive(Debug, Clone)]
+pub struct TocEntry {
+    pub title: String,
+    pub id: String,
+    pub level: u8,
+    pub children: Vec<TocEntry>,
+}
+
+impl TableOfContents {
+    /// Build a table of contents including every heading.
+    pub fn new(headings: &[MarkdownHeading]) -> Self {
+        Self::with_depth(headings, 1, 6)
+    }
+
+    /// Build a table of contents including only the headings with a level between `min_depth` and `max_depth`, inclusive.
+    pub fn with_depth(headings: &[MarkdownHeading], min_depth: u8, max_depth: u8) -> Self {
+        let headings: Vec<&MarkdownHeading> = headings
+            .iter()
+            .filter(|heading| (min_depth..=max_depth).contains(&heading.level))
+            .collect();
+
+        Self {
+            entries: nest_headings(&headings),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+}
+
+/// Every heading takes the headings following it with a higher level as children, until one with the same level or lower.
+fn nest_headings(headings: &[&MarkdownHeading]) -> Vec<TocEntry> {
+    let mut entries = Vec::new();
+    let mut start = 0;
+
+    while let Some(heading) = headings.get(start) {
+        let end = headings[start + 1..]
+            .iter()
+            .position(|next| next.level <= heading.level)
+            .map_or(headings.len(), |offset| start + 1 + offset);
+
+        entries.push(TocEntry {
+            title: heading.title.clone(),
+            id: heading.id.clone(),
+            level: heading.level,
+            children: nest_headings(&headings[start + 1..end]),
+        });
+        start = end;
+    }
+
+    entries
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn headings(levels: &[u8]) -> Vec<MarkdownHeading> {
+        levels
+            .iter()
+            .enumerate()
+            .map(|(i, level)| MarkdownHeading {
+                title: format!("Heading {i}"),
+                id: format!("heading-{i}"),
+                level: *level,
+                classes: vec![],
+            })
+            .collect()
+    }
+
+    /// Describe a tree as `id(children)` to compare it easily.
+    fn shape(entries: &[TocEntry]) -> String {
+        entries
+            .iter()
+            .map(|entry| {
+                if entry.children.is_empty() {
+                    entry.id.clone()
+                } else {
+                    format!("{}({})", entry.id, shape(&entry.children))
+                }
+            })
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+
+    #[test]
+    fn test_nested_headings() {
+        let toc = TableOfContents::new(&headings(&[1, 2, 3, 3, 2, 1]));
+        assert_eq!(
+            shape(&toc.entries),
+            "heading-0(heading-1(heading-2 heading-3) heading-4) heading-5"
+        );
+    }
+
+    #[test]
+    fn test_skipped_levels() {
+        let toc = TableOfContents::new(&headings(&[2, 4, 3, 2, 4]));
+        assert_eq!(
+            shape(&toc.entries),
+            "heading-0(heading-1 heading-2) heading-3(heading-4)"
+        );
+
+        // Headings of a lower level than the first one are still at the root
+        let toc = TableOfContents::new(&headings(&[3, 2, 3]));
+        assert_eq!(shape(&toc.entries), "heading-0 heading-1(heading-2)");
+    }
+
+    #[test]
+    fn test_depth() {
+        let toc = TableOfContents::with_depth(&headings(&[1, 2, 3, 4, 2]), 2, 3);
+        assert_eq!(shape(&toc.entries), "heading-1(heading-2) heading-4");
+
+        assert!(TableOfContents::with_depth(&headings(&[1, 1]), 2, 6).is_empty());
+    }
+
+    #[cfg(feature = "maud")]
+    #[test]
+    fn test_render() {
+        use maud::Render;
+
+        let toc = TableOfContents::new(&headings(&[2, 4, 2]));
+        assert_eq!(
+            toc.render().into_string(),
+            "<ul><li><a href=\"#heading-0\">Heading 0</a><ul><li><a href=\"#heading-1\">Heading 1</a></li></ul></li><li><a href=\"#heading-2\">Heading 2</a></li></ul>"
+        );
+        assert_eq!(TableOfContents::default().render().into_string(), "");
+    }
+}
@@ -0,0 +1,69 @@
+use pulldown_latex::{Parser, RenderConfig, Storage, config::DisplayMode, push_mathml};
+
+use super::highlight::escape_html;
+
+/// How a math expression is displayed, `$...$` being inline and `$$...$$` a block.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum MathDisplay {
+    #[default]
+    Inline,
+    Block,
+}
+
+/// Render a LaTeX math expression to MathML, which browsers can display without any JavaScript.
+///
+/// Invalid expressions don't fail the build, the errors are instead rendered in red in place of the invalid commands.
+/// The LaTeX source is kept in an `<annotation>` element, for assistive technologies and copy-pasting.
+pub fn render_math(latex: &str, display: MathDisplay) -> String {
+    // pulldown-latex writes the annotation as is
+    let annotation = escape_html(latex);
+    let storage = Storage::new();
+    let parser = Parser::new(latex, &storage);
+    let config = RenderConfig {
+        display_mode: match display {
+            MathDisplay::Inline => DisplayMode::Inline,
+            MathDisplay::Block => DisplayMode::Block,
+        },
+        annotation: Some(&annotation),
+        ..Default::default()
+    };
+
+    let mut mathml = String::new();
+    // Writing to a string can't fail
+    push_mathml(&mut mathml, parser, config).expect("Failed to render math");
+    mathml
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_render_math() {
+        let inline = render_math("x^2", MathDisplay::Inline);
+        assert!(inline.starts_with("<math"));
+        assert!(!inline.contains("display=\"block\""));
+        assert!(inline.contains("<msup>"));
+        assert!(inline.contains("<annotation encoding=\"application/x-tex\">x^2</annotation>"));
+
+        let block = render_math(r"\frac{a}{b}", MathDisplay::Block);
+        assert!(block.contains("display=\"block\""));
+        assert!(block.contains("<mfrac>"));
+    }
+
+    #[test]
+    fn test_annotation_is_escaped() {
+        let html = render_math("a<b", MathDisplay::Inline);
+        assert!(html.contains("<annotation encoding=\"application/x-tex\">a&lt;b</annotation>"));
+
+        let html = render_math("x</math><script>alert(1)</script>", MathDisplay::Inline);
+        assert!(!html.contains("<script>"));
+    }
+
+    #[test]
+    fn test_render_invalid_math() {
+        let html = render_math(r"\notacommand{x}", MathDisplay::Inline);
+        assert!(html.starts_with("<math"));
+        assert!(html.contains("<merror"));
+    }
+}
@@ -0,0 +1,89 @@
+//! Deciding whether an entry is a draft or scheduled for later, from its `draft` and `publish_date` fields.
+use std::path::Path;
+
+use chrono::{DateTime, NaiveDate, NaiveDateTime, NaiveTime, Utc};
+use log::warn;
+use serde::{
+    Deserialize, Deserializer,
+    de::{Error as _, IgnoredAny},
+};
+
+/// Fields deciding whether an entry is published, regardless of the type of the entry.
+///
+/// The entry's own type may use these fields for something else, so values that can't be read are ignored with a warning
+/// instead of failing the build, and the entry is published.
+#[derive(Deserialize, Default)]
+pub(crate) struct Publication {
+    #[serde(default)]
+    draft: Option<Lenient<bool>>,
+    #[serde(default)]
+    publish_date: Option<Lenient<PublishDate>>,
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum Lenient<T> {
+    Valid(T),
+    Invalid(IgnoredAny),
+}
+
+impl Publication {
+    /// Whether the entry read from `path` is a draft or scheduled after `now`.
+    pub(crate) fn is_draft(&self, now: DateTime<Utc>, path: &Path) -> bool {
+        let draft = match self.draft {
+            Some(Lenient::Valid(draft)) => draft,
+            Some(Lenient::Invalid(_)) => {
+                warn!(
+                    "{}: `draft` is not a boolean, the entry is not treated as a draft",
+                    path.display()
+                );
+                false
+            }
+            None => false,
+        };
+
+        let scheduled = match &self.publish_date {
+            Some(Lenient::Valid(PublishDate(date))) => *date > now,
+            Some(Lenient::Invalid(_)) => {
+                warn!(
+                    "{}: `publish_date` is not a date like `2025-01-01` or `2025-01-01T12:00:00Z`, it is ignored",
+                    path.display()
+                );
+                false
+            }
+            None => false,
+        };
+
+        draft || scheduled
+    }
+}
+
+/// Accepts RFC 3339 dates with or without a time (ex: `2025-01-01` or `2025-01-01T12:00:00Z`), as strings or TOML datetimes.
+/// Times without an offset are assumed to be UTC.
+struct PublishDate(DateTime<Utc>);
+
+impl<'de> Deserialize<'de> for PublishDate {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        #[derive(Deserialize)]
+        #[serde(untagged)]
+        enum RawDate {
+            String(String),
+            Toml(toml::value::Datetime),
+        }
+
+        let date = match RawDate::deserialize(deserializer)? {
+            RawDate::String(date) => date,
+            RawDate::Toml(date) => date.to_string(),
+        };
+
+        if let Ok(date) = DateTime::parse_from_rfc3339(&date) {
+            return Ok(Self(date.to_utc()));
+        }
+        if let Ok(date) = NaiveDateTime::parse_from_str(&date, "%Y-%m-%dT%H:%M:%S") {
+            return Ok(Self(date.and_utc()));
+        }
+        NaiveDate::parse_from_str(&date, "%Y-%m-%d")
+            .map(|date| Self(date.and_time(NaiveTime::MIN).and_utc()))
+            .map_err(|_| D::Error::custom(format!("invalid publish date `{date}`")))
+    }
+}
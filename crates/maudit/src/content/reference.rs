@@ -0,0 +1,296 @@
+//! References from the data of an entry to entries of other content sources.
+use std::{
+    cell::RefCell,
+    fmt::{self, Debug, Formatter},
+    future::Future,
+    marker::PhantomData,
+    path::{Path, PathBuf},
+    pin::Pin,
+    task::{Context, Poll},
+};
+
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+use crate::content::{ContentContext, Entry};
+
+/// Types stored in a named content source, which can be referenced from other entries using [`Reference`].
+///
+/// ## Example
+/// ```rust
+/// use maudit::content::Referenceable;
+///
+/// #[derive(serde::Deserialize)]
+/// pub struct Author {
+///   pub name: String,
+/// }
+///
+/// impl Referenceable for Author {
+///   const SOURCE: &'static str = "authors";
+/// }
+/// ```
+pub trait Referenceable: 'static {
+    /// Name of the content source entries of this type are stored in.
+    const SOURCE: &'static str;
+}
+
+/// The ID of an entry of another content source, typically used in frontmatter.
+///
+/// References are checked once all content sources are loaded, and the build fails if one of them points to an entry that doesn't exist.
+///
+/// ## Example
+/// ```rust
+/// use maudit::route::prelude::*;
+/// use maudit::content::{markdown_entry, Reference, Referenceable};
+///
+/// #[derive(serde::Deserialize)]
+/// pub struct Author {
+///   pub name: String,
+/// }
+///
+/// impl Referenceable for Author {
+///   const SOURCE: &'static str = "authors";
+/// }
+///
+/// #[markdown_entry]
+/// pub struct Article {
+///   pub title: String,
+///   pub author: Reference<Author>, // e.g. `author: jane-doe`
+/// }
+///
+/// #[route("/articles/my-article")]
+/// pub struct MyArticle;
+///
+/// impl Route for MyArticle {
+///   fn render(&self, ctx: &mut PageContext) -> impl Into<RenderResult> {
+///     let article = ctx.content::<Article>("articles").get_entry("my-article");
+///     let author = article.data(ctx).author.get(ctx);
+///
+///     format!("Written by {}", author.data(ctx).name)
+///   }
+/// }
+/// ```
+pub struct Reference<T> {
+    id: String,
+    _data: PhantomData<fn() -> T>,
+}
+
+impl<T> Reference<T> {
+    pub fn new(id: impl Into<String>) -> Self {
+        Self {
+            id: id.into(),
+            _data: PhantomData,
+        }
+    }
+
+    /// ID of the referenced entry.
+    pub fn id(&self) -> &str {
+        &self.id
+    }
+}
+
+impl<T: Referenceable> Reference<T> {
+    /// Get the referenced entry. Records a dependency on this entry, so that pages using it are re-rendered when it changes.
+    ///
+    /// Entries are reference-counted, so the returned entry is cheap to get and doesn't borrow the context.
+    pub fn get<C: ContentContext + ?Sized>(&self, ctx: &C) -> Entry<T> {
+        ctx.record_entry_read(T::SOURCE, &self.id);
+
+        let source = ctx
+            .content()
+            .get_source_safe::<T>(T::SOURCE)
+            .unwrap_or_else(|| {
+                panic!(
+                    "Reference to `{}` points to content source `{}`, which doesn't exist",
+                    self.id,
+                    T::SOURCE
+                )
+            });
+
+        source.get_entry_safe(&self.id).cloned().unwrap_or_else(|| {
+            panic!(
+                "Reference to `{}` doesn't match any entry of content source `{}`",
+                self.id,
+                T::SOURCE
+            )
+        })
+    }
+}
+
+impl<T> Clone for Reference<T> {
+    fn clone(&self) -> Self {
+        Self::new(self.id.clone())
+    }
+}
+
+impl<T> PartialEq for Reference<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.id == other.id
+    }
+}
+
+impl<T> Eq for Reference<T> {}
+
+impl<T> Debug for Reference<T> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("Reference").field(&self.id).finish()
+    }
+}
+
+impl<'de, T: Referenceable> Deserialize<'de> for Reference<T> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let id = String::deserialize(deserializer)?;
+        record_loaded_reference(T::SOURCE, &id);
+        Ok(Self::new(id))
+    }
+}
+
+impl<T> Serialize for Reference<T> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        self.id.serialize(serializer)
+    }
+}
+
+/// A reference deserialized while loading a content source.
+#[derive(Debug, Clone)]
+pub(crate) struct LoadedReference {
+    pub source: &'static str,
+    pub id: String,
+    /// File the reference was read from, if known.
+    pub origin: Option<PathBuf>,
+}
+
+#[derive(Default)]
+struct ReferenceCollector {
+    origin: Option<PathBuf>,
+    references: Vec<LoadedReference>,
+}
+
+thread_local! {
+    // Deserialization can't carry any state, so references are collected on the side while content sources load.
+    static COLLECTOR: RefCell<Option<ReferenceCollector>> = const { RefCell::new(None) };
+}
+
+fn record_loaded_reference(source: &'static str, id: &str) {
+    COLLECTOR.with_borrow_mut(|collector| {
+        if let Some(collector) = collector {
+            collector.references.push(LoadedReference {
+                source,
+                id: id.to_string(),
+                origin: collector.origin.clone(),
+            });
+        }
+    });
+}
+
+/// Run `f`, returning the references deserialized while it ran.
+pub(crate) fn collect_references<R>(f: impl FnOnce() -> R) -> (R, Vec<LoadedReference>) {
+    let previous = COLLECTOR.replace(Some(ReferenceCollector::default()));
+    let result = f();
+    let collector = COLLECTOR.replace(previous).unwrap_or_default();
+    (result, collector.references)
+}
+
+/// Future collecting the references deserialized while polling `inner`.
+///
+/// Async loaders can move between threads at every `.await`, so the collector is installed around each poll.
+pub(crate) struct CollectReferences<F> {
+    inner: F,
+    references: Vec<LoadedReference>,
+}
+
+impl<F: Future + Unpin> CollectReferences<F> {
+    pub fn new(inner: F) -> Self {
+        Self {
+            inner,
+            references: Vec::new(),
+        }
+    }
+}
+
+impl<F: Future + Unpin> Future for CollectReferences<F> {
+    type Output = (F::Output, Vec<LoadedReference>);
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let this = self.get_mut();
+        let (poll, references) = collect_references(|| Pin::new(&mut this.inner).poll(cx));
+        this.references.extend(references);
+        poll.map(|output| (output, std::mem::take(&mut this.references)))
+    }
+}
+
+/// Attribute the references deserialized by `f` to a file, for error messages.
+pub(crate) fn with_origin<R>(origin: Option<&Path>, f: impl FnOnce() -> R) -> R {
+    let previous = COLLECTOR.with_borrow_mut(|collector| {
+        collector.as_mut().map(|collector| {
+            std::mem::replace(&mut collector.origin, origin.map(Path::to_path_buf))
+        })
+    });
+    let result = f();
+    if let Some(previous) = previous {
+        COLLECTOR.with_borrow_mut(|collector| {
+            if let Some(collector) = collector {
+                collector.origin = previous;
+            }
+        });
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct Author;
+
+    impl Referenceable for Author {
+        const SOURCE: &'static str = "authors";
+    }
+
+    #[derive(Deserialize)]
+    struct Article {
+        authors: Vec<Reference<Author>>,
+    }
+
+    #[test]
+    fn test_references_are_collected_with_their_origin() {
+        let (article, references) = collect_references(|| {
+            with_origin(Some(Path::new("article.md")), || {
+                serde_yaml::from_str::<Article>("authors: [ada, grace]").unwrap()
+            })
+        });
+
+        assert_eq!(
+            article.authors,
+            vec![Reference::new("ada"), Reference::new("grace")]
+        );
+        assert_eq!(references.len(), 2);
+        assert_eq!(references[1].source, "authors");
+        assert_eq!(references[1].id, "grace");
+        assert_eq!(
+            references[1].origin.as_deref(),
+            Some(Path::new("article.md"))
+        );
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_references_are_collected_across_awaits() {
+        let load = Box::pin(async {
+            let first = serde_yaml::from_str::<Article>("authors: [ada]").unwrap();
+            tokio::task::yield_now().await;
+            let second = serde_yaml::from_str::<Article>("authors: [grace]").unwrap();
+            (first, second)
+        });
+
+        let (_, references) = tokio::spawn(CollectReferences::new(load)).await.unwrap();
+        let ids: Vec<_> = references.iter().map(|r| r.id.as_str()).collect();
+        assert_eq!(ids, vec!["ada", "grace"]);
+    }
+
+    #[test]
+    fn test_references_are_only_collected_while_loading() {
+        serde_yaml::from_str::<Article>("authors: [ada]").unwrap();
+
+        let (_, references) = collect_references(|| ());
+        assert!(references.is_empty());
+    }
+}
@@ -0,0 +1,254 @@
+//! Grouping of entries by terms such as tags or categories, to generate term listings and per-term pages.
+use std::{cell::RefCell, collections::BTreeMap, rc::Rc};
+
+use slug::slugify;
+
+use crate::{
+    content::{Entry, tracked::ContentAccessLog},
+    route::{Page, PageParams, Pages, PaginatedContentPage, paginate},
+};
+
+/// A term of a [`Taxonomy`], e.g. a tag.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TaxonomyTerm {
+    /// Name of the term, as written in the first entry using it.
+    pub name: String,
+    /// Slug of the term, suitable for URLs. Terms with the same slug (ex: `Rust` and `rust`) are merged.
+    pub slug: String,
+    /// Number of entries with this term.
+    pub count: usize,
+}
+
+/// Props of the pages generated by [`Taxonomy::term_pages`].
+pub struct TaxonomyPage<T> {
+    pub term: TaxonomyTerm,
+    /// Entries with this term for the current page.
+    pub pagination: PaginatedContentPage<T>,
+}
+
+// Entries are reference-counted, so the data of entries doesn't need to be `Clone`
+impl<T> Clone for TaxonomyPage<T> {
+    fn clone(&self) -> Self {
+        Self {
+            term: self.term.clone(),
+            pagination: self.pagination.clone(),
+        }
+    }
+}
+
+/// Entries of a content source grouped by terms such as tags or categories, obtained through [`TrackedContentSource::taxonomy`](crate::content::TrackedContentSource::taxonomy)
+/// or [`ContentQuery::taxonomy`](crate::content::ContentQuery::taxonomy).
+///
+/// Terms are sorted by slug, and entries keep the order of the query they come from within each term.
+///
+/// ## Example
+/// ```rust
+/// use maudit::route::prelude::*;
+/// use maudit::content::{markdown_entry, TaxonomyPage};
+///
+/// #[markdown_entry]
+/// pub struct Post {
+///   pub title: String,
+///   pub tags: Vec<String>,
+/// }
+///
+/// #[route("/tags/[tag]/[page]")]
+/// pub struct TagPage;
+///
+/// #[derive(Params, Clone)]
+/// pub struct TagParams {
+///   pub tag: String,
+///   pub page: usize,
+/// }
+///
+/// impl Route<TagParams, TaxonomyPage<Post>> for TagPage {
+///   fn pages(&self, ctx: &mut DynamicRouteContext) -> Pages<TagParams, TaxonomyPage<Post>> {
+///     ctx.content::<Post>("posts")
+///       .taxonomy(|post| post.data(ctx).tags.clone())
+///       .term_pages(10, |term, page| TagParams { tag: term.slug.clone(), page })
+///   }
+///
+///   fn render(&self, ctx: &mut PageContext) -> impl Into<RenderResult> {
+///     let props = ctx.props::<TaxonomyPage<Post>>();
+///     let titles = props
+///       .pagination
+///       .items
+///       .iter()
+///       .map(|post| post.data(ctx).title.clone())
+///       .collect::<Vec<_>>();
+///
+///     format!("{} ({} posts): {}", props.term.name, props.term.count, titles.join(", "))
+///   }
+/// }
+/// ```
+pub struct Taxonomy<'a, T> {
+    source_name: String,
+    log: Rc<RefCell<ContentAccessLog>>,
+    /// Terms by slug.
+    terms: BTreeMap<String, (String, Vec<&'a Entry<T>>)>,
+}
+
+impl<'a, T> Taxonomy<'a, T> {
+    pub(crate) fn new(source_name: String, log: Rc<RefCell<ContentAccessLog>>) -> Self {
+        Self {
+            source_name,
+            log,
+            terms: BTreeMap::new(),
+        }
+    }
+
+    pub(crate) fn insert(&mut self, name: String, entry: &'a Entry<T>) {
+        let (_, entries) = self
+            .terms
+            .entry(slugify(&name))
+            .or_insert_with(|| (name, Vec::new()));
+        // An entry listing the same term twice is only counted once
+        if !entries.iter().any(|e| e.id == entry.id) {
+            entries.push(entry);
+        }
+    }
+
+    /// Get every term, with the number of entries using it. Useful for tag clouds and term listings.
+    pub fn terms(&self) -> Vec<TaxonomyTerm> {
+        self.terms
+            .iter()
+            .map(|(slug, (name, entries))| TaxonomyTerm {
+                name: name.clone(),
+                slug: slug.clone(),
+                count: entries.len(),
+            })
+            .collect()
+    }
+
+    /// Get the entries with a term, identified by its slug. Records a dependency on each of them.
+    pub fn entries(&self, slug: &str) -> Vec<&'a Entry<T>> {
+        let entries = self
+            .terms
+            .get(slug)
+            .map(|(_, entries)| entries.clone())
+            .unwrap_or_default();
+
+        self.log.borrow_mut().entries_read.extend(
+            entries
+                .iter()
+                .map(|entry| (self.source_name.clone(), entry.id.clone())),
+        );
+        entries
+    }
+
+    /// Generate the pages of every term, splitting the entries of each term into pages of `per_page` entries using [`paginate`].
+    ///
+    /// `params_fn` receives the term and the index of the page in the term (starting at 0). Each page records a dependency on the entries it contains.
+    pub fn term_pages<Params>(
+        self,
+        per_page: usize,
+        mut params_fn: impl FnMut(&TaxonomyTerm, usize) -> Params,
+    ) -> Pages<Params, TaxonomyPage<T>>
+    where
+        Params: Into<PageParams>,
+    {
+        let mut pages = Vec::new();
+        for (slug, (name, entries)) in self.terms {
+            let term = TaxonomyTerm {
+                name,
+                slug,
+                count: entries.len(),
+            };
+
+            let term_pages = paginate(entries.into_iter().cloned(), per_page, |page| {
+                params_fn(&term, page)
+            });
+            for page in term_pages {
+                let source_entries = page
+                    .props
+                    .items
+                    .iter()
+                    .map(|entry| (self.source_name.clone(), entry.id.clone()))
+                    .collect();
+
+                let mut page = Page::new(
+                    page.params,
+                    TaxonomyPage {
+                        term: term.clone(),
+                        pagination: page.props,
+                    },
+                );
+                page._source_entries = source_entries;
+                pages.push(page);
+            }
+        }
+        pages
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::content::ContentEntry;
+    use rustc_hash::FxHashMap;
+
+    fn entry(id: &str) -> Entry<()> {
+        Entry::create(id.to_string(), None, None, (), vec![])
+    }
+
+    #[test]
+    fn test_terms_are_merged_by_slug() {
+        let (rust, web) = (entry("rust"), entry("web"));
+        let log = Rc::new(RefCell::new(ContentAccessLog::new()));
+        let mut taxonomy = Taxonomy::new("posts".to_string(), log.clone());
+        taxonomy.insert("Rust".to_string(), &rust);
+        taxonomy.insert("rust".to_string(), &rust);
+        taxonomy.insert("rust".to_string(), &web);
+        taxonomy.insert("Web Dev".to_string(), &web);
+
+        assert_eq!(
+            taxonomy.terms(),
+            vec![
+                TaxonomyTerm {
+                    name: "Rust".to_string(),
+                    slug: "rust".to_string(),
+                    count: 2,
+                },
+                TaxonomyTerm {
+                    name: "Web Dev".to_string(),
+                    slug: "web-dev".to_string(),
+                    count: 1,
+                },
+            ]
+        );
+        assert!(log.borrow().entries_read.is_empty());
+
+        assert_eq!(taxonomy.entries("web-dev").len(), 1);
+        assert_eq!(
+            log.borrow().entries_read,
+            vec![("posts".to_string(), "web".to_string())]
+        );
+    }
+
+    #[test]
+    fn test_term_pages() {
+        let entries = [entry("a"), entry("b"), entry("c")];
+        let log = Rc::new(RefCell::new(ContentAccessLog::new()));
+        let mut taxonomy = Taxonomy::new("posts".to_string(), log);
+        for entry in &entries {
+            taxonomy.insert("all".to_string(), entry);
+        }
+        taxonomy.insert("first".to_string(), &entries[0]);
+
+        let pages = taxonomy.term_pages(2, |term, page| {
+            PageParams(FxHashMap::from_iter([
+                ("tag".to_string(), Some(term.slug.clone())),
+                ("page".to_string(), Some(page.to_string())),
+            ]))
+        });
+
+        assert_eq!(pages.len(), 3);
+        assert_eq!(pages[1].props.term.count, 3);
+        assert_eq!(pages[1].props.pagination.page, 1);
+        assert_eq!(
+            pages[1]._source_entries,
+            vec![("posts".to_string(), "c".to_string())]
+        );
+        assert_eq!(pages[2].props.term.slug, "first");
+    }
+}
@@ -1,6 +1,10 @@
-use crate::content::{ContentSource, Entry};
-use crate::route::{Page, PageParams, Pages};
+use crate::content::{ContentSource, Entry, Taxonomy};
+use crate::route::{Page, PageParams, Pages, PaginatedContentPage, paginate};
+use rustc_hash::FxHasher;
+use serde::{Deserialize, Serialize};
 use std::cell::RefCell;
+use std::collections::BTreeMap;
+use std::hash::{Hash, Hasher};
 use std::rc::Rc;
 
 /// Records content access patterns during page rendering.
@@ -13,6 +17,21 @@ pub struct ContentAccessLog {
     pub entries_read: Vec<(String, String)>,
     /// Sources fully iterated (via into_pages, into_params, or entries()).
     pub sources_iterated: Vec<String>,
+    /// Sources whose entries were filtered, sorted or grouped (via query()).
+    /// Only the data of their entries matters, the entries actually used are in `entries_read`.
+    pub sources_queried: Vec<String>,
+    /// What each query selected, in the order queries were started.
+    pub query_results: Vec<QueryResult>,
+}
+
+/// The entries a query selected, in order, and the keys they were grouped by, hashed.
+///
+/// `pages()` runs on every build, so comparing the results of its queries to the ones of the previous build tells
+/// whether the pages of a route were split differently, without depending on the data of every entry of the source.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct QueryResult {
+    pub source: String,
+    pub hash: u64,
 }
 
 impl ContentAccessLog {
@@ -24,11 +43,30 @@ impl ContentAccessLog {
     /// Used to include content dependencies from `get_pages()` into each
     /// individual page's log. Only merges `entries_read` (not `sources_iterated`),
     /// because source iteration during `get_pages()` is for page enumeration —
-    /// structural changes to iterated sources already trigger a full rebuild
-    /// of the dynamic route via `find_stale_pages`.
+    /// structural changes to iterated sources already trigger a full rebuild of
+    /// the dynamic route via `find_stale_pages`. Neither are `sources_queried`,
+    /// the results of the queries are compared across builds instead, see [`QueryResult`].
     pub fn merge_entries_read(&mut self, other: &ContentAccessLog) {
         self.entries_read.extend(other.entries_read.iter().cloned());
     }
+
+    /// Start recording the result of a query over `entries`, returning its index in `query_results`.
+    fn start_query<T>(&mut self, source: &str, entries: &[&Entry<T>]) -> usize {
+        self.query_results.push(QueryResult {
+            source: source.to_string(),
+            hash: hash_selection(entries, ()),
+        });
+        self.query_results.len() - 1
+    }
+}
+
+fn hash_selection<T>(entries: &[&Entry<T>], keys: impl Hash) -> u64 {
+    let mut hasher = FxHasher::default();
+    for entry in entries {
+        entry.id.hash(&mut hasher);
+    }
+    keys.hash(&mut hasher);
+    hasher.finish()
 }
 
 /// A wrapper around [`ContentSource`] that records all accesses
@@ -89,7 +127,7 @@ impl<'a, T> TrackedContentSource<'a, T> {
         let source_name = self.source_name.clone();
         self.inner.into_pages(|entry| {
             let mut page = cb(entry);
-            page._source_entry = Some((source_name.clone(), entry.id.clone()));
+            page._source_entries = vec![(source_name.clone(), entry.id.clone())];
             page
         })
     }
@@ -106,12 +144,248 @@ impl<'a, T> TrackedContentSource<'a, T> {
         self.inner.into_params(cb)
     }
 
+    /// Start a query over the entries of this source, to filter, sort, group or paginate them.
+    ///
+    /// Unlike [`entries()`](Self::entries), a query only depends on the data of the entries it doesn't return,
+    /// so editing the content of an entry only re-renders the pages that actually use it. Queries run in
+    /// [`pages()`](crate::route::Route::pages) don't depend on the data of other entries at all, only on which entries they
+    /// select, in which order, and how they're grouped.
+    ///
+    /// ## Example
+    /// ```rust
+    /// use maudit::route::prelude::*;
+    /// use maudit::content::markdown_entry;
+    /// use std::cmp::Reverse;
+    ///
+    /// #[markdown_entry]
+    /// pub struct Post {
+    ///   pub title: String,
+    ///   pub date: String,
+    ///   pub draft: bool,
+    /// }
+    ///
+    /// #[route("/")]
+    /// pub struct Index;
+    ///
+    /// impl Route for Index {
+    ///   fn render(&self, ctx: &mut PageContext) -> impl Into<RenderResult> {
+    ///     let latest_posts = ctx
+    ///       .content::<Post>("posts")
+    ///       .query()
+    ///       .filter(|post| !post.data(ctx).draft)
+    ///       .sort_by_key(|post| Reverse(post.data(ctx).date.clone()))
+    ///       .take(5)
+    ///       .entries();
+    ///
+    ///     latest_posts
+    ///       .iter()
+    ///       .map(|post| format!("<li>{}</li>", post.data(ctx).title))
+    ///       .collect::<String>()
+    ///   }
+    /// }
+    /// ```
+    pub fn query(&self) -> ContentQuery<'a, T> {
+        let mut entries: Vec<&'a Entry<T>> = self.inner.entries.values().collect();
+        // Entries are stored in a hash map, start from a stable order
+        entries.sort_by(|a, b| a.id.cmp(&b.id));
+
+        let mut log = self.log.borrow_mut();
+        log.sources_queried.push(self.source_name.clone());
+        let result = log.start_query(&self.source_name, &entries);
+        drop(log);
+
+        ContentQuery {
+            source_name: self.source_name.clone(),
+            log: self.log.clone(),
+            entries,
+            result,
+        }
+    }
+
+    /// Group the entries of this source by the terms returned by `terms`, e.g. their tags.
+    /// Shorthand for `query().taxonomy(terms)`, see [`Taxonomy`].
+    pub fn taxonomy<I>(&self, terms: impl FnMut(&Entry<T>) -> I) -> Taxonomy<'a, T>
+    where
+        I: IntoIterator,
+        I::Item: Into<String>,
+    {
+        self.query().taxonomy(terms)
+    }
+
     /// Get the name of the underlying content source.
     pub fn name(&self) -> &str {
         &self.inner.name
     }
 }
 
+/// A selection of entries of a content source, obtained through [`TrackedContentSource::query`].
+///
+/// Every operation is applied immediately, so closures can borrow the page context, e.g. to call [`data()`](crate::content::EntryInner::data).
+/// Entries start sorted by ID.
+pub struct ContentQuery<'a, T> {
+    source_name: String,
+    log: Rc<RefCell<ContentAccessLog>>,
+    entries: Vec<&'a Entry<T>>,
+    /// Index of the result of this query in the access log, updated by every operation.
+    result: usize,
+}
+
+impl<'a, T> ContentQuery<'a, T> {
+    /// Keep only the entries for which `predicate` returns `true`.
+    pub fn filter(mut self, mut predicate: impl FnMut(&Entry<T>) -> bool) -> Self {
+        self.entries.retain(|entry| predicate(entry));
+        self.record_result(());
+        self
+    }
+
+    /// Sort the entries by the key returned by `key`. The sort is stable, entries with the same key keep their order.
+    ///
+    /// To sort in descending order, wrap the key in [`std::cmp::Reverse`].
+    pub fn sort_by_key<K: Ord>(mut self, mut key: impl FnMut(&Entry<T>) -> K) -> Self {
+        // Keys are computed once per entry, as they're usually read from the entry's data
+        let mut keyed: Vec<(K, &'a Entry<T>)> = self
+            .entries
+            .into_iter()
+            .map(|entry| (key(entry), entry))
+            .collect();
+        keyed.sort_by(|(a, _), (b, _)| a.cmp(b));
+        self.entries = keyed.into_iter().map(|(_, entry)| entry).collect();
+        self.record_result(());
+        self
+    }
+
+    /// Keep at most the first `n` entries.
+    pub fn take(mut self, n: usize) -> Self {
+        self.entries.truncate(n);
+        self.record_result(());
+        self
+    }
+
+    /// Split the entries into groups sharing the same key, sorted by key. Entries keep their order within each group.
+    pub fn group_by<K: Ord + Hash>(
+        mut self,
+        mut key: impl FnMut(&Entry<T>) -> K,
+    ) -> Vec<(K, ContentQuery<'a, T>)> {
+        let mut groups: BTreeMap<K, Vec<&'a Entry<T>>> = BTreeMap::new();
+        for entry in std::mem::take(&mut self.entries) {
+            groups.entry(key(entry)).or_default().push(entry);
+        }
+        let grouped: Vec<(&K, Vec<&str>)> = groups
+            .iter()
+            .map(|(key, entries)| (key, entries.iter().map(|e| e.id.as_str()).collect()))
+            .collect();
+        self.record_result(grouped);
+
+        groups
+            .into_iter()
+            .map(|(key, entries)| {
+                let result = self
+                    .log
+                    .borrow_mut()
+                    .start_query(&self.source_name, &entries);
+                let group = ContentQuery {
+                    source_name: self.source_name.clone(),
+                    log: self.log.clone(),
+                    entries,
+                    result,
+                };
+                (key, group)
+            })
+            .collect()
+    }
+
+    /// Number of entries currently selected.
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Get the selected entries. Records a dependency on each of them.
+    pub fn entries(self) -> Vec<&'a Entry<T>> {
+        self.record_entries_read();
+        self.entries
+    }
+
+    /// Convert the selected entries to pages. Like [`TrackedContentSource::into_pages`],
+    /// each page records a dependency on the entry that produced it.
+    pub fn into_pages<Params, Props>(
+        self,
+        mut cb: impl FnMut(&Entry<T>) -> Page<Params, Props>,
+    ) -> Pages<Params, Props>
+    where
+        Params: Into<PageParams>,
+    {
+        self.entries
+            .iter()
+            .map(|entry| {
+                let mut page = cb(entry);
+                page._source_entries = vec![(self.source_name.clone(), entry.id.clone())];
+                page
+            })
+            .collect()
+    }
+
+    /// Split the selected entries into pages of `per_page` entries, see [`paginate`](crate::route::paginate).
+    /// Each page records a dependency on the entries it contains.
+    pub fn paginate<Params>(
+        self,
+        per_page: usize,
+        params_fn: impl FnMut(usize) -> Params,
+    ) -> Pages<Params, PaginatedContentPage<T>>
+    where
+        Params: Into<PageParams>,
+    {
+        let mut pages = paginate(self.entries.into_iter().cloned(), per_page, params_fn);
+        for page in &mut pages {
+            page._source_entries = page
+                .props
+                .items
+                .iter()
+                .map(|entry| (self.source_name.clone(), entry.id.clone()))
+                .collect();
+        }
+        pages
+    }
+
+    /// Group the selected entries by the terms returned by `terms`, e.g. their tags. See [`Taxonomy`].
+    pub fn taxonomy<I>(self, mut terms: impl FnMut(&Entry<T>) -> I) -> Taxonomy<'a, T>
+    where
+        I: IntoIterator,
+        I::Item: Into<String>,
+    {
+        let mut entry_terms = Vec::with_capacity(self.entries.len());
+        for entry in &self.entries {
+            let terms: Vec<String> = terms(entry).into_iter().map(Into::into).collect();
+            entry_terms.push(terms);
+        }
+        self.record_result(&entry_terms);
+
+        let mut taxonomy = Taxonomy::new(self.source_name, self.log);
+        for (entry, terms) in self.entries.into_iter().zip(entry_terms) {
+            for term in terms {
+                taxonomy.insert(term, entry);
+            }
+        }
+        taxonomy
+    }
+
+    /// Record the current selection, and the keys it was grouped by, as the result of this query.
+    fn record_result(&self, keys: impl Hash) {
+        self.log.borrow_mut().query_results[self.result].hash = hash_selection(&self.entries, keys);
+    }
+
+    fn record_entries_read(&self) {
+        self.log.borrow_mut().entries_read.extend(
+            self.entries
+                .iter()
+                .map(|entry| (self.source_name.clone(), entry.id.clone())),
+        );
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -139,7 +413,10 @@ mod tests {
                 ]
             }),
         );
-        source.init();
+        tokio::runtime::Runtime::new()
+            .unwrap()
+            .block_on(source.init(false))
+            .unwrap();
         source
     }
 
@@ -181,4 +458,100 @@ mod tests {
         assert_eq!(access_log.sources_iterated.len(), 1);
         assert_eq!(access_log.sources_iterated[0], "test_source");
     }
+
+    #[test]
+    fn test_query_records_selected_entries() {
+        let source = make_test_source();
+        let log = Rc::new(RefCell::new(ContentAccessLog::new()));
+        let tracked = TrackedContentSource {
+            inner: &source,
+            source_name: "test_source".to_string(),
+            log: log.clone(),
+        };
+
+        let entries = tracked
+            .query()
+            .sort_by_key(|entry| std::cmp::Reverse(entry.id.clone()))
+            .take(1)
+            .entries();
+
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].id, "entry2");
+
+        let access_log = log.borrow();
+        assert!(access_log.sources_iterated.is_empty());
+        assert_eq!(access_log.sources_queried, vec!["test_source".to_string()]);
+        assert_eq!(
+            access_log.entries_read,
+            vec![("test_source".to_string(), "entry2".to_string())]
+        );
+    }
+
+    #[test]
+    fn test_query_filter_and_group() {
+        let source = make_test_source();
+        let log = Rc::new(RefCell::new(ContentAccessLog::new()));
+        let tracked = TrackedContentSource {
+            inner: &source,
+            source_name: "test_source".to_string(),
+            log: log.clone(),
+        };
+
+        let filtered = tracked.query().filter(|entry| entry.id != "entry1");
+        assert_eq!(filtered.len(), 1);
+
+        let groups = tracked.query().group_by(|entry| entry.id.ends_with('1'));
+        let keys: Vec<bool> = groups.iter().map(|(key, _)| *key).collect();
+        assert_eq!(keys, vec![false, true]);
+        assert_eq!(groups[1].1.len(), 1);
+
+        // Selecting entries doesn't record them as read, only getting them does
+        assert!(log.borrow().entries_read.is_empty());
+    }
+
+    #[test]
+    fn test_query_results_follow_the_selection() {
+        let source = make_test_source();
+        let run = |query: &dyn Fn(&TrackedContentSource<String>)| {
+            let log = Rc::new(RefCell::new(ContentAccessLog::new()));
+            let tracked = TrackedContentSource {
+                inner: &source,
+                source_name: "test_source".to_string(),
+                log: log.clone(),
+            };
+            query(&tracked);
+            log.take().query_results
+        };
+
+        let newest = run(&|tracked| {
+            tracked.query().take(1);
+        });
+        assert_eq!(newest.len(), 1);
+        assert_eq!(newest[0].source, "test_source");
+
+        // The same selection, however it was made, gives the same result
+        let filtered = run(&|tracked| {
+            tracked.query().filter(|entry| entry.id != "entry2");
+        });
+        assert_eq!(filtered, newest);
+
+        let reversed = run(&|tracked| {
+            tracked
+                .query()
+                .sort_by_key(|entry| std::cmp::Reverse(entry.id.clone()))
+                .take(1);
+        });
+        assert_ne!(reversed, newest);
+
+        // Grouping records the groups, then the result of each group
+        let grouped = run(&|tracked| {
+            tracked.query().group_by(|entry| entry.id.ends_with('1'));
+        });
+        assert_eq!(grouped.len(), 3);
+        let merged = run(&|tracked| {
+            tracked.query().group_by(|_| true);
+        });
+        assert_eq!(merged.len(), 2);
+        assert_ne!(merged[0], grouped[0]);
+    }
 }
@@ -31,6 +31,20 @@ pub enum BuildError {
         "`{route}` returns `RenderResult::Raw`, but includes styles or scripts, which can only be included in HTML. If you meant to return HTML, use `RenderResult::Text` instead. Alternatively, if you meant to add a reference to a script or style without including it directly, use the  `add_script` or `add_style` methods instead."
     )]
     InvalidRenderResult { route: String },
+    #[error("Failed to render `{url}`: {message}")]
+    PageRenderFailed { url: String, message: String },
+    #[error("`{url}` contains links to files that aren't the source of any page: {}", .links.join(", "))]
+    BrokenLinks { url: String, links: Vec<String> },
+    #[error("Failed to expand shortcodes in `{url}`:\n{}", .errors.iter().map(|e| e.to_string()).collect::<Vec<_>>().join("\n"))]
+    ShortcodesFailed {
+        url: String,
+        errors: Vec<ShortcodeError>,
+    },
+    #[error("Failed to highlight code in `{url}`:\n{}", .errors.iter().map(|e| e.to_string()).collect::<Vec<_>>().join("\n"))]
+    HighlightFailed {
+        url: String,
+        errors: Vec<HighlightError>,
+    },
 }
 
 #[derive(Error)]
@@ -68,6 +82,141 @@ pub enum AssetError {
     },
 }
 
+#[derive(Error)]
+pub enum ContentError {
+    #[error("{}", .0.describe("frontmatter"))]
+    InvalidFrontmatter(Box<InvalidContent>),
+    #[error("{}", .0.describe("data"))]
+    InvalidData(Box<InvalidContent>),
+    #[error("{}invalid reference: content source `{source_name}` has no entry `{id}`", .path.as_ref().map(|path| format!("{}: ", path.display())).unwrap_or_default())]
+    DanglingReference {
+        /// Path of the file the reference was read from, if known.
+        path: Option<PathBuf>,
+        /// Name of the referenced content source.
+        source_name: String,
+        id: String,
+    },
+    #[error("Invalid glob pattern `{pattern}`: {source}")]
+    InvalidPattern {
+        pattern: String,
+        #[source]
+        source: glob::PatternError,
+    },
+    #[error("Failed to read {}: {source}", .path.display())]
+    ReadFailed {
+        path: PathBuf,
+        #[source]
+        source: std::io::Error,
+    },
+    #[error("{}: entry `{id}` has the same ID as {}", .path.display(), .other_path.display())]
+    DuplicateEntry {
+        id: String,
+        path: PathBuf,
+        /// Path of the file the first entry with this ID was read from.
+        other_path: PathBuf,
+    },
+    #[error("Failed to load content source `{name}`:\n{error}")]
+    SourceFailed {
+        name: String,
+        error: Box<ContentError>,
+    },
+    #[error(transparent)]
+    Highlight(#[from] HighlightError),
+    /// Every error found while loading content, so that they can all be fixed at once.
+    #[error("{}", .0.iter().map(|e| e.to_string()).collect::<Vec<_>>().join("\n"))]
+    Multiple(Vec<ContentError>),
+}
+
+#[derive(Error)]
+pub enum HighlightError {
+    #[error(
+        "Highlight theme `{0}` is neither a built-in theme, one of the loaded themes, nor a path to a `.tmTheme` file"
+    )]
+    ThemeNotFound(String),
+    #[error("Failed to load highlight theme {path}: {message}")]
+    ThemeLoadFailed { path: PathBuf, message: String },
+    #[error("Failed to load syntax definitions from {path}: {message}")]
+    SyntaxLoadFailed { path: PathBuf, message: String },
+    #[error("Failed to highlight code: {0}")]
+    Highlight(#[from] syntect::Error),
+    #[error("Failed to write highlight stylesheet: {0}")]
+    StylesheetWriteFailed(#[from] std::io::Error),
+}
+
+impl ContentError {
+    /// Combine errors into a single one, or return `Ok` if there are none.
+    pub(crate) fn aggregate(mut errors: Vec<ContentError>) -> Result<(), ContentError> {
+        match errors.len() {
+            0 => Ok(()),
+            1 => Err(errors.remove(0)),
+            _ => Err(ContentError::Multiple(errors)),
+        }
+    }
+}
+
+/// Details of a frontmatter or data file that couldn't be deserialized.
+#[derive(Debug, Clone)]
+pub struct InvalidContent {
+    /// Path of the file, if the content was loaded from a file.
+    pub path: Option<PathBuf>,
+    /// Line of the error in the file (1-based).
+    pub line: Option<usize>,
+    /// Column of the error in the file (1-based).
+    pub column: Option<usize>,
+    /// Path of the invalid field, e.g. `author.name` or `[2].name`.
+    pub field: Option<String>,
+    /// Type the field was expected to have, e.g. `a string`.
+    pub expected: Option<String>,
+    pub message: String,
+}
+
+impl InvalidContent {
+    fn describe(&self, kind: &str) -> String {
+        let location = match (&self.path, self.line, self.column) {
+            (Some(path), Some(line), Some(column)) => {
+                format!("{}:{}:{}: ", path.display(), line, column)
+            }
+            (Some(path), Some(line), None) => format!("{}:{}: ", path.display(), line),
+            (Some(path), None, _) => format!("{}: ", path.display()),
+            (None, Some(line), Some(column)) => format!("{}:{}: ", line, column),
+            _ => String::new(),
+        };
+
+        match &self.field {
+            Some(field) => format!("{location}invalid {kind} field `{field}`: {}", self.message),
+            None => format!("{location}invalid {kind}: {}", self.message),
+        }
+    }
+}
+
+/// A shortcode that couldn't be parsed or expanded, or whose function returned an error.
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+#[error("{}{line}:{column}: {message}", .path.as_ref().map(|path| format!("{}:", path.display())).unwrap_or_default())]
+pub struct ShortcodeError {
+    /// Path of the Markdown file, if the content was loaded from a file.
+    pub path: Option<PathBuf>,
+    /// Line of the shortcode in the file (1-based).
+    pub line: usize,
+    /// Column of the shortcode in the file (1-based).
+    pub column: usize,
+    pub message: String,
+}
+
+/// Shortcodes that failed to expand and code blocks that failed to highlight while rendering Markdown,
+/// see [`try_render_markdown`](crate::content::try_render_markdown).
+#[derive(Error, Default)]
+#[error("{}", .shortcodes.iter().map(|e| e.to_string()).chain(.highlight.iter().map(|e| e.to_string())).collect::<Vec<_>>().join("\n"))]
+pub struct MarkdownError {
+    pub shortcodes: Vec<ShortcodeError>,
+    pub highlight: Vec<HighlightError>,
+}
+
+impl MarkdownError {
+    pub(crate) fn is_empty(&self) -> bool {
+        self.shortcodes.is_empty() && self.highlight.is_empty()
+    }
+}
+
 #[derive(Error, Debug)]
 pub enum MauditError {
     #[error(transparent)]
@@ -79,8 +228,18 @@ pub enum MauditError {
     #[error(transparent)]
     Build(#[from] BuildError),
 
+    #[error(transparent)]
+    Content(#[from] ContentError),
+
     #[error(transparent)]
     Io(#[from] std::io::Error),
 }
 
-impl_debug_for_error!(UrlError, BuildError, AssetError);
+impl_debug_for_error!(
+    UrlError,
+    BuildError,
+    AssetError,
+    ContentError,
+    HighlightError,
+    MarkdownError
+);
@@ -140,6 +140,27 @@ macro_rules! routes {
 /// }
 /// ```
 ///
+/// ## Async loaders
+/// Sources whose entries are loaded by async code, for instance from an API, can be written as `async` blocks. All content sources are initialized concurrently.
+/// ```rust
+/// use maudit::content_sources;
+/// use maudit::content::{ContentEntry, Entry};
+///
+/// async fn fetch_products() -> Vec<String> {
+///     vec!["chair".into(), "table".into()]
+/// }
+///
+/// content_sources![
+///     "products" => async {
+///         fetch_products()
+///             .await
+///             .into_iter()
+///             .map(|name| Entry::create(name.clone(), None, None, name, vec![]))
+///             .collect::<Vec<_>>()
+///     }
+/// ];
+/// ```
+///
 /// ## Expand
 /// ```rust
 /// # use maudit::{content_sources};
@@ -168,8 +189,17 @@ macro_rules! routes {
 /// ]);
 #[macro_export]
 macro_rules! content_sources {
-    ($($name:expr => $entries:expr),*) => {
-        maudit::content::ContentSources::new(vec![$(Box::new(maudit::content::ContentSource::new($name, Box::new(move || $entries)))),*])
+    (@sources [$($sources:expr),*]) => {
+        maudit::content::ContentSources::new(vec![$($sources),*])
+    };
+    (@sources [$($sources:expr),*] $name:expr => async $entries:block $(, $($rest:tt)*)?) => {
+        maudit::content_sources!(@sources [$($sources,)* Box::new(maudit::content::ContentSource::new_async($name, move || async move $entries))] $($($rest)*)?)
+    };
+    (@sources [$($sources:expr),*] $name:expr => $entries:expr $(, $($rest:tt)*)?) => {
+        maudit::content_sources!(@sources [$($sources,)* Box::new(maudit::content::ContentSource::new($name, Box::new(move || $entries)))] $($($rest)*)?)
+    };
+    ($($sources:tt)*) => {
+        maudit::content_sources!(@sources [] $($sources)*)
     };
 }
 /// The version of Maudit being used.
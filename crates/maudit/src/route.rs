@@ -90,8 +90,9 @@ where
 {
     pub params: Params,
     pub props: Props,
+    /// Entries this page was generated from, recorded as dependencies of the page.
     #[doc(hidden)]
-    pub _source_entry: Option<(String, String)>,
+    pub _source_entries: Vec<(String, String)>,
 }
 
 impl<Params, Props> Page<Params, Props>
@@ -102,7 +103,7 @@ where
         Self {
             params,
             props,
-            _source_entry: None,
+            _source_entries: Vec::new(),
         }
     }
 }
@@ -115,7 +116,7 @@ where
         Self {
             params,
             props: (),
-            _source_entry: None,
+            _source_entries: Vec::new(),
         }
     }
 }
@@ -319,6 +320,16 @@ pub struct PageContext<'a> {
     pub variant: Option<String>,
     pub(crate) access_log:
         std::rc::Rc<std::cell::RefCell<crate::content::tracked::ContentAccessLog>>,
+    /// URLs of the pages of entries, to resolve links between them. Only available during builds.
+    pub(crate) entry_urls: Option<&'a crate::content::links::EntryUrls>,
+    /// Links to files that aren't the source of any page, reported once the page is rendered.
+    pub(crate) broken_links: Vec<crate::content::links::BrokenLink>,
+    /// Shortcodes that failed to expand, reported once the page is rendered.
+    pub(crate) shortcode_errors: Vec<crate::errors::ShortcodeError>,
+    /// Code blocks that failed to highlight, reported once the page is rendered.
+    pub(crate) highlight_errors: Vec<crate::errors::HighlightError>,
+    /// HTML of Markdown entries rendered in previous builds. Only available during incremental builds.
+    pub(crate) markdown_cache: Option<&'a crate::build::cache::MarkdownRenderCache>,
 }
 
 impl<'a> PageContext<'a> {
@@ -340,6 +351,11 @@ impl<'a> PageContext<'a> {
             access_log: std::rc::Rc::new(std::cell::RefCell::new(
                 crate::content::tracked::ContentAccessLog::new(),
             )),
+            entry_urls: None,
+            broken_links: Vec::new(),
+            shortcode_errors: Vec::new(),
+            highlight_errors: Vec::new(),
+            markdown_cache: None,
         }
     }
 
@@ -362,6 +378,11 @@ impl<'a> PageContext<'a> {
             access_log: std::rc::Rc::new(std::cell::RefCell::new(
                 crate::content::tracked::ContentAccessLog::new(),
             )),
+            entry_urls: None,
+            broken_links: Vec::new(),
+            shortcode_errors: Vec::new(),
+            highlight_errors: Vec::new(),
+            markdown_cache: None,
         }
     }
 
@@ -995,7 +1016,7 @@ pub type PagesResult = (
     PageParams,
     PageProps,
     PageTypedParams,
-    Option<(String, String)>,
+    Vec<(String, String)>,
 );
 pub type PagesResults = Vec<PagesResult>;
 
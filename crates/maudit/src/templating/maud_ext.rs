@@ -3,6 +3,7 @@ use maud::{Markup, PreEscaped, Render, html};
 use crate::{
     GENERATOR,
     assets::{Asset, RenderedImage, Script, Style},
+    content::{TableOfContents, TocEntry},
     route::RenderResult,
 };
 
@@ -28,6 +29,37 @@ impl Render for RenderedImage {
     }
 }
 
+impl Render for TableOfContents {
+    fn render(&self) -> Markup {
+        html! {
+            @if !self.is_empty() {
+                ul {
+                    @for entry in &self.entries {
+                        (entry)
+                    }
+                }
+            }
+        }
+    }
+}
+
+impl Render for TocEntry {
+    fn render(&self) -> Markup {
+        html! {
+            li {
+                a href=(format!("#{}", self.id)) { (self.title) }
+                @if !self.children.is_empty() {
+                    ul {
+                        @for child in &self.children {
+                            (child)
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
 /// Can be used to create a generator tag in the output HTML. See [`GENERATOR`](crate::GENERATOR).
 pub fn generator() -> Markup {
     html! {
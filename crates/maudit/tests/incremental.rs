@@ -1,9 +1,14 @@
 use std::fs;
 use std::path::{Path, PathBuf};
 use std::sync::Mutex;
+use std::sync::atomic::{AtomicUsize, Ordering};
 
 use maudit::content::markdown_entry;
-use maudit::content::{ContentSource, ContentSources, glob_markdown};
+use maudit::content::shortcodes::MarkdownShortcodes;
+use maudit::content::{
+    ContentSource, ContentSources, MarkdownOptions, Reference, Referenceable, TaxonomyPage,
+    glob_markdown, glob_markdown_with_options,
+};
 use maudit::route::prelude::*;
 use maudit::{BuildOptions, coronate};
 use serial_test::serial;
@@ -71,6 +76,32 @@ impl Route<ArticleParams> for ArticlePage {
     }
 }
 
+impl Referenceable for ArticleContent {
+    const SOURCE: &'static str = "articles";
+}
+
+#[markdown_entry]
+#[derive(Debug, Clone)]
+pub struct ReviewContent {
+    pub title: String,
+    pub article: Reference<ArticleContent>,
+}
+
+#[route("/reviewed")]
+pub struct ReviewedPage;
+
+impl Route for ReviewedPage {
+    fn render(&self, ctx: &mut PageContext) -> impl Into<RenderResult> {
+        let review = ctx.content::<ReviewContent>("reviews").get_entry("review");
+        let article = review.data(ctx).article.get(ctx);
+        format!(
+            "<html><body><h1>{}</h1><p>Review of {}</p></body></html>",
+            review.data(ctx).title,
+            article.data(ctx).title
+        )
+    }
+}
+
 #[markdown_entry]
 #[derive(Debug, Clone)]
 pub struct ProjectContent {
@@ -162,6 +193,54 @@ impl Route for FeaturedArticlePage {
     }
 }
 
+#[route("/latest")]
+pub struct LatestArticlePage;
+
+impl Route for LatestArticlePage {
+    fn render(&self, ctx: &mut PageContext) -> impl Into<RenderResult> {
+        let latest = ctx
+            .content::<ArticleContent>("articles")
+            .query()
+            .sort_by_key(|entry| entry.data(ctx).title.clone())
+            .take(1)
+            .entries();
+
+        let data = latest[0].data(ctx);
+        format!("<html><body><h1>Latest: {}</h1></body></html>", data.title)
+    }
+}
+
+#[route("/descriptions/[description]")]
+pub struct DescriptionPage;
+
+#[derive(Params, Clone)]
+pub struct DescriptionParams {
+    pub description: String,
+}
+
+impl Route<DescriptionParams, TaxonomyPage<ArticleContent>> for DescriptionPage {
+    fn pages(
+        &self,
+        ctx: &mut DynamicRouteContext,
+    ) -> Pages<DescriptionParams, TaxonomyPage<ArticleContent>> {
+        ctx.content::<ArticleContent>("articles")
+            .taxonomy(|entry| [entry.data(ctx).description.clone()])
+            .term_pages(10, |term, _| DescriptionParams {
+                description: term.slug.clone(),
+            })
+    }
+
+    fn render(&self, ctx: &mut PageContext) -> impl Into<RenderResult> {
+        let props = ctx.props::<TaxonomyPage<ArticleContent>>();
+        let mut html = format!("<html><body><h1>{}</h1><ul>", props.term.name);
+        for entry in &props.pagination.items {
+            html.push_str(&format!("<li>{}</li>", entry.render(ctx)));
+        }
+        html.push_str("</ul></body></html>");
+        html
+    }
+}
+
 static SAFE_ENTRY_ID: Mutex<Option<String>> = Mutex::new(None);
 
 #[route("/safe-lookup")]
@@ -325,6 +404,14 @@ fn routes_with_featured() -> &'static [&'static dyn FullRoute] {
     &[&IndexPage, &AboutPage, &ArticlePage, &FeaturedArticlePage]
 }
 
+fn routes_with_latest() -> &'static [&'static dyn FullRoute] {
+    &[&IndexPage, &AboutPage, &ArticlePage, &LatestArticlePage]
+}
+
+fn routes_with_descriptions() -> &'static [&'static dyn FullRoute] {
+    &[&IndexPage, &AboutPage, &ArticlePage, &DescriptionPage]
+}
+
 fn routes_with_safe_lookup() -> &'static [&'static dyn FullRoute] {
     &[&IndexPage, &AboutPage, &ArticlePage, &SafeLookupPage]
 }
@@ -888,6 +975,60 @@ fn test_second_article_cached_when_first_changes() {
     );
 }
 
+#[test]
+fn test_page_order_is_stable_across_parallel_builds() {
+    let tmp = tempfile::tempdir().unwrap();
+    let content_dir = tmp.path().join("content");
+    fs::create_dir_all(content_dir.join("articles")).unwrap();
+
+    for i in 0..24 {
+        write_markdown(
+            &content_dir.join("articles"),
+            &format!("article-{}.md", i),
+            &format!("Article {}", i),
+            "An article",
+            "Body",
+        );
+    }
+
+    let page_files = |output: &maudit::BuildOutput| -> Vec<String> {
+        output.pages.iter().map(|p| p.file_path.clone()).collect()
+    };
+
+    // First build: every page is rendered in parallel
+    let first = coronate(
+        routes(),
+        make_content_sources(&content_dir),
+        build_options(tmp.path()),
+    )
+    .unwrap();
+
+    assert_eq!(first.pages.len(), 26);
+    // Pages are reported in route order, regardless of which finished rendering first
+    assert_eq!(first.pages[0].route, "/");
+    assert_eq!(first.pages[1].route, "/about");
+
+    // Second build: a mix of cached and rendered pages
+    write_markdown(
+        &content_dir.join("articles"),
+        "article-7.md",
+        "Article 7 Updated",
+        "An article",
+        "Body",
+    );
+
+    let second = coronate(
+        routes(),
+        make_content_sources(&content_dir),
+        build_options(tmp.path()),
+    )
+    .unwrap();
+
+    assert!(second.pages.iter().any(|p| p.cached));
+    assert!(second.pages.iter().any(|p| !p.cached));
+    assert_eq!(page_files(&first), page_files(&second));
+}
+
 #[test]
 fn test_three_builds_progressive_caching() {
     let tmp = tempfile::tempdir().unwrap();
@@ -1329,6 +1470,153 @@ fn test_get_entry_safe_tracks_dependencies() {
     );
 }
 
+fn make_review_content_sources(content_dir: &Path) -> ContentSources {
+    let articles_pattern = content_dir
+        .join("articles/*.md")
+        .to_string_lossy()
+        .to_string();
+    let reviews_pattern = content_dir
+        .join("reviews/*.md")
+        .to_string_lossy()
+        .to_string();
+    ContentSources::new(vec![
+        Box::new(ContentSource::new(
+            "articles",
+            Box::new(move || glob_markdown::<ArticleContent>(&articles_pattern)),
+        )),
+        Box::new(ContentSource::new(
+            "reviews",
+            Box::new(move || glob_markdown::<ReviewContent>(&reviews_pattern)),
+        )),
+    ])
+}
+
+fn write_review(content_dir: &Path, article: &str) {
+    fs::write(
+        content_dir.join("reviews/review.md"),
+        format!("---\ntitle: \"A review\"\narticle: {}\n---\n", article),
+    )
+    .unwrap();
+}
+
+#[test]
+fn test_reference_tracks_referenced_entry() {
+    let tmp = tempfile::tempdir().unwrap();
+    let content_dir = tmp.path().join("content");
+    fs::create_dir_all(content_dir.join("articles")).unwrap();
+    fs::create_dir_all(content_dir.join("reviews")).unwrap();
+
+    write_markdown(
+        &content_dir.join("articles"),
+        "first.md",
+        "First Post",
+        "The first post",
+        "Hello world",
+    );
+    write_markdown(
+        &content_dir.join("articles"),
+        "second.md",
+        "Second Post",
+        "The second post",
+        "Goodbye world",
+    );
+    write_review(&content_dir, "first");
+
+    let routes: &[&dyn FullRoute] = &[&ArticlePage, &ReviewedPage];
+
+    // Build 1: full build
+    let _ = coronate(
+        routes,
+        make_review_content_sources(&content_dir),
+        build_options(tmp.path()),
+    )
+    .unwrap();
+    let html = fs::read_to_string(tmp.path().join("dist/reviewed/index.html")).unwrap();
+    assert!(html.contains("Review of First Post"));
+
+    // Change the article that isn't referenced by the review
+    write_markdown(
+        &content_dir.join("articles"),
+        "second.md",
+        "Second Post Updated",
+        "Updated",
+        "Updated body",
+    );
+
+    // Build 2
+    let output = coronate(
+        routes,
+        make_review_content_sources(&content_dir),
+        build_options(tmp.path()),
+    )
+    .unwrap();
+
+    let cached = cached_routes(&output);
+    assert!(
+        cached.contains(&"/reviewed".to_string()),
+        "review should be cached when an unreferenced article changed, cached={:?}",
+        cached
+    );
+
+    // Change the referenced article
+    write_markdown(
+        &content_dir.join("articles"),
+        "first.md",
+        "First Post Updated",
+        "Updated first",
+        "Updated first body",
+    );
+
+    // Build 3
+    let output = coronate(
+        routes,
+        make_review_content_sources(&content_dir),
+        build_options(tmp.path()),
+    )
+    .unwrap();
+
+    let rendered = rendered_routes(&output);
+    assert!(
+        rendered.contains(&"/reviewed".to_string()),
+        "review should be re-rendered when the referenced article changed, rendered={:?}",
+        rendered
+    );
+    let html = fs::read_to_string(tmp.path().join("dist/reviewed/index.html")).unwrap();
+    assert!(html.contains("Review of First Post Updated"));
+}
+
+#[test]
+fn test_dangling_reference_fails_the_build() {
+    let tmp = tempfile::tempdir().unwrap();
+    let content_dir = tmp.path().join("content");
+    fs::create_dir_all(content_dir.join("articles")).unwrap();
+    fs::create_dir_all(content_dir.join("reviews")).unwrap();
+
+    write_markdown(
+        &content_dir.join("articles"),
+        "first.md",
+        "First Post",
+        "The first post",
+        "Hello world",
+    );
+    write_review(&content_dir, "missing");
+
+    let routes: &[&dyn FullRoute] = &[&ArticlePage, &ReviewedPage];
+    let error = coronate(
+        routes,
+        make_review_content_sources(&content_dir),
+        build_options(tmp.path()),
+    )
+    .unwrap_err()
+    .to_string();
+
+    assert!(error.contains("review.md"), "error: {error}");
+    assert!(
+        error.contains("content source `articles` has no entry `missing`"),
+        "error: {error}"
+    );
+}
+
 #[test]
 fn test_source_emptied_completely() {
     let tmp = tempfile::tempdir().unwrap();
@@ -2805,3 +3093,585 @@ fn test_style_dropped_from_page_clears_url_from_html() {
         html_without_style
     );
 }
+
+#[test]
+fn test_query_only_depends_on_data_of_other_entries() {
+    let tmp = tempfile::tempdir().unwrap();
+    let content_dir = tmp.path().join("content");
+    fs::create_dir_all(content_dir.join("articles")).unwrap();
+
+    write_markdown(
+        &content_dir.join("articles"),
+        "first.md",
+        "A First Post",
+        "The first post",
+        "Hello world",
+    );
+    write_markdown(
+        &content_dir.join("articles"),
+        "second.md",
+        "B Second Post",
+        "The second post",
+        "Goodbye world\n\n<!-- more -->\n\nFarewell friends",
+    );
+
+    // Build 1: full build
+    let _ = coronate(
+        routes_with_latest(),
+        make_content_sources(&content_dir),
+        build_options(tmp.path()),
+    )
+    .unwrap();
+
+    // Change only the body of the second article after its excerpt, keeping its summary, which /latest doesn't show
+    write_markdown(
+        &content_dir.join("articles"),
+        "second.md",
+        "B Second Post",
+        "The second post",
+        "Goodbye world\n\n<!-- more -->\n\nUpdated body",
+    );
+
+    // Build 2
+    let output = coronate(
+        routes_with_latest(),
+        make_content_sources(&content_dir),
+        build_options(tmp.path()),
+    )
+    .unwrap();
+
+    let cached = cached_routes(&output);
+    assert!(
+        cached.contains(&"/latest".to_string()),
+        "latest should be cached when only the body of another article changed, cached={:?}",
+        cached
+    );
+
+    // Change the title of the second article, which is the sort key
+    write_markdown(
+        &content_dir.join("articles"),
+        "second.md",
+        "0 Second Post",
+        "The second post",
+        "Goodbye world\n\n<!-- more -->\n\nUpdated body",
+    );
+
+    // Build 3
+    let output = coronate(
+        routes_with_latest(),
+        make_content_sources(&content_dir),
+        build_options(tmp.path()),
+    )
+    .unwrap();
+
+    let rendered = rendered_routes(&output);
+    assert!(
+        rendered.contains(&"/latest".to_string()),
+        "latest should be re-rendered when the sort key of an article changed, rendered={:?}",
+        rendered
+    );
+    let html = fs::read_to_string(tmp.path().join("dist/latest/index.html")).unwrap();
+    assert!(html.contains("Latest: 0 Second Post"));
+}
+
+#[test]
+fn test_taxonomy_term_pages_track_their_entries() {
+    let tmp = tempfile::tempdir().unwrap();
+    let content_dir = tmp.path().join("content");
+    fs::create_dir_all(content_dir.join("articles")).unwrap();
+
+    write_markdown(
+        &content_dir.join("articles"),
+        "first.md",
+        "First Post",
+        "News",
+        "Hello world",
+    );
+    write_markdown(
+        &content_dir.join("articles"),
+        "second.md",
+        "Second Post",
+        "Blog",
+        "Goodbye world\n\n<!-- more -->\n\nFarewell friends",
+    );
+
+    // Build 1: full build
+    let _ = coronate(
+        routes_with_descriptions(),
+        make_content_sources(&content_dir),
+        build_options(tmp.path()),
+    )
+    .unwrap();
+
+    // Change the body of the second article after its excerpt, it is only listed on the "blog" page
+    write_markdown(
+        &content_dir.join("articles"),
+        "second.md",
+        "Second Post",
+        "Blog",
+        "Goodbye world\n\n<!-- more -->\n\nUpdated body",
+    );
+
+    // Build 2
+    let output = coronate(
+        routes_with_descriptions(),
+        make_content_sources(&content_dir),
+        build_options(tmp.path()),
+    )
+    .unwrap();
+
+    let term_page_cached = |term: &str| {
+        output.pages.iter().any(|p| {
+            p.cached
+                && p.params
+                    .as_ref()
+                    .and_then(|params| params.get("description"))
+                    .and_then(|v| v.as_deref())
+                    == Some(term)
+        })
+    };
+    assert!(
+        term_page_cached("news"),
+        "news page should be cached when only an article of another term changed"
+    );
+    assert!(
+        !term_page_cached("blog"),
+        "blog page should be re-rendered when one of its articles changed"
+    );
+
+    let html = fs::read_to_string(tmp.path().join("dist/descriptions/blog/index.html")).unwrap();
+    assert!(html.contains("Updated body"));
+}
+
+#[test]
+fn test_pages_queries_only_depend_on_what_they_select() {
+    let tmp = tempfile::tempdir().unwrap();
+    let content_dir = tmp.path().join("content");
+    fs::create_dir_all(content_dir.join("articles")).unwrap();
+
+    write_markdown(
+        &content_dir.join("articles"),
+        "first.md",
+        "First Post",
+        "News",
+        "Hello world",
+    );
+    write_markdown(
+        &content_dir.join("articles"),
+        "second.md",
+        "Second Post",
+        "Blog",
+        "Goodbye world",
+    );
+
+    // Build 1: full build
+    let _ = coronate(
+        routes_with_descriptions(),
+        make_content_sources(&content_dir),
+        build_options(tmp.path()),
+    )
+    .unwrap();
+
+    // Change the title of the second article, its data changes but not the terms of the taxonomy
+    write_markdown(
+        &content_dir.join("articles"),
+        "second.md",
+        "Second Post, Edited",
+        "Blog",
+        "Goodbye world",
+    );
+
+    // Build 2
+    let output = coronate(
+        routes_with_descriptions(),
+        make_content_sources(&content_dir),
+        build_options(tmp.path()),
+    )
+    .unwrap();
+
+    let term_page_cached = |output: &maudit::BuildOutput, term: &str| {
+        output.pages.iter().any(|p| {
+            p.cached
+                && p.params
+                    .as_ref()
+                    .and_then(|params| params.get("description"))
+                    .and_then(|v| v.as_deref())
+                    == Some(term)
+        })
+    };
+    assert!(
+        term_page_cached(&output, "news"),
+        "news page should be cached when the data of an article of another term changed"
+    );
+    assert!(
+        !term_page_cached(&output, "blog"),
+        "blog page should be re-rendered when one of its articles changed"
+    );
+
+    // Move the second article to the news term, which changes what the taxonomy selects
+    write_markdown(
+        &content_dir.join("articles"),
+        "second.md",
+        "Second Post, Edited",
+        "News",
+        "Goodbye world",
+    );
+
+    // Build 3
+    let output = coronate(
+        routes_with_descriptions(),
+        make_content_sources(&content_dir),
+        build_options(tmp.path()),
+    )
+    .unwrap();
+
+    assert!(
+        !term_page_cached(&output, "news"),
+        "news page should be re-rendered when an article was added to it"
+    );
+    let html = fs::read_to_string(tmp.path().join("dist/descriptions/news/index.html")).unwrap();
+    assert!(html.contains("Goodbye world"));
+}
+
+#[route("/rendered/[article]")]
+pub struct RenderedArticlePage;
+
+impl Route<ArticleParams> for RenderedArticlePage {
+    fn pages(&self, ctx: &mut DynamicRouteContext) -> Pages<ArticleParams> {
+        let articles = ctx.content::<ArticleContent>("articles");
+        articles.into_pages(|entry| {
+            Page::from_params(ArticleParams {
+                article: entry.id.clone(),
+            })
+        })
+    }
+
+    fn render(&self, ctx: &mut PageContext) -> impl Into<RenderResult> {
+        let params = ctx.params::<ArticleParams>();
+        let article = ctx
+            .content::<ArticleContent>("articles")
+            .get_entry(&params.article);
+        format!("<html><body>{}</body></html>", article.render(ctx))
+    }
+}
+
+#[test]
+fn test_markdown_links_point_to_entry_pages() {
+    let tmp = tempfile::tempdir().unwrap();
+    let content_dir = tmp.path().join("content");
+    fs::create_dir_all(content_dir.join("articles")).unwrap();
+
+    write_markdown(
+        &content_dir.join("articles"),
+        "first.md",
+        "First Post",
+        "The first post",
+        "Read [the second post](./second.md#intro) and [the docs](https://example.com/docs.md).",
+    );
+    write_markdown(
+        &content_dir.join("articles"),
+        "second.md",
+        "Second Post",
+        "The second post",
+        "Hello world",
+    );
+
+    let routes: &[&dyn FullRoute] = &[&RenderedArticlePage];
+    let _ = coronate(
+        routes,
+        make_content_sources(&content_dir),
+        build_options(tmp.path()),
+    )
+    .unwrap();
+
+    let html = fs::read_to_string(tmp.path().join("dist/rendered/first/index.html")).unwrap();
+    assert!(
+        html.contains("<a href=\"/rendered/second/#intro\">the second post</a>"),
+        "html: {html}"
+    );
+    assert!(html.contains("<a href=\"https://example.com/docs.md\">"));
+
+    // Deleting the linked article re-renders the page linking to it, the link is then reported as broken
+    fs::remove_file(content_dir.join("articles/second.md")).unwrap();
+    let error = coronate(
+        routes,
+        make_content_sources(&content_dir),
+        BuildOptions {
+            strict_links: true,
+            ..build_options(tmp.path())
+        },
+    )
+    .unwrap_err()
+    .to_string();
+
+    assert!(error.contains("/rendered/first"), "error: {error}");
+    assert!(error.contains("`./second.md#intro` in"), "error: {error}");
+    assert!(error.contains("first.md"), "error: {error}");
+}
+
+#[route("/first-article")]
+pub struct FirstArticlePage;
+
+impl Route for FirstArticlePage {
+    fn render(&self, ctx: &mut PageContext) -> impl Into<RenderResult> {
+        let article = ctx.content::<ArticleContent>("articles").get_entry("first");
+        format!("<html><body>{}</body></html>", article.render(ctx))
+    }
+}
+
+#[derive(Params, Clone)]
+pub struct NoteParams {
+    pub note: String,
+}
+
+#[route("/notes/[note]")]
+pub struct NotePage;
+
+impl Route<NoteParams> for NotePage {
+    fn pages(&self, ctx: &mut DynamicRouteContext) -> Pages<NoteParams> {
+        let notes = ctx.content::<ArticleContent>("notes");
+        notes.into_pages(|entry| {
+            Page::from_params(NoteParams {
+                note: entry.id.clone(),
+            })
+        })
+    }
+
+    fn render(&self, ctx: &mut PageContext) -> impl Into<RenderResult> {
+        let params = ctx.params::<NoteParams>();
+        let note = ctx
+            .content::<ArticleContent>("notes")
+            .get_entry(&params.note);
+        format!("<html><body>{}</body></html>", note.render(ctx))
+    }
+}
+
+fn make_articles_and_notes_content_sources(content_dir: &Path) -> ContentSources {
+    let glob = |source: &str| {
+        Box::new(ContentSource::new(source, {
+            let pattern = content_dir
+                .join(format!("{source}/*.md"))
+                .to_string_lossy()
+                .to_string();
+            Box::new(move || glob_markdown::<ArticleContent>(&pattern))
+        }))
+    };
+    ContentSources::new(vec![glob("articles"), glob("notes")])
+}
+
+#[test]
+fn test_broken_links_are_fixed_once_their_target_is_added() {
+    let tmp = tempfile::tempdir().unwrap();
+    let content_dir = tmp.path().join("content");
+    fs::create_dir_all(content_dir.join("articles")).unwrap();
+    fs::create_dir_all(content_dir.join("notes")).unwrap();
+
+    write_markdown(
+        &content_dir.join("articles"),
+        "first.md",
+        "First Post",
+        "The first post",
+        "Read [the idea](../notes/idea.md).",
+    );
+    write_markdown(
+        &content_dir.join("notes"),
+        "other.md",
+        "Other Note",
+        "Another note",
+        "Hello world",
+    );
+
+    let routes: &[&dyn FullRoute] = &[&FirstArticlePage, &NotePage];
+    let build = || {
+        coronate(
+            routes,
+            make_articles_and_notes_content_sources(&content_dir),
+            build_options(tmp.path()),
+        )
+        .unwrap()
+    };
+
+    build();
+    let html = fs::read_to_string(tmp.path().join("dist/first-article/index.html")).unwrap();
+    assert!(
+        html.contains("<a href=\"../notes/idea.md\">"),
+        "html: {html}"
+    );
+
+    // The note is part of another content source than the article, yet the page linking to it is rendered again
+    write_markdown(
+        &content_dir.join("notes"),
+        "idea.md",
+        "Idea",
+        "An idea",
+        "Some idea",
+    );
+    let output = build();
+    assert!(
+        rendered_routes(&output).contains(&"/first-article".to_string()),
+        "rendered: {:?}",
+        rendered_routes(&output)
+    );
+
+    let html = fs::read_to_string(tmp.path().join("dist/first-article/index.html")).unwrap();
+    assert!(html.contains("<a href=\"/notes/idea/\">"), "html: {html}");
+}
+
+#[test]
+fn test_failing_shortcode_fails_the_build() {
+    let tmp = tempfile::tempdir().unwrap();
+    let content_dir = tmp.path().join("content");
+    fs::create_dir_all(content_dir.join("articles")).unwrap();
+
+    write_markdown(
+        &content_dir.join("articles"),
+        "first.md",
+        "First Post",
+        "The first post",
+        "Some text.\n\n{{ rating stars=6 /}}",
+    );
+
+    let pattern = content_dir
+        .join("articles/*.md")
+        .to_string_lossy()
+        .to_string();
+    let content_sources = ContentSources::new(vec![Box::new(ContentSource::new(
+        "articles",
+        Box::new(move || {
+            let mut shortcodes = MarkdownShortcodes::new();
+            shortcodes.register("rating", |args, _| {
+                let stars: u8 = args.get_or("stars", 0);
+                if stars > 5 {
+                    return Err(format!("{stars} stars is more than the maximum of 5"));
+                }
+                Ok("★".repeat(stars.into()))
+            });
+
+            glob_markdown_with_options::<ArticleContent>(
+                &pattern,
+                MarkdownOptions {
+                    shortcodes,
+                    ..Default::default()
+                },
+            )
+        }),
+    ))]);
+
+    let routes: &[&dyn FullRoute] = &[&RenderedArticlePage];
+    let error = coronate(routes, content_sources, build_options(tmp.path()))
+        .unwrap_err()
+        .to_string();
+
+    assert!(
+        error.contains("Failed to expand shortcodes in `/rendered/first/`"),
+        "error: {error}"
+    );
+    assert!(
+        error.contains(
+            "first.md:8:1: Shortcode 'rating' failed: 6 stars is more than the maximum of 5"
+        ),
+        "error: {error}"
+    );
+}
+
+static MARKDOWN_RENDERS: AtomicUsize = AtomicUsize::new(0);
+
+#[route("/all-articles")]
+pub struct AllArticlesPage;
+
+impl Route for AllArticlesPage {
+    fn render(&self, ctx: &mut PageContext) -> impl Into<RenderResult> {
+        let articles = ctx.content::<ArticleContent>("articles");
+        let mut entries = articles.entries().collect::<Vec<_>>();
+        entries.sort_by(|a, b| a.id.cmp(&b.id));
+
+        let mut html = String::from("<html><body>");
+        for entry in entries {
+            html.push_str(&entry.render(ctx));
+        }
+        html.push_str("</body></html>");
+        html
+    }
+}
+
+fn make_counted_content_sources(content_dir: &Path) -> ContentSources {
+    let pattern = content_dir
+        .join("articles/*.md")
+        .to_string_lossy()
+        .to_string();
+    ContentSources::new(vec![Box::new(ContentSource::new(
+        "articles",
+        Box::new(move || {
+            let mut shortcodes = MarkdownShortcodes::new();
+            // Shortcodes are also expanded without a page when summarizing entries, which isn't rendering them
+            shortcodes.register("counted", |_, route_ctx| {
+                if route_ctx.is_some() {
+                    MARKDOWN_RENDERS.fetch_add(1, Ordering::SeqCst);
+                }
+                String::new()
+            });
+
+            glob_markdown_with_options::<ArticleContent>(
+                &pattern,
+                MarkdownOptions {
+                    shortcodes,
+                    ..Default::default()
+                },
+            )
+        }),
+    ))])
+}
+
+#[test]
+fn test_unchanged_entries_reuse_their_rendered_markdown() {
+    let tmp = tempfile::tempdir().unwrap();
+    let content_dir = tmp.path().join("content");
+    fs::create_dir_all(content_dir.join("articles")).unwrap();
+
+    let body = |text: &str| format!("{text}{{{{ counted /}}}}\n\n```rust\nfn main() {{}}\n```");
+    write_markdown(
+        &content_dir.join("articles"),
+        "first.md",
+        "First Post",
+        "The first post",
+        &body("First body."),
+    );
+    write_markdown(
+        &content_dir.join("articles"),
+        "second.md",
+        "Second Post",
+        "The second post",
+        &body("Second body."),
+    );
+
+    let routes: &[&dyn FullRoute] = &[&AllArticlesPage];
+    let build = || {
+        coronate(
+            routes,
+            make_counted_content_sources(&content_dir),
+            build_options(tmp.path()),
+        )
+        .unwrap()
+    };
+
+    build();
+    assert_eq!(MARKDOWN_RENDERS.load(Ordering::SeqCst), 2);
+
+    // The page lists every article and is rendered again, but only the changed article is
+    write_markdown(
+        &content_dir.join("articles"),
+        "second.md",
+        "Second Post",
+        "The second post",
+        &body("Updated body."),
+    );
+    let output = build();
+    assert_eq!(rendered_routes(&output), vec!["/all-articles"]);
+    assert_eq!(MARKDOWN_RENDERS.load(Ordering::SeqCst), 3);
+
+    let html = fs::read_to_string(tmp.path().join("dist/all-articles/index.html")).unwrap();
+    assert!(html.contains("<p>First body.</p>"), "html: {html}");
+    assert!(html.contains("<p>Updated body.</p>"), "html: {html}");
+    assert!(!html.contains("Second body."), "html: {html}");
+    assert!(html.contains("<pre"), "html: {html}");
+}
@@ -14,7 +14,7 @@ pub fn build_website(
     options: &BuildOptions,
 ) -> Result<(), Box<dyn std::error::Error>> {
     // Initialize all the content sources
-    content_sources.init_all();
+    content_sources.init_all()?;
 
     // Options we'll be passing to RouteAssets instances.
     // This value automatically has the paths joined based on the output directory in BuildOptions for us, so we don't have to do it ourselves.